@@ -0,0 +1,129 @@
+//! Proves that a 9x9 sudoku puzzle has a solution, and verifies the proof.
+//!
+//! The puzzle is read from the first argument, or from the standard input
+//! if no argument is given, as the 81 cells of the grid, row by row.
+//! Empty cells are written as `.` or `0`, and whitespace is ignored.
+//! If no puzzle is given at all, an example one is used.
+//!
+//! ```text
+//! cargo run --release --example sudoku_cli -- 53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79
+//! ```
+//!
+//! The process exits with a nonzero code if the puzzle cannot be read,
+//! has no solution, or if proving or verifying fails.
+
+use std::{io::Read, process::ExitCode};
+
+use halo2_proofs::pasta::Fp;
+use zk_halo2_playground::{
+    utilities::{as_instance_slices, ProverWrapper, VerifierWrapper},
+    SudokuCircuit,
+};
+
+type Sudoku = SudokuCircuit<Fp, 9, 3>;
+
+/// Enough rows for the 9x9 sudoku circuit, as in the crate tests.
+const POW_OF_2_MAX_ROWS: u32 = 9;
+
+const EXAMPLE_PUZZLE: &str =
+    "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+/// Reads a puzzle, row by row, into a grid of columns,
+/// which is the layout `SudokuCircuit` works with.
+fn parse_puzzle(puzzle: &str) -> Result<[[Fp; 9]; 9], String> {
+    let cells = puzzle
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '.' | '0' => Ok(0),
+            '1'..='9' => Ok(c as u64 - '0' as u64),
+            _ => Err(format!("unexpected character {c:?} in the puzzle")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if cells.len() != 81 {
+        return Err(format!(
+            "the puzzle has {} cells, but 81 are expected",
+            cells.len()
+        ));
+    }
+    Ok(core::array::from_fn(|col_idx| {
+        core::array::from_fn(|row_idx| Fp::from(cells[9 * row_idx + col_idx]))
+    }))
+}
+
+/// Solves the puzzle, proves that it has a solution, and verifies the proof.
+/// Returns the length of the proof, in bytes.
+fn run(puzzle: &str) -> Result<usize, String> {
+    let symbols = core::array::from_fn(|idx| Fp::from(idx as u64 + 1));
+    let problem = parse_puzzle(puzzle)?;
+
+    let solution = Sudoku::solve(&problem, &symbols).ok_or("the puzzle has no solution")?;
+    let circuit = Sudoku::try_new(problem, solution, symbols)
+        .map_err(|()| "the solution does not fit the puzzle")?;
+
+    let mut prover = ProverWrapper::initialize_parameters_and_prover(
+        POW_OF_2_MAX_ROWS,
+        Sudoku::circuit_wiring_from_symbols(symbols),
+    )
+    .map_err(|err| format!("prover setup failed: {err:?}"))?;
+
+    // The problem grid is the public input, one instance column per grid column.
+    let instance = as_instance_slices(&problem);
+    prover.add_item(circuit, &instance);
+    let transcript = prover
+        .prove()
+        .map_err(|err| format!("proof generation failed: {err:?}"))?;
+
+    let mut verifier = VerifierWrapper::from(prover);
+    if !verifier.verify([instance.as_slice()], &transcript) {
+        return Err("the proof was rejected".to_owned());
+    }
+    Ok(transcript.len())
+}
+
+fn main() -> ExitCode {
+    let puzzle = match std::env::args().nth(1) {
+        Some(puzzle) => puzzle,
+        None => {
+            let mut puzzle = String::new();
+            if let Err(err) = std::io::stdin().read_to_string(&mut puzzle) {
+                eprintln!("cannot read the puzzle: {err}");
+                return ExitCode::FAILURE;
+            }
+            puzzle
+        }
+    };
+    let puzzle = if puzzle.trim().is_empty() {
+        EXAMPLE_PUZZLE
+    } else {
+        puzzle.as_str()
+    };
+
+    match run(puzzle) {
+        Ok(proof_length) => {
+            println!("The puzzle has a solution, proved in {proof_length} bytes, and verified.");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test that the example puzzle is proved and verified,
+    /// and that malformed and unsolvable puzzles are refused.
+    fn sudoku_cli_smoke() {
+        assert!(run(EXAMPLE_PUZZLE).is_ok());
+
+        assert!(run(&EXAMPLE_PUZZLE[1..]).is_err());
+        assert!(run(&EXAMPLE_PUZZLE.replace('.', "x")).is_err());
+        // Two 5s in the first row.
+        assert!(run(&EXAMPLE_PUZZLE.replacen('.', "5", 1)).is_err());
+    }
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+impl<F: ff::Field> AddChip<F> {
+    /// Loads `a` and `b` in the circuit, and upon successful execution,
+    /// returns `Ok(c)`, where `c` is a cell constrained to be equal to `a + b`.
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || prefixed_name(self.name_prefix, "addition"),
+            |mut region| {
+                let config = self.config();
+
+                // We enable the gate that constrains the sum.
+                config.s_add.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "first summand", &mut region, config.columns[0], 0)?;
+                b.copy_advice(|| "second summand", &mut region, config.columns[1], 0)?;
+
+                region
+                    .assign_advice(
+                        || "sum",
+                        config.columns[2],
+                        0,
+                        || a.value().copied() + b.value().copied(),
+                    )
+                    .map(Number)
+            },
+        )
+    }
+}
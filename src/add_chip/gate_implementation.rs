@@ -0,0 +1,51 @@
+use super::*;
+
+impl<F: ff::Field> AddChip<F> {
+    pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
+        Self {
+            config,
+            name_prefix,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `add` assigns, relative to the first row of its region, in the order
+    /// in which they are assigned. The column index is the position
+    /// of the column in `AConfig::columns`.
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        Vec::from_iter((0..3).map(|col_idx| (col_idx, 0)))
+    }
+
+    /// `columns` contain, in order, the two summands and the sum.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: [Column<Advice>; 3],
+    ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
+        for col in columns {
+            meta.enable_equality(col);
+        }
+
+        let s_add = meta.selector();
+
+        meta.create_gate("addition", |meta| {
+            let s_add = meta.query_selector(s_add);
+            let [a, b, c] = columns.map(|col| meta.query_advice(col, Rotation::cur()));
+
+            // if s_add is enabled, then `c == a + b`
+            vec![s_add * (a + b - c)]
+        });
+
+        AConfig { columns, s_add }
+    }
+}
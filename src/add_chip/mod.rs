@@ -0,0 +1,139 @@
+use halo2_proofs::{
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{utilities::prefixed_name, Number};
+
+/// in this module, we implement the functions needed to load
+/// the summands and the sum in the advice columns.
+mod chip_setup_api;
+/// in this module, we implement the gate logic.
+mod gate_implementation;
+
+#[derive(Debug, Clone)]
+pub struct AddChip<F: ff::Field> {
+    config: AConfig,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
+    _marker: std::marker::PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AConfig {
+    /// The columns containing, in order, the two summands and the sum.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    pub columns: [Column<Advice>; 3],
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_add: Selector,
+}
+
+impl<F: ff::Field> halo2_proofs::circuit::Chip<F> for AddChip<F> {
+    type Config = AConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    /// A circuit that proves that the public output is the sum of two secret numbers.
+    struct AddCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for AddCircuit {
+        type Config = (AConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: Value::unknown(),
+                b: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+
+            let columns = [(); 3].map(|_| meta.advice_column());
+            (AddChip::configure(meta, columns), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let add_chip = AddChip::<Fp>::construct(config);
+
+            let (a, b) = layouter.assign_region(
+                || "summands",
+                |mut region| {
+                    let columns = add_chip.config().columns;
+                    let a = region.assign_advice(|| "a", columns[0], 0, || self.a)?;
+                    let b = region.assign_advice(|| "b", columns[1], 0, || self.b)?;
+                    Ok((Number(a), Number(b)))
+                },
+            )?;
+
+            let sum = add_chip.add(layouter.namespace(|| "addition"), a, b)?;
+            layouter.constrain_instance(sum.cell(), instance, 0)
+        }
+    }
+
+    #[test]
+    /// Test the addition chip with the mock prover,
+    /// both with a correct and a wrong output.
+    fn mock_add() {
+        const POW_OF_2_MAX_ROWS: u32 = 4;
+
+        let circuit = AddCircuit {
+            a: Value::known(Fp::from(2)),
+            b: Value::known(Fp::from(3)),
+        };
+
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![vec![Fp::from(5)]])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![vec![Fp::from(6)]])
+            .expect("Proof generation goes wrong");
+        assert!(prover.verify().is_err(), "Bogous proof was accepted");
+    }
+
+    #[test]
+    /// Test that the addition chip occupies the three cells of a single row,
+    /// each one once.
+    fn add_chip_occupied_cells() {
+        let cells = AddChip::<Fp>::occupied_cells();
+        assert_eq!(cells, vec![(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(
+            cells
+                .iter()
+                .collect::<std::collections::BTreeSet<_>>()
+                .len(),
+            cells.len()
+        );
+    }
+}
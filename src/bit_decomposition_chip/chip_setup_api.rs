@@ -0,0 +1,50 @@
+use super::*;
+
+impl<F: ff::PrimeField, const BITS: usize> BitDecompositionChip<F, BITS> {
+    /// Loads `input` in the circuit, and upon successful execution,
+    /// returns `Ok(bits)`, where `bits` are boolean cells such that
+    /// `input == bits[0] + 2 * bits[1] + ... + 2^(BITS - 1) * bits[BITS - 1]`.
+    ///
+    /// If the value of `input` does not fit in `BITS` bits,
+    /// the constraints of the gate are not satisfied.
+    pub fn decompose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input: Number<F>,
+    ) -> Result<[Number<F>; BITS], Error> {
+        layouter.assign_region(
+            || prefixed_name(self.name_prefix, "bit decomposition"),
+            |mut region| {
+                let config = self.config();
+
+                // We enable the gate that constrains the decomposition.
+                config.s_bits.enable(&mut region, 0)?;
+
+                input.copy_advice(|| "input", &mut region, config.input_column, 0)?;
+
+                let bits = input
+                    .value()
+                    .map(|value| little_endian_bits::<F, BITS>(*value))
+                    .transpose_array();
+
+                let mut bit_cells = Vec::with_capacity(BITS);
+                for (idx, bit) in bits.into_iter().enumerate() {
+                    bit_cells.push(
+                        region
+                            .assign_advice(
+                                || format!("{idx}-th bit"),
+                                config.bits_column,
+                                idx,
+                                || bit.map(|bit| if bit { F::ONE } else { F::ZERO }),
+                            )
+                            .map(Number)?,
+                    );
+                }
+
+                Ok(bit_cells
+                    .try_into()
+                    .expect("the number of items is correct"))
+            },
+        )
+    }
+}
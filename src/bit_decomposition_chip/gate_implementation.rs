@@ -0,0 +1,97 @@
+use super::*;
+
+impl<F: ff::PrimeField, const BITS: usize> BitDecompositionChip<F, BITS> {
+    pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
+        Self {
+            config,
+            name_prefix,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `decompose` assigns, relative to the first row of its region,
+    /// in the order in which they are assigned. The column index is `0`
+    /// for `BDConfig::input_column`, and `1` for `BDConfig::bits_column`.
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        let mut cells = vec![(0, 0)];
+        cells.extend((0..BITS).map(|row_idx| (1, row_idx)));
+        cells
+    }
+
+    /// Panics if `BITS` is not valid, see `try_configure`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        input_column: Column<Advice>,
+        bits_column: Column<Advice>,
+    ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
+        Self::try_configure(meta, input_column, bits_column).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `configure`, but if `BITS` is zero, or not smaller than
+    /// the bit size of the field, an error is returned, and `meta` is left untouched.
+    pub fn try_configure(
+        meta: &mut ConstraintSystem<F>,
+        input_column: Column<Advice>,
+        bits_column: Column<Advice>,
+    ) -> Result<<Self as halo2_proofs::circuit::Chip<F>>::Config, ConfigError> {
+        if BITS == 0 {
+            return Err(ConfigError::NoBits);
+        }
+        // If the bits could represent numbers bigger than the field modulus,
+        // then some field elements would have more than one decomposition.
+        if BITS >= F::NUM_BITS as usize {
+            return Err(ConfigError::TooManyBits {
+                bits: BITS,
+                max_bits: F::NUM_BITS as usize,
+            });
+        }
+
+        meta.enable_equality(input_column);
+        meta.enable_equality(bits_column);
+
+        let s_bits = meta.selector();
+
+        meta.create_gate("bit decomposition", |meta| {
+            let s_bits = meta.query_selector(s_bits);
+            let input = meta.query_advice(input_column, Rotation::cur());
+
+            let mut constraints = vec![];
+            let mut weighted_sum = Expression::Constant(F::ZERO);
+            let mut power_of_two = F::ONE;
+
+            for idx in 0..BITS {
+                let bit = meta.query_advice(bits_column, Rotation(idx as i32));
+
+                // every bit must be a boolean value
+                constraints.push(
+                    s_bits.clone() * bit.clone() * (bit.clone() - Expression::Constant(F::ONE)),
+                );
+
+                weighted_sum = weighted_sum + bit * Expression::Constant(power_of_two);
+                power_of_two = power_of_two.double();
+            }
+
+            // the bits must add up to the input
+            constraints.push(s_bits * (input - weighted_sum));
+
+            constraints
+        });
+
+        Ok(BDConfig {
+            input_column,
+            bits_column,
+            s_bits,
+        })
+    }
+}
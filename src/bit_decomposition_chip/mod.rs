@@ -0,0 +1,180 @@
+use halo2_proofs::{
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    utilities::{prefixed_name, ConfigError},
+    Number,
+};
+
+/// in this module, we implement the functions needed to load
+/// the input and its bits in the advice columns.
+mod chip_setup_api;
+/// in this module, we implement the gate logic.
+mod gate_implementation;
+
+#[derive(Debug, Clone)]
+pub struct BitDecompositionChip<F: ff::PrimeField, const BITS: usize> {
+    config: BDConfig<BITS>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
+    _marker: std::marker::PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BDConfig<const BITS: usize> {
+    /// The column that contains a copy of the input, in the first row of the gate.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    pub input_column: Column<Advice>,
+    /// The column that contains the bits of the input, from the least
+    /// significant to the most significant, in the first `BITS` rows of the gate.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    pub bits_column: Column<Advice>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_bits: Selector,
+}
+
+impl<F: ff::PrimeField, const BITS: usize> halo2_proofs::circuit::Chip<F>
+    for BitDecompositionChip<F, BITS>
+{
+    type Config = BDConfig<BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+/// Given a field element, returns its `BITS` least significant bits,
+/// starting from the least significant one.
+///
+/// We assume that the representation of `F` elements is little endian,
+/// as it is the case for the Pasta fields.
+fn little_endian_bits<F: ff::PrimeField, const BITS: usize>(value: F) -> [bool; BITS] {
+    let repr = value.to_repr();
+    let bytes = repr.as_ref();
+    core::array::from_fn(|idx| (bytes[idx / 8] >> (idx % 8)) & 1 == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    /// A circuit that decomposes a secret number in `BITS` bits,
+    /// and exposes the bits in the instance column.
+    struct BitDecompositionCircuit<const BITS: usize> {
+        input: Value<Fp>,
+    }
+
+    impl<const BITS: usize> Circuit<Fp> for BitDecompositionCircuit<BITS> {
+        type Config = (BDConfig<BITS>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                input: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+
+            let input_column = meta.advice_column();
+            let bits_column = meta.advice_column();
+            (
+                BitDecompositionChip::<Fp, BITS>::configure(meta, input_column, bits_column),
+                instance,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = BitDecompositionChip::<Fp, BITS>::construct(config);
+
+            let input = layouter.assign_region(
+                || "input",
+                |mut region| {
+                    region
+                        .assign_advice(|| "input", chip.config().input_column, 0, || self.input)
+                        .map(Number)
+                },
+            )?;
+
+            let bits = chip.decompose(layouter.namespace(|| "bit decomposition"), input)?;
+            for (idx, bit) in bits.iter().enumerate() {
+                layouter.constrain_instance(bit.cell(), instance, idx)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    /// Test the bit decomposition chip with the mock prover
+    /// with an input that fits in the given bits, and with one that does not.
+    fn mock_bit_decomposition() {
+        const POW_OF_2_MAX_ROWS: u32 = 4;
+
+        let bits_of_13 = vec![1_u64, 0, 1, 1].into_iter().map(Fp::from).collect();
+
+        let circuit = BitDecompositionCircuit::<4> {
+            input: Value::known(Fp::from(13)),
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![bits_of_13])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+
+        // 16 does not fit in 4 bits, so every choice of the bits is rejected.
+        let circuit = BitDecompositionCircuit::<4> {
+            input: Value::known(Fp::from(16)),
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![vec![Fp::from(0); 4]])
+            .expect("Proof generation goes wrong");
+        assert!(prover.verify().is_err(), "Bogous proof was accepted");
+    }
+
+    #[test]
+    /// Test the coordinates of the cells occupied by the bit decomposition
+    /// chip for a known number of bits, and that they are distinct,
+    /// for a range of numbers of bits.
+    fn bit_decomposition_chip_occupied_cells() {
+        // The input, and the 4 bits below each other.
+        assert_eq!(
+            BitDecompositionChip::<Fp, 4>::occupied_cells(),
+            vec![(0, 0), (1, 0), (1, 1), (1, 2), (1, 3)]
+        );
+
+        fn check<const BITS: usize>() {
+            let cells = BitDecompositionChip::<Fp, BITS>::occupied_cells();
+            assert_eq!(cells.len(), 1 + BITS);
+            assert_eq!(
+                cells
+                    .iter()
+                    .collect::<std::collections::BTreeSet<_>>()
+                    .len(),
+                cells.len()
+            );
+        }
+        check::<1>();
+        check::<8>();
+        check::<64>();
+    }
+}
@@ -0,0 +1,303 @@
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    permutation_circuit::PCircuitConfig,
+    utilities::{running_accumulator_gates, FieldCounter},
+    Number, PermutationCircuit,
+};
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+/// A permutation circuit that, rather than exposing the permuted output,
+/// commits to it by exposing `Σ output[i]` and `Σ i * output[i]`,
+/// the indices starting from 0. See `commitment`.
+/// The input items are committed to in the same way, so that the proof
+/// is bound to them, and not only to the output.
+///
+/// The circuit proves that the committed output is the committed input
+/// permuted by the secret permutation.
+/// The commitments are not hiding: anyone can check whether
+/// candidate input or output items match them.
+///
+/// The instance column contains the two values of the commitment to the output,
+/// followed by the two values of the commitment to the input, see `instance`.
+#[derive(Clone, Debug)]
+pub struct CommittedPermutationCircuit<F: ff::Field, const N_OBJECTS: usize> {
+    circuit: PermutationCircuit<F, N_OBJECTS>,
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> CommittedPermutationCircuit<F, N_OBJECTS> {
+    /// Same as `PermutationCircuit::hide_output`.
+    pub fn new(circuit: PermutationCircuit<F, N_OBJECTS>) -> Self {
+        Self { circuit }
+    }
+
+    /// The sum and the sum weighted by their indices of `output_items`,
+    /// computed out of the circuit.
+    pub fn commitment(output_items: &[F; N_OBJECTS]) -> [F; 2] {
+        output_items
+            .iter()
+            .zip(FieldCounter::start_counting_from(F::ZERO))
+            .fold([F::ZERO; 2], |[sum, weighted_sum], (item, index)| {
+                [sum + item, weighted_sum + index * item]
+            })
+    }
+
+    /// The values the instance column has to contain
+    /// for the given input and output items.
+    pub fn instance(input_items: &[F; N_OBJECTS], output_items: &[F; N_OBJECTS]) -> Vec<F> {
+        [
+            Self::commitment(output_items),
+            Self::commitment(input_items),
+        ]
+        .concat()
+    }
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> Default for CommittedPermutationCircuit<F, N_OBJECTS> {
+    fn default() -> Self {
+        Self::new(PermutationCircuit::default())
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommittedPermutationConfig<const N_OBJECTS: usize> {
+    permutation_config: PCircuitConfig<N_OBJECTS>,
+    /// The columns where each committed item, the sum of the items up to it,
+    /// and their sum weighted by their indices, are laid out, in this order.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    commitment_columns: [Column<Advice>; 3],
+    /// The index of each committed item.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    index_column: Column<Fixed>,
+    /// Enabled on the first committed item.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_first: Selector,
+    /// Enabled on every committed item but the first one.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_next: Selector,
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> Circuit<F>
+    for CommittedPermutationCircuit<F, N_OBJECTS>
+{
+    type Config = CommittedPermutationConfig<N_OBJECTS>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // The instance column of the permutation circuit
+        // is used for the commitment, rather than for the output.
+        let permutation_config = PermutationCircuit::<F, N_OBJECTS>::configure(meta);
+
+        let commitment_columns = [(); 3].map(|_| meta.advice_column());
+        for col in commitment_columns {
+            meta.enable_equality(col);
+        }
+        let index_column = meta.fixed_column();
+
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        running_accumulator_gates(
+            meta,
+            ["first item commitment", "next item commitment"],
+            [s_first, s_next],
+            [commitment_columns[1], commitment_columns[2]],
+            |meta, [sum, weighted_sum]| {
+                let item = meta.query_advice(commitment_columns[0], Rotation::cur());
+                let index = meta.query_fixed(index_column);
+                [sum + item.clone(), weighted_sum + index * item]
+            },
+        );
+
+        CommittedPermutationConfig {
+            permutation_config,
+            commitment_columns,
+            index_column,
+            s_first,
+            s_next,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let instance = config.permutation_config.instance_column();
+        let (input_cells, output_cells, _) = self.circuit.assign_permutation_with_inputs(
+            config.permutation_config.clone(),
+            layouter.namespace(|| "permutation"),
+        )?;
+
+        let output_commitment = config.assign_commitment(
+            layouter.namespace(|| "output commitment"),
+            "output",
+            &output_cells,
+        )?;
+        let input_commitment = config.assign_commitment(
+            layouter.namespace(|| "input commitment"),
+            "input",
+            &input_cells,
+        )?;
+
+        for (row, cell) in output_commitment
+            .iter()
+            .chain(&input_commitment)
+            .enumerate()
+        {
+            layouter.constrain_instance(cell.cell(), instance, row)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N_OBJECTS: usize> CommittedPermutationConfig<N_OBJECTS> {
+    /// Accumulates the sum and the weighted sum of `cells`,
+    /// and returns the cells holding their final values.
+    /// `name` tells apart the items in the annotations.
+    fn assign_commitment<F: ff::Field>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &str,
+        cells: &[Number<F>; N_OBJECTS],
+    ) -> Result<[AssignedCell<F, F>; 2], Error> {
+        layouter.assign_region(
+            || format!("{name} commitment"),
+            |mut region| {
+                let [item_column, sum_column, weighted_sum_column] = self.commitment_columns;
+
+                let mut sum = Value::known(F::ZERO);
+                let mut weighted_sum = Value::known(F::ZERO);
+                let mut accumulator_cells = None;
+                for ((row, cell), index) in cells
+                    .iter()
+                    .enumerate()
+                    .zip(FieldCounter::start_counting_from(F::ZERO))
+                {
+                    if row == 0 {
+                        self.s_first.enable(&mut region, row)?;
+                    } else {
+                        self.s_next.enable(&mut region, row)?;
+                    }
+
+                    region.assign_fixed(
+                        || format!("{row}-th {name} item index"),
+                        self.index_column,
+                        row,
+                        || Value::known(index),
+                    )?;
+                    let item = cell.copy_advice(
+                        || format!("{row}-th {name} item"),
+                        &mut region,
+                        item_column,
+                        row,
+                    )?;
+                    sum = sum + item.value().copied();
+                    weighted_sum = weighted_sum + item.value().map(|x| index * x);
+
+                    accumulator_cells = Some([
+                        region.assign_advice(
+                            || format!("{row}-th partial sum of the {name} items"),
+                            sum_column,
+                            row,
+                            || sum,
+                        )?,
+                        region.assign_advice(
+                            || format!("{row}-th partial weighted sum of the {name} items"),
+                            weighted_sum_column,
+                            row,
+                            || weighted_sum,
+                        )?,
+                    ]);
+                }
+                accumulator_cells.ok_or(Error::Synthesis)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied, inverse_permutation};
+    use halo2_proofs::pasta::Fp;
+
+    const N_OBJECTS: usize = 5;
+    const K: u32 = 5;
+
+    type Committed = CommittedPermutationCircuit<Fp, N_OBJECTS>;
+
+    #[test]
+    /// Test that the circuit accepts the commitments to the input and to the
+    /// permuted output, and rejects the commitments to tampered outputs,
+    /// including the ones that only differ in the order of the items,
+    /// and to other inputs.
+    fn mock_committed_permutation() {
+        let objects: [Fp; N_OBJECTS] = core::array::from_fn(|n| Fp::from(10 * n as u64 + 1));
+
+        for permutation in [[0, 1, 2, 3, 4], [1, 2, 0, 4, 3], [4, 3, 2, 1, 0]] {
+            let circuit = PermutationCircuit::<Fp, N_OBJECTS>::new_unchecked(
+                objects.map(Value::known),
+                permutation,
+            )
+            .hide_output();
+            let output = inverse_permutation(permutation).map(|idx| objects[idx]);
+
+            // Permuting the items does not change their sum,
+            // but it changes their weighted sum.
+            assert_eq!(
+                Committed::commitment(&output)[0],
+                Committed::commitment(&objects)[0]
+            );
+            assert_satisfied(K, &circuit, vec![Committed::instance(&objects, &output)]);
+
+            let mut swapped_output = output;
+            swapped_output.swap(0, 1);
+            assert_rejected(
+                K,
+                &circuit,
+                vec![Committed::instance(&objects, &swapped_output)],
+            );
+
+            let mut altered_output = output;
+            altered_output[2] += Fp::from(1);
+            assert_rejected(
+                K,
+                &circuit,
+                vec![Committed::instance(&objects, &altered_output)],
+            );
+
+            // The proof is bound to the input: a circuit permuting other items
+            // to the same output does not match the commitment to the input.
+            let mut other_objects = objects;
+            other_objects.swap(0, 1);
+            let other_output = inverse_permutation(permutation).map(|idx| other_objects[idx]);
+            let other_circuit = PermutationCircuit::<Fp, N_OBJECTS>::new_unchecked(
+                other_objects.map(Value::known),
+                permutation,
+            )
+            .hide_output();
+            assert_rejected(
+                K,
+                &other_circuit,
+                vec![Committed::instance(&objects, &other_output)],
+            );
+            assert_satisfied(
+                K,
+                &other_circuit,
+                vec![Committed::instance(&other_objects, &other_output)],
+            );
+        }
+    }
+}
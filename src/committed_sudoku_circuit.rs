@@ -0,0 +1,277 @@
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{sudoku_circuit::SudokuConfig, utilities::running_accumulator_gates, SudokuCircuit};
+
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+/// The base of the fingerprint of a solution grid, see `CommittedSudokuCircuit`.
+pub const FINGERPRINT_BASE: u64 = 65_537;
+
+/// A sudoku circuit that, rather than keeping the solution grid
+/// completely hidden, commits to it by exposing its fingerprint.
+///
+/// The fingerprint is a Rabin-style one: the cells of the solution grid,
+/// taken column by column, are read as the digits of a number in base
+/// `FINGERPRINT_BASE`, the first cell being the most significant digit.
+/// For the symbols `1, ..., SIZE`, these digits are the ordinals of the
+/// symbols, plus one. See `fingerprint`.
+///
+/// The fingerprint is not hiding: anyone can check whether a candidate solution
+/// matches it. It only allows to tell whether two proofs are about the same solution.
+///
+/// The instance columns contain the problem grid, as in `SudokuCircuit`,
+/// followed by a column containing the fingerprint in its first row,
+/// see `instance`.
+#[derive(Clone, Debug)]
+pub struct CommittedSudokuCircuit<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize> {
+    circuit: SudokuCircuit<F, SIZE, SIZE_SQRT>,
+}
+
+impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize>
+    CommittedSudokuCircuit<F, SIZE, SIZE_SQRT>
+{
+    /// Same as `SudokuCircuit::try_new`.
+    pub fn try_new(
+        problem: [[F; SIZE]; SIZE],
+        solution: [[F; SIZE]; SIZE],
+        symbols: [F; SIZE],
+    ) -> Result<Self, ()> {
+        SudokuCircuit::try_new(problem, solution, symbols).map(|circuit| Self { circuit })
+    }
+
+    /// Same as `SudokuCircuit::circuit_wiring_from_symbols`.
+    pub fn circuit_wiring_from_symbols(symbols: [F; SIZE]) -> Self {
+        Self {
+            circuit: SudokuCircuit::circuit_wiring_from_symbols(symbols),
+        }
+    }
+
+    /// The fingerprint of `solution`, computed out of the circuit.
+    pub fn fingerprint(solution: &[[F; SIZE]; SIZE]) -> F {
+        let base = F::from(FINGERPRINT_BASE);
+        solution
+            .iter()
+            .flatten()
+            .fold(F::ZERO, |fingerprint, cell| fingerprint * base + cell)
+    }
+
+    /// The values the instance columns have to contain
+    /// for the given problem and solution grids.
+    pub fn instance(problem: &[[F; SIZE]; SIZE], solution: &[[F; SIZE]; SIZE]) -> Vec<Vec<F>> {
+        let mut instance = Vec::from_iter(problem.iter().map(|column| column.to_vec()));
+        instance.push(vec![Self::fingerprint(solution)]);
+        instance
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommittedSudokuConfig<const SIZE: usize> {
+    sudoku_config: SudokuConfig<SIZE>,
+    /// The columns where each cell of the solution, and the fingerprint
+    /// of the cells up to it, are laid out, in this order.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    fingerprint_columns: [Column<Advice>; 2],
+    /// Enabled on the first cell of the solution.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_first: Selector,
+    /// Enabled on every cell of the solution but the first one.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_next: Selector,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    fingerprint_instance: Column<Instance>,
+}
+
+impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize> Circuit<F>
+    for CommittedSudokuCircuit<F, SIZE, SIZE_SQRT>
+{
+    type Config = CommittedSudokuConfig<SIZE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: self.circuit.without_witnesses(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // The problem columns come first, so that the
+        // fingerprint column is the last instance column.
+        let sudoku_config = SudokuCircuit::<F, SIZE, SIZE_SQRT>::configure_sudoku(meta);
+
+        let fingerprint_instance = meta.instance_column();
+        meta.enable_equality(fingerprint_instance);
+
+        let fingerprint_columns = [(); 2].map(|_| meta.advice_column());
+        for col in fingerprint_columns {
+            meta.enable_equality(col);
+        }
+
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        running_accumulator_gates(
+            meta,
+            ["first cell fingerprint", "next cell fingerprint"],
+            [s_first, s_next],
+            [fingerprint_columns[1]],
+            |meta, [fingerprint]| {
+                let cell = meta.query_advice(fingerprint_columns[0], Rotation::cur());
+                [fingerprint * Expression::Constant(F::from(FINGERPRINT_BASE)) + cell]
+            },
+        );
+
+        CommittedSudokuConfig {
+            sudoku_config,
+            fingerprint_columns,
+            s_first,
+            s_next,
+            fingerprint_instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let solution_cells = self
+            .circuit
+            .synthesize_sudoku(config.sudoku_config, layouter.namespace(|| "sudoku"))?;
+
+        // We accumulate the fingerprint of the solution, and expose its final value.
+        let fingerprint = layouter
+            .namespace(|| "solution fingerprint")
+            .assign_region(
+                || "solution fingerprint",
+                |mut region| {
+                    let [cell_column, fingerprint_column] = config.fingerprint_columns;
+                    let base = Value::known(F::from(FINGERPRINT_BASE));
+
+                    let mut fingerprint = Value::known(F::ZERO);
+                    let mut fingerprint_cell = None;
+                    for (row, cell) in solution_cells.iter().flatten().enumerate() {
+                        if row == 0 {
+                            config.s_first.enable(&mut region, row)?;
+                        } else {
+                            config.s_next.enable(&mut region, row)?;
+                        }
+
+                        let cell = cell.copy_advice(
+                            || format!("{row}-th solution cell"),
+                            &mut region,
+                            cell_column,
+                            row,
+                        )?;
+                        fingerprint = fingerprint * base + cell.value().copied();
+                        fingerprint_cell = Some(region.assign_advice(
+                            || format!("fingerprint up to the {row}-th cell"),
+                            fingerprint_column,
+                            row,
+                            || fingerprint,
+                        )?);
+                    }
+                    fingerprint_cell.ok_or(Error::Synthesis)
+                },
+            )?;
+
+        layouter.constrain_instance(fingerprint.cell(), config.fingerprint_instance, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied};
+    use ff::Field;
+    use halo2_proofs::pasta::Fp;
+
+    type SudokuGrid = [[Fp; 9]; 9];
+
+    const K: u32 = 10;
+
+    /// A valid solution, another valid solution obtained by swapping
+    /// the symbols 1 and 2 in it, and the problem, obtained by clearing
+    /// the cells containing 1 or 2, that both of them solve.
+    fn two_solutions_of_one_problem() -> ([Fp; 9], SudokuGrid, SudokuGrid, SudokuGrid) {
+        let grid: [[u64; 9]; 9] = [
+            [2, 4, 9, 5, 3, 6, 1, 8, 7],
+            [3, 5, 1, 2, 7, 8, 4, 9, 6],
+            [6, 7, 8, 4, 9, 1, 5, 3, 2],
+            [8, 9, 7, 1, 4, 5, 6, 2, 3],
+            [4, 2, 3, 6, 8, 9, 7, 5, 1],
+            [5, 1, 6, 7, 2, 3, 9, 4, 8],
+            [1, 6, 2, 3, 5, 4, 8, 7, 9],
+            [9, 3, 5, 8, 6, 7, 2, 1, 4],
+            [7, 8, 4, 9, 1, 2, 3, 6, 5],
+        ];
+        let swap = |n: u64| match n {
+            1 => 2,
+            2 => 1,
+            n => n,
+        };
+        let to_field = |grid: [[u64; 9]; 9]| grid.map(|column| column.map(Fp::from));
+
+        (
+            core::array::from_fn(|n| Fp::from(n as u64 + 1)),
+            to_field(grid),
+            to_field(grid.map(|column| column.map(swap))),
+            to_field(grid.map(|column| column.map(|n| if n <= 2 { 0 } else { n }))),
+        )
+    }
+
+    #[test]
+    /// Test that the circuit accepts the fingerprint of the solution,
+    /// that the fingerprint matches its definition and is the same for
+    /// a circuit rebuilt from the same solution, and that two different solutions
+    /// of the same problem have different fingerprints, so that
+    /// the fingerprint of one solution is rejected for the other one.
+    fn mock_committed_sudoku() {
+        let (symbols, solution, other_solution, problem) = two_solutions_of_one_problem();
+
+        type Committed = CommittedSudokuCircuit<Fp, 9, 3>;
+
+        // The fingerprint is the sum of the cells, taken in order,
+        // times the decreasing powers of the base.
+        let cells = Vec::from_iter(solution.iter().flatten().copied());
+        let base = Fp::from(FINGERPRINT_BASE);
+        let expected_fingerprint = cells
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(exponent, cell)| base.pow_vartime([exponent as u64]) * cell)
+            .sum::<Fp>();
+        assert_eq!(Committed::fingerprint(&solution), expected_fingerprint);
+        assert_ne!(
+            Committed::fingerprint(&solution),
+            Committed::fingerprint(&other_solution)
+        );
+
+        let circuit = Committed::try_new(problem, solution, symbols).expect("the grids are valid");
+        let other_circuit =
+            Committed::try_new(problem, other_solution, symbols).expect("the grids are valid");
+
+        // A circuit for the same solution, rebuilt from its flattened cells,
+        // exposes the fingerprint computed above in-circuit.
+        let rebuilt_solution: SudokuGrid =
+            core::array::from_fn(|col| core::array::from_fn(|row| cells[col * 9 + row]));
+        let rebuilt_circuit =
+            Committed::try_new(problem, rebuilt_solution, symbols).expect("the grids are valid");
+
+        assert_satisfied(K, &circuit, Committed::instance(&problem, &solution));
+        let mut rebuilt_instance = Vec::from_iter(problem.iter().map(|column| column.to_vec()));
+        rebuilt_instance.push(vec![expected_fingerprint]);
+        assert_satisfied(K, &rebuilt_circuit, rebuilt_instance);
+        assert_satisfied(
+            K,
+            &other_circuit,
+            Committed::instance(&problem, &other_solution),
+        );
+        assert_rejected(K, &circuit, Committed::instance(&problem, &other_solution));
+    }
+}
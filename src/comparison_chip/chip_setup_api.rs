@@ -0,0 +1,71 @@
+use super::*;
+
+impl<F: ff::PrimeField, const BITS: usize> ComparisonChip<F, BITS> {
+    /// Loads `a` and `b` in the circuit, and upon successful execution,
+    /// returns `Ok(less_than)`, where `less_than` is a boolean cell
+    /// that is equal to 1 if and only if `a < b`.
+    ///
+    /// Both `a` and `b` are assumed to be in the range `[0, 2^BITS)`.
+    pub fn is_less_than(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let config = self.config();
+
+        let (less_than, difference) = layouter.assign_region(
+            || prefixed_name(self.name_prefix, "comparison"),
+            |mut region| {
+                // We enable the gate that constrains the comparison result.
+                config.s_less_than.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, config.columns[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.columns[1], 0)?;
+
+                let less_than = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a, b)| integer_less_than(*a, *b));
+
+                let less_than_cell = region
+                    .assign_advice(
+                        || "a < b",
+                        config.columns[2],
+                        0,
+                        || less_than.map(|less_than| if less_than { F::ONE } else { F::ZERO }),
+                    )
+                    .map(Number)?;
+
+                let difference_cell = region
+                    .assign_advice(
+                        || "a - b + (a < b) * 2^BITS",
+                        config.columns[3],
+                        0,
+                        || {
+                            a.value().copied() - b.value().copied()
+                                + less_than.map(|less_than| {
+                                    if less_than {
+                                        F::from(2).pow_vartime([BITS as u64])
+                                    } else {
+                                        F::ZERO
+                                    }
+                                })
+                        },
+                    )
+                    .map(Number)?;
+
+                Ok((less_than_cell, difference_cell))
+            },
+        )?;
+
+        // The comparison result is correct only if the difference is in range.
+        RangeCheckChip::<F, BITS>::construct_with_name_prefix(
+            config.range_check_config.clone(),
+            self.name_prefix,
+        )
+        .constrain_in_range(layouter.namespace(|| "difference range check"), difference)?;
+
+        Ok(less_than)
+    }
+}
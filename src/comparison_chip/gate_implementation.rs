@@ -0,0 +1,95 @@
+use super::*;
+
+impl<F: ff::PrimeField, const BITS: usize> ComparisonChip<F, BITS> {
+    pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
+        Self {
+            config,
+            name_prefix,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `is_less_than` assigns in the comparison region, relative to its
+    /// first row, in the order in which they are assigned. The column index
+    /// is the position of the column in `CConfig::columns`.
+    ///
+    /// The range check of the difference is laid out in a region of its own,
+    /// see `RangeCheckChip::occupied_cells`.
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        Vec::from_iter((0..4).map(|col_idx| (col_idx, 0)))
+    }
+
+    /// `columns` contain, in order, the two compared numbers `a` and `b`,
+    /// the comparison result, and the difference `a - b + (a < b) * 2^BITS`.
+    /// `bits_column` is used to range check the difference.
+    ///
+    /// Panics if `BITS` is not valid, see `try_configure`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: [Column<Advice>; 4],
+        bits_column: Column<Advice>,
+    ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
+        Self::try_configure(meta, columns, bits_column).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `configure`, but if `BITS` is zero, or not smaller than the
+    /// bit size of the field minus one, an error is returned, and `meta` is left untouched.
+    pub fn try_configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: [Column<Advice>; 4],
+        bits_column: Column<Advice>,
+    ) -> Result<<Self as halo2_proofs::circuit::Chip<F>>::Config, ConfigError> {
+        // The range check of the difference needs at least one bit.
+        if BITS == 0 {
+            return Err(ConfigError::NoBits);
+        }
+        // We need `a - b + 2^BITS` not to wrap around the field modulus.
+        if BITS + 1 >= F::NUM_BITS as usize {
+            return Err(ConfigError::TooManyBits {
+                bits: BITS,
+                max_bits: F::NUM_BITS as usize - 1,
+            });
+        }
+
+        for col in columns {
+            meta.enable_equality(col);
+        }
+
+        let s_less_than = meta.selector();
+        let two_pow_bits = F::from(2).pow_vartime([BITS as u64]);
+
+        meta.create_gate("less than", |meta| {
+            let s_less_than = meta.query_selector(s_less_than);
+            let [a, b, less_than, difference] =
+                columns.map(|col| meta.query_advice(col, Rotation::cur()));
+
+            // If `a < b`, then `a - b + 2^BITS` is in `[0, 2^BITS)`,
+            // otherwise `a - b` is. Given that the difference is range checked,
+            // only one value of `less_than` satisfies the constraints.
+            vec![
+                // less_than must be a boolean value
+                s_less_than.clone()
+                    * less_than.clone()
+                    * (less_than.clone() - Expression::Constant(F::ONE)),
+                s_less_than * (a - b + less_than * Expression::Constant(two_pow_bits) - difference),
+            ]
+        });
+
+        Ok(CConfig {
+            columns,
+            range_check_config: RangeCheckChip::<F, BITS>::configure(meta, columns[3], bits_column),
+            s_less_than,
+        })
+    }
+}
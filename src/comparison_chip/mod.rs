@@ -0,0 +1,197 @@
+use halo2_proofs::{
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    range_check_chip::{RCConfig, RangeCheckChip},
+    utilities::{prefixed_name, ConfigError},
+    Number,
+};
+
+/// in this module, we implement the functions needed to load
+/// the compared numbers and the comparison result in the advice columns.
+mod chip_setup_api;
+/// in this module, we implement the gate logic.
+mod gate_implementation;
+
+/// A chip that compares two numbers, interpreted as integers.
+///
+/// Both numbers are assumed to be in the range `[0, 2^BITS)`.
+/// It is up to the user of the chip to make sure that this holds,
+/// for example with a `RangeCheckChip`. If this assumption does
+/// not hold, the comparison result is meaningless.
+#[derive(Debug, Clone)]
+pub struct ComparisonChip<F: ff::PrimeField, const BITS: usize> {
+    config: CConfig<BITS>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
+    _marker: std::marker::PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CConfig<const BITS: usize> {
+    /// The columns containing, in order, the two compared numbers `a` and `b`,
+    /// the comparison result `a < b`, and the difference `a - b + (a < b) * 2^BITS`.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    pub columns: [Column<Advice>; 4],
+    range_check_config: RCConfig<BITS>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_less_than: Selector,
+}
+
+impl<F: ff::PrimeField, const BITS: usize> halo2_proofs::circuit::Chip<F>
+    for ComparisonChip<F, BITS>
+{
+    type Config = CConfig<BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+/// Compares two field elements, interpreted as integers.
+///
+/// We assume that the representation of `F` elements is little endian,
+/// as it is the case for the Pasta fields.
+pub(crate) fn integer_less_than<F: ff::PrimeField>(a: F, b: F) -> bool {
+    let (a, b) = (a.to_repr(), b.to_repr());
+    a.as_ref().iter().rev().lt(b.as_ref().iter().rev())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    /// A circuit that exposes the result of comparing two secret numbers.
+    struct LessThanCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for LessThanCircuit {
+        type Config = (CConfig<8>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: Value::unknown(),
+                b: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+
+            let columns = [(); 4].map(|_| meta.advice_column());
+            let bits_column = meta.advice_column();
+            (
+                ComparisonChip::<Fp, 8>::configure(meta, columns, bits_column),
+                instance,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = ComparisonChip::<Fp, 8>::construct(config);
+
+            let (a, b) = layouter.assign_region(
+                || "compared numbers",
+                |mut region| {
+                    let columns = chip.config().columns;
+                    let a = region.assign_advice(|| "a", columns[0], 0, || self.a)?;
+                    let b = region.assign_advice(|| "b", columns[1], 0, || self.b)?;
+                    Ok((Number(a), Number(b)))
+                },
+            )?;
+
+            let less_than = chip.is_less_than(layouter.namespace(|| "comparison"), a, b)?;
+            layouter.constrain_instance(less_than.cell(), instance, 0)
+        }
+    }
+
+    /// Runs the mock prover on `a < b`, with `expected_out` as public output.
+    fn run_less_than(a: u64, b: u64, expected_out: u64) -> bool {
+        const POW_OF_2_MAX_ROWS: u32 = 5;
+
+        let circuit = LessThanCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
+        MockProver::run(
+            POW_OF_2_MAX_ROWS,
+            &circuit,
+            vec![vec![Fp::from(expected_out)]],
+        )
+        .expect("Proof generation goes wrong")
+        .verify()
+        .is_ok()
+    }
+
+    #[test]
+    /// Test the comparison chip with the mock prover.
+    fn mock_less_than() {
+        assert!(run_less_than(3, 5, 1), "3 < 5 should be true");
+        assert!(run_less_than(5, 3, 0), "5 < 3 should be false");
+        assert!(run_less_than(4, 4, 0), "4 < 4 should be false");
+
+        assert!(!run_less_than(3, 5, 0), "Bogous proof was accepted");
+        assert!(!run_less_than(4, 4, 1), "Bogous proof was accepted");
+    }
+
+    #[test]
+    /// Test that the comparison chip occupies the four cells
+    /// of a single row in its comparison region, each one once.
+    fn comparison_chip_occupied_cells() {
+        let cells = ComparisonChip::<Fp, 8>::occupied_cells();
+        assert_eq!(cells, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(
+            cells
+                .iter()
+                .collect::<std::collections::BTreeSet<_>>()
+                .len(),
+            cells.len()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    /// Test that the configuration of the comparison chip,
+    /// range check included, survives a round trip through JSON.
+    fn comparison_config_serde_round_trip() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        // We allocate some columns and selectors beforehand,
+        // so that the columns and selectors of the chip do not have index 0.
+        let _ = [(); 2].map(|_| meta.advice_column());
+        let _ = [(); 3].map(|_| meta.selector());
+        let columns = [(); 4].map(|_| meta.advice_column());
+        let bits_column = meta.advice_column();
+        let config = ComparisonChip::<Fp, 8>::configure(&mut meta, columns, bits_column);
+
+        let json = serde_json::to_string(&config).expect("serialization should not fail");
+        let deserialized: CConfig<8> =
+            serde_json::from_str(&json).expect("deserialization should not fail");
+
+        assert_eq!(deserialized.columns, config.columns);
+        assert_eq!(format!("{deserialized:?}"), format!("{config:?}"));
+    }
+}
@@ -0,0 +1,206 @@
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    permutation_chip::{PConfig, PermutationChip},
+    utilities::compose_permutations,
+    Number,
+};
+
+use halo2_proofs::{
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Column, ConstraintSystem, Error, Instance},
+};
+
+/// A circuit that proves that applying the permutation `p` and then
+/// the permutation `q` to the input values is the same as applying
+/// the single permutation `r` to them, i.e. that `r = q ∘ p`.
+///
+/// The values obtained at the end are exposed in the instance column.
+pub struct ComposedPermutationCircuit<F: ff::Field, const N_OBJECTS: usize> {
+    input_items: [Value<F>; N_OBJECTS],
+    p: [usize; N_OBJECTS],
+    q: [usize; N_OBJECTS],
+    r: [usize; N_OBJECTS],
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> ComposedPermutationCircuit<F, N_OBJECTS> {
+    /// Builds a circuit without checking its inputs.
+    /// It is up to the caller to guarantee that `p`, `q`, `r` are permutations.
+    /// Whether `r` is the composition of `p` and `q` is checked by the circuit.
+    pub fn new_unchecked(
+        input_items: [Value<F>; N_OBJECTS],
+        p: [usize; N_OBJECTS],
+        q: [usize; N_OBJECTS],
+        r: [usize; N_OBJECTS],
+    ) -> Self {
+        Self {
+            input_items,
+            p,
+            q,
+            r,
+        }
+    }
+
+    /// Same as `new_unchecked`, with `r` computed as the composition of `p` and `q`.
+    pub fn from_composition(
+        input_items: [Value<F>; N_OBJECTS],
+        p: [usize; N_OBJECTS],
+        q: [usize; N_OBJECTS],
+    ) -> Self {
+        Self::new_unchecked(input_items, p, q, compose_permutations(p, q))
+    }
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> Default for ComposedPermutationCircuit<F, N_OBJECTS> {
+    fn default() -> Self {
+        let identity = core::array::from_fn(|idx| idx);
+        Self::new_unchecked([Value::unknown(); N_OBJECTS], identity, identity, identity)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComposedPermutationConfig<const N_OBJECTS: usize> {
+    pconfig: PConfig<N_OBJECTS>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    instance: Column<Instance>,
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<F>
+    for ComposedPermutationCircuit<F, N_OBJECTS>
+{
+    type Config = ComposedPermutationConfig<N_OBJECTS>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let item_columns = [(); N_OBJECTS].map(|_| meta.advice_column());
+        let swap_selector_columns = (0..N_OBJECTS / 2).map(|_| meta.advice_column()).collect();
+
+        ComposedPermutationConfig {
+            pconfig: PermutationChip::configure(meta, item_columns, swap_selector_columns),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let permutation_chip = PermutationChip::<N_OBJECTS, F>::construct(config.pconfig);
+
+        let input_cells = layouter.namespace(|| "input values").assign_region(
+            || "input values",
+            |mut region| {
+                let item_columns = permutation_chip.config().get_item_columns();
+                let mut cells = vec![];
+                for (idx, value) in self.input_items.into_iter().enumerate() {
+                    cells.push(
+                        region
+                            .assign_advice(
+                                || format!("{idx}-th input value"),
+                                item_columns[idx],
+                                0,
+                                || value,
+                            )
+                            .map(Number)?,
+                    );
+                }
+                Ok(core::array::from_fn(|idx| cells[idx].clone()))
+            },
+        )?;
+
+        // We apply `p`, and then `q` to its output.
+        let p_output_cells = permutation_chip.apply_permutation(
+            layouter.namespace(|| "applying p"),
+            input_cells.clone(),
+            self.p,
+        )?;
+        let q_output_cells = permutation_chip.apply_permutation(
+            layouter.namespace(|| "applying q"),
+            p_output_cells,
+            self.q,
+        )?;
+
+        // We apply `r` to the input values directly.
+        let r_output_cells = permutation_chip.apply_permutation(
+            layouter.namespace(|| "applying r"),
+            input_cells,
+            self.r,
+        )?;
+
+        layouter.namespace(|| "composition check").assign_region(
+            || "composition check",
+            |mut region| {
+                for (left, right) in q_output_cells.iter().zip(r_output_cells.iter()) {
+                    region.constrain_equal(left.cell(), right.cell())?;
+                }
+                Ok(())
+            },
+        )?;
+
+        let mut output_layouter = layouter.namespace(|| "public output assignment");
+        for (idx, cell) in q_output_cells.iter().enumerate() {
+            output_layouter.constrain_instance(cell.cell(), config.instance, idx)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied, inverse_permutation};
+    use halo2_proofs::pasta::Fp;
+
+    const N_OBJECTS: usize = 5;
+    const K: u32 = 5;
+
+    fn objects() -> [Value<Fp>; N_OBJECTS] {
+        core::array::from_fn(|n| Value::known(Fp::from(n as u64)))
+    }
+
+    /// The values obtained by applying `r` to `objects()`.
+    fn instance(r: [usize; N_OBJECTS]) -> Vec<Vec<Fp>> {
+        vec![Vec::from(
+            inverse_permutation(r).map(|x| Fp::from(x as u64)),
+        )]
+    }
+
+    #[test]
+    /// Test that the composition of two permutations is accepted.
+    fn mock_composed_permutation() {
+        let p = [1, 2, 0, 4, 3];
+        let q = [4, 3, 2, 1, 0];
+        let circuit =
+            ComposedPermutationCircuit::<Fp, N_OBJECTS>::from_composition(objects(), p, q);
+
+        assert_satisfied(K, &circuit, instance(compose_permutations(p, q)));
+    }
+
+    #[test]
+    /// Test that a permutation that is not the composition is rejected,
+    /// even when the public output agrees with it.
+    fn mock_composed_permutation_wrong_composition() {
+        let p = [1, 2, 0, 4, 3];
+        let q = [4, 3, 2, 1, 0];
+        // The composition in the wrong order.
+        let r = compose_permutations(q, p);
+        assert_ne!(r, compose_permutations(p, q));
+
+        let circuit =
+            ComposedPermutationCircuit::<Fp, N_OBJECTS>::new_unchecked(objects(), p, q, r);
+
+        assert_rejected(K, &circuit, instance(compose_permutations(p, q)));
+        assert_rejected(K, &circuit, instance(r));
+    }
+}
@@ -0,0 +1,81 @@
+use super::*;
+
+impl<F: ff::PrimeField, const N: usize, const BITS: usize> DistinctnessChip<F, N, BITS> {
+    /// Constrains the numbers in `input_items` to be pairwise distinct.
+    ///
+    /// The numbers are copied, so their cells need to be in columns
+    /// with equality enabled, e.g. the item columns of the chip.
+    pub fn enforce_distinct(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input_items: [Number<F>; N],
+    ) -> Result<(), Error> {
+        // When the values are not known, e.g. during key generation,
+        // the identity permutation is laid out in place of the sorting one,
+        // so that the layout of the circuit does not depend on the witness.
+        let permutation =
+            Self::sorting_permutation(&input_items).unwrap_or(core::array::from_fn(|idx| idx));
+
+        let sorted_items = PermutationChip::<N, F>::construct_with_name_prefix(
+            self.config().permutation_config.clone(),
+            self.name_prefix,
+        )
+        .apply_permutation(
+            layouter.namespace(|| "sorting the numbers"),
+            input_items,
+            permutation,
+        )?;
+
+        // The sorted numbers are strictly increasing only if they are distinct.
+        let comparison_chip = ComparisonChip::<F, BITS>::construct_with_name_prefix(
+            self.config().comparison_config.clone(),
+            self.name_prefix,
+        );
+        for (idx, pair) in sorted_items.windows(2).enumerate() {
+            let less_than = comparison_chip.is_less_than(
+                layouter
+                    .namespace(|| format!("comparing the {idx}-th and the next sorted numbers")),
+                pair[0].clone(),
+                pair[1].clone(),
+            )?;
+            layouter.assign_region(
+                || {
+                    prefixed_name(
+                        self.name_prefix,
+                        &format!("the {idx}-th sorted number is smaller than the next one"),
+                    )
+                },
+                |mut region| region.constrain_constant(less_than.cell(), F::ONE),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// If the values of all the cells are known, returns the permutation
+    /// that sorts them, i.e. the permutation that maps the index of every
+    /// cell to the position of its value among the sorted values.
+    fn sorting_permutation(items: &[Number<F>; N]) -> Option<[usize; N]> {
+        let values = items
+            .iter()
+            .map(Number::known_value)
+            .collect::<Option<Vec<F>>>()?;
+
+        let mut order = Vec::from_iter(0..N);
+        order.sort_by(|&i, &j| {
+            if integer_less_than(values[i], values[j]) {
+                std::cmp::Ordering::Less
+            } else if integer_less_than(values[j], values[i]) {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        });
+
+        let mut permutation = [0; N];
+        for (position, idx) in order.into_iter().enumerate() {
+            permutation[idx] = position;
+        }
+        Some(permutation)
+    }
+}
@@ -0,0 +1,88 @@
+use super::*;
+
+impl<F: ff::PrimeField, const N: usize, const BITS: usize> DistinctnessChip<F, N, BITS> {
+    pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
+        Self {
+            config,
+            name_prefix,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// `item_columns` and `swap_selector_columns` are used to sort the numbers,
+    /// see `PermutationChip::configure`, while `comparison_columns` and
+    /// `bits_column` are used to compare them, see `ComparisonChip::configure`.
+    /// `constant_column` is used to lay out the expected comparison results,
+    /// and may be shared with other chips.
+    ///
+    /// Panics if the parameters are not valid, see `try_configure`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        item_columns: [Column<Advice>; N],
+        swap_selector_columns: Vec<Column<Advice>>,
+        comparison_columns: [Column<Advice>; 4],
+        bits_column: Column<Advice>,
+        constant_column: Column<Fixed>,
+    ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
+        Self::try_configure(
+            meta,
+            item_columns,
+            swap_selector_columns,
+            comparison_columns,
+            bits_column,
+            constant_column,
+        )
+        .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `configure`, but if `BITS`, or the number of swap selector
+    /// columns, is not valid, an error is returned.
+    /// `BITS` is checked first: if it is not valid, `meta` is left untouched.
+    ///
+    /// Besides the requirements of `ComparisonChip`, `BITS + ceil(log2(N))`
+    /// has to be smaller than `F::NUM_BITS - 1`, otherwise the gaps between
+    /// the sorted numbers could wrap around the field characteristic,
+    /// and a number could be sorted twice.
+    pub fn try_configure(
+        meta: &mut ConstraintSystem<F>,
+        item_columns: [Column<Advice>; N],
+        swap_selector_columns: Vec<Column<Advice>>,
+        comparison_columns: [Column<Advice>; 4],
+        bits_column: Column<Advice>,
+        constant_column: Column<Fixed>,
+    ) -> Result<<Self as halo2_proofs::circuit::Chip<F>>::Config, ConfigError> {
+        // The sum of the `N - 1` gaps, each one at most `2^BITS`,
+        // has to be smaller than `2^(F::NUM_BITS - 1)`, and thus than the modulus.
+        let log2_items = N.next_power_of_two().trailing_zeros() as usize;
+        let max_bits = (F::NUM_BITS as usize - 1).saturating_sub(log2_items);
+        if BITS >= max_bits {
+            return Err(ConfigError::TooManyBitsForItems {
+                bits: BITS,
+                items: N,
+                max_bits,
+            });
+        }
+
+        let comparison_config =
+            ComparisonChip::<F, BITS>::try_configure(meta, comparison_columns, bits_column)?;
+        let permutation_config =
+            PermutationChip::<N, F>::try_configure(meta, item_columns, swap_selector_columns)?;
+
+        meta.enable_constant(constant_column);
+
+        Ok(DConfig {
+            permutation_config,
+            comparison_config,
+        })
+    }
+}
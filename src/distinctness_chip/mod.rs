@@ -0,0 +1,193 @@
+use halo2_proofs::{
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+};
+
+use crate::{
+    comparison_chip::{integer_less_than, CConfig, ComparisonChip},
+    permutation_chip::{PConfig, PermutationChip},
+    utilities::{prefixed_name, ConfigError},
+    Number,
+};
+
+/// in this module, we implement the functions needed to sort
+/// the numbers and to compare the sorted ones.
+mod chip_setup_api;
+/// in this module, we configure the chips the gate is made of.
+mod gate_implementation;
+
+/// A chip that enforces `N` numbers, interpreted as integers,
+/// to be pairwise distinct.
+///
+/// The numbers are sorted by a `PermutationChip`, and every sorted number
+/// is constrained to be smaller than the next one by a `ComparisonChip`.
+/// The sorting permutation is computed out of the values of the numbers,
+/// so the prover does not have to provide it.
+///
+/// For the sorted numbers to compare as expected, the numbers have to be
+/// in the range `[0, 2^BITS)`, see `ComparisonChip`, otherwise distinct
+/// numbers may be rejected.
+///
+/// Each comparison only proves that the gap between two sorted numbers
+/// is in `(0, 2^BITS]`, modulo the field characteristic. The chip requires
+/// `BITS + ceil(log2(N)) < F::NUM_BITS - 1`, see `try_configure`, so that
+/// the gaps cannot add up to a multiple of the characteristic, and
+/// equal numbers are rejected whatever their range.
+#[derive(Debug, Clone)]
+pub struct DistinctnessChip<F: ff::PrimeField, const N: usize, const BITS: usize> {
+    config: DConfig<N, BITS>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
+    _marker: std::marker::PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DConfig<const N: usize, const BITS: usize> {
+    permutation_config: PConfig<N>,
+    comparison_config: CConfig<BITS>,
+}
+
+impl<F: ff::PrimeField, const N: usize, const BITS: usize> halo2_proofs::circuit::Chip<F>
+    for DistinctnessChip<F, N, BITS>
+{
+    type Config = DConfig<N, BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::mock_prove_auto_k;
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    /// A circuit that proves that 4 secret numbers are pairwise distinct.
+    struct DistinctnessCircuit {
+        values: [Value<Fp>; 4],
+        name_prefix: &'static str,
+    }
+
+    impl Circuit<Fp> for DistinctnessCircuit {
+        type Config = DConfig<4, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: [Value::unknown(); 4],
+                name_prefix: self.name_prefix,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let item_columns = [(); 4].map(|_| meta.advice_column());
+            let swap_selector_columns = Vec::from_iter(
+                (0..PermutationChip::<4, Fp>::recommended_swap_selector_columns(4))
+                    .map(|_| meta.advice_column()),
+            );
+            let comparison_columns = [(); 4].map(|_| meta.advice_column());
+            let bits_column = meta.advice_column();
+            let constant_column = meta.fixed_column();
+
+            DistinctnessChip::<Fp, 4, 8>::configure(
+                meta,
+                item_columns,
+                swap_selector_columns,
+                comparison_columns,
+                bits_column,
+                constant_column,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip =
+                DistinctnessChip::<Fp, 4, 8>::construct_with_name_prefix(config, self.name_prefix);
+
+            let items = layouter.assign_region(
+                || "distinct numbers",
+                |mut region| {
+                    let item_columns = chip.config().permutation_config.get_item_columns();
+                    let mut cells = vec![];
+                    for (idx, value) in self.values.into_iter().enumerate() {
+                        cells.push(
+                            region
+                                .assign_advice(
+                                    || format!("{idx}-th number"),
+                                    item_columns[idx],
+                                    0,
+                                    || value,
+                                )
+                                .map(Number)?,
+                        );
+                    }
+                    Ok(core::array::from_fn(|idx| cells[idx].clone()))
+                },
+            )?;
+
+            chip.enforce_distinct(layouter.namespace(|| "distinctness"), items)
+        }
+    }
+
+    fn run_distinctness(values: [u64; 4]) -> bool {
+        let circuit = DistinctnessCircuit {
+            values: values.map(|value| Value::known(Fp::from(value))),
+            name_prefix: "",
+        };
+        mock_prove_auto_k(&circuit, vec![]).is_ok()
+    }
+
+    #[test]
+    /// Test the distinctness chip with the mock prover.
+    fn mock_distinctness() {
+        assert!(
+            run_distinctness([3, 1, 4, 2]),
+            "distinct numbers were rejected"
+        );
+        assert!(
+            run_distinctness([0, 255, 7, 8]),
+            "distinct numbers were rejected"
+        );
+
+        assert!(!run_distinctness([3, 1, 3, 2]), "Bogous proof was accepted");
+        assert!(!run_distinctness([5, 5, 5, 5]), "Bogous proof was accepted");
+    }
+
+    #[test]
+    /// Test that the name prefix of the chip is passed on to the chips
+    /// it is made of, and appears in the regions recorded by the `MockProver`.
+    fn distinctness_chip_name_prefix() {
+        let circuit = DistinctnessCircuit {
+            values: [3, 1, 4, 2].map(|value| Value::known(Fp::from(value))),
+            name_prefix: "distinct",
+        };
+        let prover = halo2_proofs::dev::MockProver::run(
+            crate::utilities::recommended_k(&circuit)
+                .expect("layout should not fail")
+                .0,
+            &circuit,
+            vec![],
+        )
+        .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+
+        let layout = format!("{prover:?}");
+        for name in ["load input", "comparison", "bit decomposition"] {
+            assert!(layout.contains(&format!("distinct: {name}")));
+        }
+    }
+}
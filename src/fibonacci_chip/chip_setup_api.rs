@@ -0,0 +1,43 @@
+use super::*;
+
+impl<F: ff::Field, const N: usize> FibonacciChip<F, N> {
+    /// Given the first two terms `f[0]` and `f[1]` of a Fibonacci-like
+    /// sequence, that is, a sequence such that `f[i + 2] = f[i + 1] + f[i]`,
+    /// upon successful execution, returns `Ok(f[N])`.
+    pub fn compute(
+        &self,
+        mut layouter: impl Layouter<F>,
+        first: Number<F>,
+        second: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || prefixed_name(self.name_prefix, "assign fibonacci chip advice"),
+            |mut region| {
+                let config = self.config();
+
+                // The first two terms are copied in the first two rows,
+                // and every other term is assigned in the row below its predecessor.
+                let mut terms = vec![
+                    first.copy_advice(|| "f[0]", &mut region, config.column, 0)?,
+                    second.copy_advice(|| "f[1]", &mut region, config.column, 1)?,
+                ];
+
+                for idx in 2..=N {
+                    // The gate enabled at row `idx - 2` constrains
+                    // the term at row `idx`.
+                    config.s_fib.enable(&mut region, idx - 2)?;
+
+                    let value = terms[idx - 2].value().copied() + terms[idx - 1].value().copied();
+                    terms.push(region.assign_advice(
+                        || format!("f[{idx}]"),
+                        config.column,
+                        idx,
+                        || value,
+                    )?);
+                }
+
+                Ok(Number(terms.swap_remove(N)))
+            },
+        )
+    }
+}
@@ -0,0 +1,52 @@
+use super::*;
+
+impl<F: ff::Field, const N: usize> FibonacciChip<F, N> {
+    pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
+        Self {
+            config,
+            name_prefix,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `compute` assigns, relative to the first row of its region,
+    /// in the order in which they are assigned: the terms `f[0], ..., f[N]`,
+    /// one per row, in the only column of the chip.
+    /// The first two terms are assigned even if `N < 1`.
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        Vec::from_iter((0..=N.max(1)).map(|row_idx| (0, row_idx)))
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        column: Column<Advice>,
+    ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
+        meta.enable_equality(column);
+
+        let s_fib = meta.selector();
+
+        meta.create_gate("fibonacci step", |meta| {
+            let s_fib = meta.query_selector(s_fib);
+            let f0 = meta.query_advice(column, Rotation::cur());
+            let f1 = meta.query_advice(column, Rotation::next());
+            let f2 = meta.query_advice(column, Rotation(2));
+
+            // if s_fib is enabled, the cell two rows below the current one
+            // is the sum of the current cell and the next one.
+            vec![s_fib * (f2 - f1 - f0)]
+        });
+
+        FConfig { column, s_fib }
+    }
+}
@@ -0,0 +1,42 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{utilities::prefixed_name, Number};
+
+mod chip_setup_api;
+mod gate_implementation;
+
+#[derive(Debug, Clone)]
+pub struct FibonacciChip<F: ff::Field, const N: usize> {
+    config: FConfig,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FConfig {
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    pub column: Column<Advice>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_fib: Selector,
+}
+
+impl<F: ff::Field, const N: usize> halo2_proofs::circuit::Chip<F> for FibonacciChip<F, N> {
+    type Config = FConfig;
+    type Loaded = ();
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
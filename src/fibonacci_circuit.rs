@@ -0,0 +1,182 @@
+use halo2_proofs::{
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Column, Instance},
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::Number;
+
+/// A circuit that proves that the public output is the `N`-th term of
+/// the Fibonacci-like sequence starting from two secret terms.
+#[derive(Default)]
+pub struct FibonacciCircuit<F: ff::Field, const N: usize> {
+    first: Value<F>,
+    second: Value<F>,
+}
+
+impl<F: ff::Field, const N: usize> FibonacciCircuit<F, N> {
+    pub fn new(first: F, second: F) -> Self {
+        Self {
+            first: Value::known(first),
+            second: Value::known(second),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FibCircuitConfig {
+    fib_config: crate::fibonacci_chip::FConfig,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    instance_column: Column<Instance>,
+}
+
+impl<F: ff::Field, const N: usize> halo2_proofs::plonk::Circuit<F> for FibonacciCircuit<F, N> {
+    type Config = FibCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Default::default()
+    }
+
+    fn configure(meta: &mut halo2_proofs::plonk::ConstraintSystem<F>) -> Self::Config {
+        let column = meta.advice_column();
+        let instance_column = meta.instance_column();
+        meta.enable_equality(instance_column);
+
+        FibCircuitConfig {
+            fib_config: crate::fibonacci_chip::FibonacciChip::<F, N>::configure(meta, column),
+            instance_column,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), halo2_proofs::plonk::Error> {
+        let fibonacci_chip =
+            crate::fibonacci_chip::FibonacciChip::<F, N>::construct(config.fib_config);
+
+        let (first, second) = layouter
+            .namespace(|| "allocation of input items")
+            .assign_region(
+                || "allocation of input items",
+                |mut region| {
+                    let column = fibonacci_chip.config().column;
+                    let first = region.assign_advice(|| "f[0]", column, 0, || self.first)?;
+                    let second = region.assign_advice(|| "f[1]", column, 1, || self.second)?;
+                    Ok((Number(first), Number(second)))
+                },
+            )?;
+
+        let output_item = fibonacci_chip.compute(
+            layouter.namespace(|| "fibonacci computation"),
+            first,
+            second,
+        )?;
+
+        layouter.namespace(|| "copy of output").constrain_instance(
+            output_item.cell(),
+            config.instance_column,
+            0,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    #[test]
+    /// Test the fibonacci circuit with the mock prover, which prints out errors and warnings.
+    /// We prove that we know the 10th Fibonacci number, and that a
+    /// wrong output is rejected.
+    fn mock_fibonacci_10() {
+        const POW_OF_2_MAX_ROWS: u32 = 5;
+
+        let circuit = FibonacciCircuit::<Fp, 10>::new(Fp::from(0), Fp::from(1));
+
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![vec![Fp::from(55)]])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![vec![Fp::from(56)]])
+            .expect("Proof generation goes wrong");
+        assert!(prover.verify().is_err(), "Bogous proof was accepted");
+    }
+
+    #[test]
+    /// Test the fibonacci circuit with actual prover and verifier through the wrappers we implemented.
+    fn fibonacci() {
+        const MAX_NR_ROWS_POW_2_EXPONENT: u32 = 5;
+        const N: usize = 20;
+
+        use crate::utilities::{ProverWrapper, VerifierWrapper};
+
+        let mut prover = ProverWrapper::initialize_parameters_and_prover(
+            MAX_NR_ROWS_POW_2_EXPONENT,
+            FibonacciCircuit::<Fp, N>::default(),
+        )
+        .expect("prover setup should not fail");
+
+        let instance = [Fp::from(6765)];
+        let instance = [instance.as_slice()];
+
+        prover.add_item(
+            FibonacciCircuit::<Fp, N>::new(Fp::from(0), Fp::from(1)),
+            instance.as_slice(),
+        );
+
+        let transcript = crate::time_it! {
+            "proof generation time: {:?}",
+            prover.prove().expect("proof generation fails")
+        };
+
+        let mut verifier = VerifierWrapper::from(prover);
+
+        crate::time_it! {
+            "proof verification time: {:?}",
+            assert!(
+                verifier.verify([instance.as_slice()], transcript.as_slice()),
+                "proof verification fails"
+            )
+        };
+    }
+
+    #[test]
+    /// Test the coordinates of the cells occupied by the fibonacci chip
+    /// for a known number of terms, and that they are distinct,
+    /// for a range of numbers of terms.
+    fn fibonacci_chip_occupied_cells() {
+        use crate::fibonacci_chip::FibonacciChip;
+
+        assert_eq!(
+            FibonacciChip::<Fp, 3>::occupied_cells(),
+            vec![(0, 0), (0, 1), (0, 2), (0, 3)]
+        );
+        // The first two terms are laid out anyway.
+        assert_eq!(
+            FibonacciChip::<Fp, 0>::occupied_cells(),
+            vec![(0, 0), (0, 1)]
+        );
+
+        fn check<const N: usize>() {
+            let cells = FibonacciChip::<Fp, N>::occupied_cells();
+            assert_eq!(
+                cells
+                    .iter()
+                    .collect::<std::collections::BTreeSet<_>>()
+                    .len(),
+                cells.len()
+            );
+        }
+        check::<1>();
+        check::<10>();
+        check::<20>();
+    }
+}
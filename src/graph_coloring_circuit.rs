@@ -0,0 +1,291 @@
+use crate::{
+    set_membership_chip::{SMConfig, SetMembershipChip},
+    Number,
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use halo2_proofs::{
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+/// How many factors `color - symbol` are multiplied together in a single constraint.
+const MUL_BATCH_SIZE: usize = 3;
+
+/// How many advice columns are used to lay out the partial products.
+const N_COLUMNS: usize = 2;
+
+/// A circuit that proves the knowledge of a proper `K`-coloring of a public graph
+/// with `V` vertices, i.e. of an assignment of a color in `1..=K` to every vertex,
+/// such that the endpoints of every edge have different colors.
+///
+/// The edge `(u, v)` is exposed in the instance column as the two
+/// consecutive values `u, v`, in the order in which the edges are given.
+/// The coloring is kept secret.
+///
+/// The edges are laid out in fixed columns, so they are part of the
+/// circuit's verifying key, as the symbols are in `SetMembershipCircuit`.
+#[derive(Clone, Debug)]
+pub struct GraphColoringCircuit<F: ff::PrimeField, const V: usize, const K: usize> {
+    edges: Vec<(usize, usize)>,
+    coloring: [Value<F>; V],
+}
+
+impl<F: ff::PrimeField, const V: usize, const K: usize> GraphColoringCircuit<F, V, K> {
+    /// Builds a circuit from a graph and a coloring of its vertices,
+    /// checking that the endpoints of every edge are vertices of the graph.
+    /// Whether the coloring is proper is not checked here, since ruling out
+    /// improper colorings is the whole point of the circuit.
+    pub fn try_new(edges: Vec<(usize, usize)>, coloring: [u64; V]) -> Result<Self, ()> {
+        if edges.iter().any(|&(u, v)| u >= V || v >= V) {
+            return Err(());
+        }
+
+        Ok(Self {
+            edges,
+            coloring: coloring.map(|color| Value::known(F::from(color))),
+        })
+    }
+
+    /// Given an edge list, outputs an instance of the circuit
+    /// without witness values
+    pub fn circuit_wiring_from_edges(edges: Vec<(usize, usize)>) -> Self {
+        Self {
+            edges,
+            coloring: [Value::unknown(); V],
+        }
+    }
+
+    /// The values the instance column has to contain, i.e.
+    /// the endpoints of the edges of the graph, flattened.
+    pub fn instance(&self) -> Vec<F> {
+        self.edges
+            .iter()
+            .flat_map(|&(u, v)| [u, v])
+            .map(|endpoint| F::from(endpoint as u64))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphColoringConfig {
+    smconfig: SMConfig<N_COLUMNS>,
+    /// The columns where the couples of colors `(color[u], color[v])`,
+    /// along with the witness of their distinctness, are laid out.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    edge_columns: [Column<Advice>; 3],
+    /// The columns where the endpoints `(u, v)` of the edges are laid out.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    endpoint_columns: [Column<Fixed>; 2],
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_edge: Selector,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    instance: Column<Instance>,
+}
+
+impl<F: ff::PrimeField, const V: usize, const K: usize> halo2_proofs::plonk::Circuit<F>
+    for GraphColoringCircuit<F, V, K>
+{
+    type Config = GraphColoringConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::circuit_wiring_from_edges(self.edges.clone())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let columns = [(); N_COLUMNS].map(|_| meta.advice_column());
+        let symbols_column = meta.advice_column();
+        let constant_column = meta.fixed_column();
+
+        let edge_columns = [(); 3].map(|_| meta.advice_column());
+        for col in edge_columns.iter().take(2) {
+            meta.enable_equality(*col);
+        }
+        let endpoint_columns = [(); 2].map(|_| meta.fixed_column());
+        for col in endpoint_columns.iter() {
+            meta.enable_equality(*col);
+        }
+
+        let s_edge = meta.selector();
+
+        meta.create_gate("distinct colors", |meta| {
+            let s_edge = meta.query_selector(s_edge);
+            let [left, right, inverse] =
+                edge_columns.map(|col| meta.query_advice(col, Rotation::cur()));
+
+            // We prove that `color[u] - color[v]` is not zero by exhibiting its inverse.
+            vec![s_edge * ((left - right) * inverse - Expression::Constant(F::ONE))]
+        });
+
+        GraphColoringConfig {
+            smconfig: SetMembershipChip::<F, K, MUL_BATCH_SIZE, N_COLUMNS>::configure(
+                meta,
+                columns,
+                symbols_column,
+                constant_column,
+            ),
+            edge_columns,
+            endpoint_columns,
+            s_edge,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let membership_chip =
+            SetMembershipChip::<F, K, MUL_BATCH_SIZE, N_COLUMNS>::construct(config.smconfig);
+
+        let color_cells = layouter.namespace(|| "coloring").assign_region(
+            || "coloring",
+            |mut region| {
+                let mut cells = vec![];
+                for (idx, color) in self.coloring.iter().enumerate() {
+                    cells.push(
+                        region
+                            .assign_advice(
+                                || format!("color of vertex {idx}"),
+                                membership_chip.config().columns[0],
+                                idx,
+                                || *color,
+                            )
+                            .map(Number)?,
+                    );
+                }
+                Ok(cells)
+            },
+        )?;
+
+        // Every color has to be one of the symbols `1, ..., K`.
+        let symbols = core::array::from_fn(|idx| F::from(idx as u64 + 1));
+        for (idx, cell) in color_cells.iter().enumerate() {
+            membership_chip.constrain_membership(
+                layouter.namespace(|| format!("color range of vertex {idx}")),
+                cell.clone(),
+                symbols,
+            )?;
+        }
+
+        let endpoint_cells = layouter.namespace(|| "distinct colors").assign_region(
+            || "distinct colors",
+            |mut region| {
+                let [left_column, right_column, inverse_column] = config.edge_columns;
+                let [u_column, v_column] = config.endpoint_columns;
+
+                let mut endpoint_cells = vec![];
+                for (row, &(u, v)) in self.edges.iter().enumerate() {
+                    config.s_edge.enable(&mut region, row)?;
+
+                    for (endpoint, column) in [(u, u_column), (v, v_column)] {
+                        endpoint_cells.push(region.assign_fixed(
+                            || format!("endpoint {endpoint} of edge {row}"),
+                            column,
+                            row,
+                            || Value::known(F::from(endpoint as u64)),
+                        )?);
+                    }
+
+                    let left = color_cells[u].copy_advice(
+                        || format!("color of vertex {u}"),
+                        &mut region,
+                        left_column,
+                        row,
+                    )?;
+                    let right = color_cells[v].copy_advice(
+                        || format!("color of vertex {v}"),
+                        &mut region,
+                        right_column,
+                        row,
+                    )?;
+
+                    // If the endpoints share a color, there is no inverse,
+                    // and we assign zero, which does not satisfy the gate.
+                    let inverse = left.value().zip(right.value()).map(|(left, right)| {
+                        Option::<F>::from((*left - *right).invert()).unwrap_or(F::ZERO)
+                    });
+                    region.assign_advice(
+                        || format!("color distinctness witness for edge {row}"),
+                        inverse_column,
+                        row,
+                        || inverse,
+                    )?;
+                }
+                Ok(endpoint_cells)
+            },
+        )?;
+
+        let mut output_layouter = layouter.namespace(|| "public edges");
+        for (idx, cell) in endpoint_cells.iter().map(|c| c.cell()).enumerate() {
+            output_layouter.constrain_instance(cell, config.instance, idx)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied};
+    use halo2_proofs::pasta::Fp;
+
+    const POW_2_EXP_MAX_ROWS: u32 = 6;
+
+    /// A 5-cycle, with a chord between the vertices 0 and 2.
+    fn edges() -> Vec<(usize, usize)> {
+        vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (0, 2)]
+    }
+
+    #[test]
+    /// Test that a proper 3-coloring is accepted.
+    fn mock_graph_coloring() {
+        let circuit = GraphColoringCircuit::<Fp, 5, 3>::try_new(edges(), [1, 2, 3, 1, 2])
+            .expect("the edges are between vertices of the graph");
+        let instance = circuit.instance();
+        assert_satisfied(POW_2_EXP_MAX_ROWS, &circuit, vec![instance]);
+    }
+
+    #[test]
+    /// Test that colorings that are not proper are rejected.
+    fn mock_graph_coloring_bad_colorings() {
+        // The endpoints of the edge `(2, 3)` share a color.
+        let circuit = GraphColoringCircuit::<Fp, 5, 3>::try_new(edges(), [1, 2, 3, 3, 2])
+            .expect("the edges are between vertices of the graph");
+        let instance = circuit.instance();
+        assert_rejected(POW_2_EXP_MAX_ROWS, &circuit, vec![instance]);
+
+        // The vertex 3 has a color out of `1..=3`.
+        let circuit = GraphColoringCircuit::<Fp, 5, 3>::try_new(edges(), [1, 2, 3, 4, 2])
+            .expect("the edges are between vertices of the graph");
+        let instance = circuit.instance();
+        assert_rejected(POW_2_EXP_MAX_ROWS, &circuit, vec![instance]);
+    }
+
+    #[test]
+    /// Test that a proof does not verify against a different edge list.
+    fn mock_graph_coloring_wrong_edges() {
+        let circuit = GraphColoringCircuit::<Fp, 5, 3>::try_new(edges(), [1, 2, 3, 1, 2])
+            .expect("the edges are between vertices of the graph");
+        let mut instance = circuit.instance();
+        instance[0] = Fp::from(3);
+        assert_rejected(POW_2_EXP_MAX_ROWS, &circuit, vec![instance]);
+    }
+
+    #[test]
+    /// Test that edges with endpoints outside of the graph are refused.
+    fn graph_coloring_try_new() {
+        assert!(GraphColoringCircuit::<Fp, 3, 3>::try_new(vec![(0, 1), (1, 2)], [1; 3]).is_ok());
+        assert!(GraphColoringCircuit::<Fp, 3, 3>::try_new(vec![(0, 1), (1, 3)], [1; 3]).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    permutation_chip::{PConfig, PermutationChip},
+    utilities::inverse_permutation,
+    Number,
+};
+
+use halo2_proofs::{
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Column, ConstraintSystem, Error, Instance},
+};
+
+/// A circuit that proves that the output values are the input values
+/// permuted by a secret permutation `p`, which is an involution,
+/// i.e. such that applying `p` twice gives back the input values.
+///
+/// The permutation is applied twice with the same swaps, and the values
+/// obtained at the end are constrained to be equal to the input values.
+/// If some input values are repeated, the circuit only proves that
+/// `p` is an involution up to swaps of equal values.
+///
+/// The values obtained after the first application are exposed
+/// in the instance column.
+pub struct InvolutionCircuit<F: ff::Field, const N_OBJECTS: usize> {
+    input_items: [Value<F>; N_OBJECTS],
+    permutation: [usize; N_OBJECTS],
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> InvolutionCircuit<F, N_OBJECTS> {
+    /// Builds a circuit without checking its inputs.
+    /// It is up to the caller to guarantee that `permutation` is a permutation.
+    /// Whether it is an involution is checked by the circuit,
+    /// see `is_involution` for the same check out of the circuit.
+    pub fn new_unchecked(
+        input_items: [Value<F>; N_OBJECTS],
+        permutation: [usize; N_OBJECTS],
+    ) -> Self {
+        Self {
+            input_items,
+            permutation,
+        }
+    }
+
+    /// The public output the circuit proves against, i.e.
+    /// the input items after the permutation has been applied once.
+    pub fn expected_public_output(&self) -> [Value<F>; N_OBJECTS] {
+        inverse_permutation(self.permutation).map(|idx| self.input_items[idx])
+    }
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> Default for InvolutionCircuit<F, N_OBJECTS> {
+    fn default() -> Self {
+        Self::new_unchecked(
+            [Value::unknown(); N_OBJECTS],
+            core::array::from_fn(|idx| idx),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InvolutionConfig<const N_OBJECTS: usize> {
+    pconfig: PConfig<N_OBJECTS>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    instance: Column<Instance>,
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<F>
+    for InvolutionCircuit<F, N_OBJECTS>
+{
+    type Config = InvolutionConfig<N_OBJECTS>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let item_columns = [(); N_OBJECTS].map(|_| meta.advice_column());
+        let swap_selector_columns = Vec::from_iter(
+            (0..PermutationChip::<N_OBJECTS, F>::recommended_swap_selector_columns(N_OBJECTS))
+                .map(|_| meta.advice_column()),
+        );
+        // The swap selectors of the two applications are constrained to be equal.
+        for col in swap_selector_columns.iter() {
+            meta.enable_equality(*col);
+        }
+
+        InvolutionConfig {
+            pconfig: PermutationChip::configure(meta, item_columns, swap_selector_columns),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let permutation_chip = PermutationChip::<N_OBJECTS, F>::construct(config.pconfig);
+
+        let input_cells = layouter.namespace(|| "input values").assign_region(
+            || "input values",
+            |mut region| {
+                let item_columns = permutation_chip.config().get_item_columns();
+                let mut cells = vec![];
+                for (idx, value) in self.input_items.into_iter().enumerate() {
+                    cells.push(
+                        region
+                            .assign_advice(
+                                || format!("{idx}-th input value"),
+                                item_columns[idx],
+                                0,
+                                || value,
+                            )
+                            .map(Number)?,
+                    );
+                }
+                Ok(core::array::from_fn(|idx| cells[idx].clone()))
+            },
+        )?;
+
+        let output_cells = permutation_chip.apply_involution(
+            layouter.namespace(|| "applying the involution"),
+            input_cells,
+            self.permutation,
+        )?;
+
+        let mut output_layouter = layouter.namespace(|| "public output assignment");
+        for (idx, cell) in output_cells.iter().enumerate() {
+            output_layouter.constrain_instance(cell.cell(), config.instance, idx)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied, is_involution};
+    use halo2_proofs::pasta::Fp;
+
+    const N_OBJECTS: usize = 5;
+    const K: u32 = 5;
+
+    fn objects() -> [Value<Fp>; N_OBJECTS] {
+        core::array::from_fn(|n| Value::known(Fp::from(n as u64)))
+    }
+
+    /// The values obtained by applying `p` once to `objects()`.
+    fn instance(p: [usize; N_OBJECTS]) -> Vec<Vec<Fp>> {
+        vec![Vec::from(
+            inverse_permutation(p).map(|x| Fp::from(x as u64)),
+        )]
+    }
+
+    #[test]
+    /// Test that involutions, such as transpositions, are accepted,
+    /// and that a 3-cycle is rejected, even with the right public output.
+    fn mock_involution() {
+        for p in [[0, 1, 2, 3, 4], [3, 1, 2, 0, 4], [1, 0, 2, 4, 3]] {
+            assert!(is_involution(p));
+            let circuit = InvolutionCircuit::<Fp, N_OBJECTS>::new_unchecked(objects(), p);
+            assert_satisfied(K, &circuit, instance(p));
+        }
+
+        let three_cycle = [1, 2, 0, 3, 4];
+        assert!(!is_involution(three_cycle));
+        let circuit = InvolutionCircuit::<Fp, N_OBJECTS>::new_unchecked(objects(), three_cycle);
+        assert_rejected(K, &circuit, instance(three_cycle));
+    }
+}
@@ -1,21 +1,93 @@
 mod permutation_circuit;
 pub use permutation_circuit::PermutationCircuit;
 
+mod committed_permutation_circuit;
+pub use committed_permutation_circuit::CommittedPermutationCircuit;
+
+mod composed_permutation_circuit;
+pub use composed_permutation_circuit::ComposedPermutationCircuit;
+
+mod involution_circuit;
+pub use involution_circuit::InvolutionCircuit;
+
 mod sudoku_circuit;
-pub use sudoku_circuit::SudokuCircuit;
+pub use sudoku_circuit::{OrderedSudokuCircuit, SudokuCircuit, ValidatedSymbols};
+
+mod committed_sudoku_circuit;
+pub use committed_sudoku_circuit::{CommittedSudokuCircuit, FINGERPRINT_BASE};
+
+mod sudoku_part_circuit;
+pub use sudoku_part_circuit::SudokuPartCircuit;
 
 mod truncated_factorial_circuit;
-pub use truncated_factorial_circuit::TruncatedFactorialCircuit;
+pub use truncated_factorial_circuit::{
+    BoundedTruncatedFactorialCircuit, TruncatedFactorialCircuit,
+};
+
+mod fibonacci_circuit;
+pub use fibonacci_circuit::FibonacciCircuit;
+
+mod n_queens_circuit;
+pub use n_queens_circuit::NQueensCircuit;
+
+mod set_membership_circuit;
+pub use set_membership_circuit::SetMembershipCircuit;
+
+mod magic_square_circuit;
+pub use magic_square_circuit::MagicSquareCircuit;
+
+mod graph_coloring_circuit;
+pub use graph_coloring_circuit::GraphColoringCircuit;
+
+mod shuffle_circuit;
+pub use shuffle_circuit::ShuffleCircuit;
+
+/// This chip implements a gate that enforces a cell
+/// to be the sum of two other cells.
+pub mod add_chip;
+
+/// This chip implements a gate that decomposes a number
+/// in a fixed amount of bits.
+pub mod bit_decomposition_chip;
+
+/// This chip implements a gate that compares two numbers,
+/// interpreted as integers.
+pub mod comparison_chip;
+
+/// This chip enforces an array of numbers, interpreted
+/// as integers, to be pairwise distinct.
+pub mod distinctness_chip;
+
+/// This module implements a chip that, given two input numbers `f[0]` and `f[1]`,
+/// forces the output cell to be equal to the `N`-th term of the sequence
+/// such that `f[i + 2] = f[i + 1] + f[i]`.
+pub mod fibonacci_chip;
+
+/// This gadget enforces lines of cells to contain every one
+/// of a fixed set of symbols exactly once, as the columns,
+/// rows, and regions of a sudoku grid do.
+pub mod line_permutation_gadget;
 
 /// This chip implements a gate that enforces two
 /// sets of values to be a permutation of each other.
 pub mod permutation_chip;
 
+/// This chip enforces a number to be in the range `[0, 2^BITS)`.
+pub mod range_check_chip;
+
+/// This chip enforces a number to be equal to one of
+/// a fixed set of symbols, without revealing which one.
+pub mod set_membership_chip;
+
 /// This chip implements a gate that enforces two
 /// grids to be a couple of compatible problem-solution
 /// sudoku grids.
 pub mod sudoku_problem_chip;
 
+/// This chip implements a gate that enforces a row of cells
+/// to sum up to an expected value.
+pub mod sum_chip;
+
 /// This module implements a chip that, given an input number `N_FACTORS`,
 /// given `F: ff::Field`, and
 /// given an input number `n: F`, forces the output cell to be equal to
@@ -30,12 +102,126 @@ pub mod truncated_factorial_chip;
 pub mod utilities;
 
 /// A variable representing a number.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Number<F: ff::Field>(halo2_proofs::circuit::AssignedCell<F, F>);
 
+impl<F: ff::Field> Number<F> {
+    /// The value assigned to the cell, if known.
+    pub fn value_field(&self) -> halo2_proofs::circuit::Value<F> {
+        self.0.value().copied()
+    }
+
+    /// The value assigned to the cell, if it is known.
+    ///
+    /// This only reads the witness out of the cell,
+    /// and does not impose any constraint on it.
+    pub fn known_value(&self) -> Option<F> {
+        let mut known_value = None;
+        self.value_field().map(|value| known_value = Some(value));
+        known_value
+    }
+
+    /// Applies `f` to the value assigned to the cell.
+    ///
+    /// As for `known_value`, no constraint is imposed on the result.
+    pub fn map_value<W>(&self, f: impl FnOnce(F) -> W) -> halo2_proofs::circuit::Value<W> {
+        self.value_field().map(f)
+    }
+
+    /// Unwraps the cell the number is assigned to.
+    pub fn into_inner(self) -> halo2_proofs::circuit::AssignedCell<F, F> {
+        self.0
+    }
+}
+
 impl<F: ff::Field> std::ops::Deref for Number<F> {
     type Target = halo2_proofs::circuit::AssignedCell<F, F>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
+
+impl<F: ff::Field> From<halo2_proofs::circuit::AssignedCell<F, F>> for Number<F> {
+    fn from(value: halo2_proofs::circuit::AssignedCell<F, F>) -> Self {
+        Self(value)
+    }
+}
+
+impl<F: ff::Field> std::fmt::Debug for Number<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+
+    /// A circuit that assigns a single cell, and checks the `Number` API on it.
+    struct NumberApiCircuit {
+        value: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for NumberApiCircuit {
+        type Config = Column<Advice>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            meta.advice_column()
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "number api",
+                |mut region| {
+                    let cell = region.assign_advice(|| "number", config, 0, || self.value)?;
+                    let number = Number::from(cell);
+
+                    number
+                        .value_field()
+                        .zip(self.value)
+                        .assert_if_known(|(a, b)| a == b);
+
+                    let mut known_value = None;
+                    self.value.map(|value| known_value = Some(value));
+                    assert_eq!(number.known_value(), known_value);
+
+                    number
+                        .map_value(|value| value.double())
+                        .zip(self.value)
+                        .assert_if_known(|(a, b)| *a == b.double());
+
+                    let debug_output = format!("{number:?}");
+                    assert_eq!(debug_output, format!("{:?}", number.clone().into_inner()));
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn number_api() {
+        let circuit = NumberApiCircuit {
+            value: Value::known(Fp::from(7)),
+        };
+        let prover = MockProver::run(4, &circuit, vec![]).expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use halo2_proofs::{circuit::Layouter, plonk::Error};
+
+use crate::{
+    permutation_chip::{PConfig, PermutationChip},
+    Number,
+};
+
+/// A gadget that enforces lines of cells, e.g. the rows, columns, and regions
+/// of a sudoku grid, to contain every one of `SIZE` symbols exactly once.
+///
+/// Each line is permuted by a `PermutationChip`, so that it gets aligned to
+/// the symbols, and the permuted cells are constrained to be equal to them.
+/// The permutation is computed out of the values in the cells, so the prover
+/// does not have to provide it.
+#[derive(Debug, Clone)]
+pub(crate) struct LinePermutationGadget<const SIZE: usize, F: ff::Field> {
+    permutation_chip: PermutationChip<SIZE, F>,
+}
+
+impl<const SIZE: usize, F: ff::Field> LinePermutationGadget<SIZE, F> {
+    pub fn construct(config: PConfig<SIZE>) -> Self {
+        Self {
+            permutation_chip: PermutationChip::construct(config),
+        }
+    }
+
+    /// Same as `enforce_lines_are_symbol_permutations`, but the ordinal of a
+    /// symbol, i.e. its index in `symbol_cells`, is given by `symbol_ordinal`,
+    /// rather than found out of the byte representations of the symbols.
+    /// `symbol_ordinal` returns `None` for values that are not symbols.
+    ///
+    /// `symbol_ordinal` only drives the witness generation: the constraints
+    /// are the same whatever ordering is used, so a wrong ordering cannot make
+    /// a line that is not a permutation of the symbols pass. However, it has to
+    /// map the `idx`-th symbol to `idx`, and in particular it has to be injective
+    /// over the symbols, otherwise valid lines are rejected.
+    pub fn enforce_lines_are_symbol_permutations_with_ordering(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lines: &[[Number<F>; SIZE]],
+        symbol_cells: &[Number<F>; SIZE],
+        symbol_ordinal: impl Fn(&F) -> Option<usize>,
+    ) -> Result<(), Error> {
+        // When the values are not known, e.g. during key generation,
+        // or when a line is not a permutation of the symbols,
+        // the identity permutation is laid out in its place,
+        // so that the layout of the circuit does not depend on the witness.
+        // In the latter case, the equality constraints below are not satisfied.
+        let permutations = Vec::from_iter(lines.iter().map(|line| {
+            Self::aligning_permutation(line, &symbol_ordinal)
+                .unwrap_or(core::array::from_fn(|idx| idx))
+        }));
+
+        let aligned_lines = self.permutation_chip.apply_permutations_batched(
+            layouter.namespace(|| "aligning the lines to the symbols"),
+            lines,
+            &permutations,
+        )?;
+
+        layouter.assign_region(
+            || "equality of the aligned lines to the symbols",
+            |mut region| {
+                for aligned_line in aligned_lines.iter() {
+                    for (left, right) in aligned_line.iter().zip(symbol_cells.iter()) {
+                        region.constrain_equal(left.cell(), right.cell())?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// If the values of all the cells are known, and `line_cells` contains
+    /// every symbol exactly once, returns the permutation that aligns
+    /// `line_cells` to the symbols, i.e. the permutation that maps
+    /// the index of every cell of the line to the ordinal of its symbol.
+    fn aligning_permutation(
+        line_cells: &[Number<F>; SIZE],
+        symbol_ordinal: impl Fn(&F) -> Option<usize>,
+    ) -> Option<[usize; SIZE]> {
+        let mut seen = [false; SIZE];
+        let mut permutation = [0; SIZE];
+        for (idx, cell) in line_cells.iter().enumerate() {
+            let ordinal = symbol_ordinal(&cell.known_value()?)?;
+            if ordinal >= SIZE || seen[ordinal] {
+                return None;
+            }
+            seen[ordinal] = true;
+            permutation[idx] = ordinal;
+        }
+        Some(permutation)
+    }
+}
+
+impl<const SIZE: usize, F: ff::PrimeField> LinePermutationGadget<SIZE, F> {
+    /// Constrains each of `lines` to be a permutation of `symbol_cells`.
+    /// The permutations are laid out in a single region,
+    /// see `PermutationChip::apply_permutations_batched`.
+    pub fn enforce_lines_are_symbol_permutations(
+        &self,
+        layouter: impl Layouter<F>,
+        lines: &[[Number<F>; SIZE]],
+        symbol_cells: &[Number<F>; SIZE],
+    ) -> Result<(), Error> {
+        // If the symbols are not known, no line can be aligned to them.
+        let symbol_to_ordinal = symbol_cells
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| Some((cell.known_value()?.to_repr().as_ref().to_owned(), idx)))
+            .collect::<Option<BTreeMap<_, _>>>()
+            .unwrap_or_default();
+
+        self.enforce_lines_are_symbol_permutations_with_ordering(
+            layouter,
+            lines,
+            symbol_cells,
+            |value| symbol_to_ordinal.get(value.to_repr().as_ref()).copied(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied, RegionSequenceAssignment};
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        pasta::Fp,
+        plonk::{Circuit, Column, ConstraintSystem, Fixed},
+    };
+
+    const SIZE: usize = 5;
+
+    /// A circuit that proves that a secret line contains
+    /// every one of the symbols `1, ..., SIZE` exactly once.
+    struct LineCircuit {
+        line: [Value<Fp>; SIZE],
+    }
+
+    impl Circuit<Fp> for LineCircuit {
+        type Config = (PConfig<SIZE>, Column<Fixed>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                line: [Value::unknown(); SIZE],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let symbols_column = meta.fixed_column();
+            meta.enable_equality(symbols_column);
+
+            let item_columns = [(); SIZE].map(|_| meta.advice_column());
+            let swap_selector_columns = (0..SIZE / 2).map(|_| meta.advice_column()).collect();
+
+            (
+                PermutationChip::configure(meta, item_columns, swap_selector_columns),
+                symbols_column,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (config, symbols_column): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let item_columns = *config.get_item_columns();
+            let gadget = LinePermutationGadget::<SIZE, Fp>::construct(config);
+
+            let symbol_cells = layouter.assign_region(
+                || "symbols",
+                |mut region| {
+                    region.assign_array_to_column::<SIZE, _>(
+                        symbols_column,
+                        0,
+                        core::array::from_fn(|idx| Value::known(Fp::from(idx as u64 + 1))),
+                    )
+                },
+            )?;
+
+            let [line_cells] = layouter.assign_region(
+                || "line",
+                |mut region| {
+                    region.assign_grid_to_columns_transposed(
+                        item_columns,
+                        0,
+                        self.line.map(|value| [value]),
+                    )
+                },
+            )?;
+
+            gadget.enforce_lines_are_symbol_permutations(
+                layouter.namespace(|| "line permutation"),
+                &[line_cells],
+                &symbol_cells,
+            )
+        }
+    }
+
+    const K: u32 = 5;
+
+    #[test]
+    /// Test that a shuffled line of symbols is accepted,
+    /// and that lines with a repeated symbol, or a value
+    /// that is not a symbol, are rejected.
+    fn mock_line_permutation_gadget() {
+        let line = |values: [u64; SIZE]| LineCircuit {
+            line: values.map(|value| Value::known(Fp::from(value))),
+        };
+
+        assert_satisfied(K, &line([3, 5, 1, 4, 2]), vec![]);
+        assert_satisfied(K, &line([1, 2, 3, 4, 5]), vec![]);
+        assert_rejected(K, &line([3, 5, 1, 4, 3]), vec![]);
+        assert_rejected(K, &line([3, 5, 1, 4, 6]), vec![]);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    add_chip::{AConfig, AddChip},
+    permutation_chip::{PConfig, PermutationChip},
+    utilities::RegionSequenceAssignment,
+    Number,
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use halo2_proofs::{
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Column, ConstraintSystem, Error, Fixed, Instance},
+};
+
+/// A circuit that proves the knowledge of a `N x N` magic square,
+/// i.e. of a grid containing every number in `1..=N_SQUARED` exactly once,
+/// such that every column, every row, and both diagonals sum to the same
+/// magic constant, which is made public.
+///
+/// `N_SQUARED` has to be equal to `N * N`.
+/// The cell in the `col_idx`-th column and in the `row_idx`-th row
+/// of the square is `square[col_idx][row_idx]`.
+pub struct MagicSquareCircuit<F: ff::PrimeField, const N: usize, const N_SQUARED: usize> {
+    square: [[Value<F>; N]; N],
+    /// The permutation that sorts the entries of the square,
+    /// read column by column, into `1, ..., N_SQUARED`.
+    sorting_permutation: [usize; N_SQUARED],
+}
+
+impl<F: ff::PrimeField, const N: usize, const N_SQUARED: usize>
+    MagicSquareCircuit<F, N, N_SQUARED>
+{
+    /// Builds a circuit from a square, checking that its entries are
+    /// the numbers `1..=N_SQUARED`, each appearing once.
+    /// The sums of the lines are not checked here, since ruling out
+    /// squares that are not magic is the whole point of the circuit.
+    pub fn try_new(square: [[usize; N]; N]) -> Result<Self, ()> {
+        // We check that the compile time constants are consistent
+        if N_SQUARED != N * N {
+            return Err(());
+        }
+
+        // Since there are `N_SQUARED` entries, if none of them is repeated
+        // then each of `1..=N_SQUARED` appears once.
+        let mut sorting_permutation = [0; N_SQUARED];
+        let mut seen = [false; N_SQUARED];
+        for (idx, entry) in square.into_iter().flatten().enumerate() {
+            if entry == 0 || entry > N_SQUARED || seen[entry - 1] {
+                return Err(());
+            }
+            seen[entry - 1] = true;
+            // The permutation chip puts the `idx`-th item in the position
+            // `sorting_permutation[idx]`.
+            sorting_permutation[idx] = entry - 1;
+        }
+
+        Ok(Self {
+            square: square.map(|column| column.map(|entry| Value::known(F::from(entry as u64)))),
+            sorting_permutation,
+        })
+    }
+
+    /// The magic constant of a `N x N` magic square,
+    /// i.e. the sum of `1..=N_SQUARED` divided by `N`.
+    pub fn magic_constant() -> u64 {
+        (N * (N_SQUARED + 1) / 2) as u64
+    }
+
+    /// The lines of the square that have to sum to the magic constant,
+    /// that is, the columns, the rows, and the two diagonals, in this order.
+    ///
+    /// Each line consists of the `(col_idx, row_idx)` positions of its cells.
+    fn lines() -> Vec<[(usize, usize); N]> {
+        let mut lines = Vec::with_capacity(2 * N + 2);
+        for col_idx in 0..N {
+            lines.push(core::array::from_fn(|row_idx| (col_idx, row_idx)));
+        }
+        for row_idx in 0..N {
+            lines.push(core::array::from_fn(|col_idx| (col_idx, row_idx)));
+        }
+        lines.push(core::array::from_fn(|idx| (idx, idx)));
+        lines.push(core::array::from_fn(|idx| (idx, N - 1 - idx)));
+        lines
+    }
+}
+
+impl<F: ff::PrimeField, const N: usize, const N_SQUARED: usize> Default
+    for MagicSquareCircuit<F, N, N_SQUARED>
+{
+    fn default() -> Self {
+        Self {
+            square: [[Value::unknown(); N]; N],
+            sorting_permutation: core::array::from_fn(|idx| idx),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MagicSquareConfig<const N_SQUARED: usize> {
+    pconfig: PConfig<N_SQUARED>,
+    aconfig: AConfig,
+    /// The column where the numbers `1, ..., N_SQUARED` are laid out.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    entries_column: Column<Fixed>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    instance: Column<Instance>,
+}
+
+impl<F: ff::PrimeField, const N: usize, const N_SQUARED: usize> halo2_proofs::plonk::Circuit<F>
+    for MagicSquareCircuit<F, N, N_SQUARED>
+{
+    type Config = MagicSquareConfig<N_SQUARED>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        assert_eq!(N * N, N_SQUARED);
+        assert!(N >= 2, "The addition chip reuses three item columns.");
+
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let entries_column = meta.fixed_column();
+        meta.enable_equality(entries_column);
+
+        let item_columns = [(); N_SQUARED].map(|_| meta.advice_column());
+        let swap_selector_columns = (0..N_SQUARED / 2).map(|_| meta.advice_column()).collect();
+
+        MagicSquareConfig {
+            pconfig: PermutationChip::configure(meta, item_columns, swap_selector_columns),
+            aconfig: AddChip::configure(meta, [item_columns[0], item_columns[1], item_columns[2]]),
+            entries_column,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let permutation_chip = PermutationChip::<N_SQUARED, F>::construct(config.pconfig);
+        let add_chip = AddChip::<F>::construct(config.aconfig);
+
+        // We assign the entries of the square, column by column,
+        // to the first row of the item columns of the permutation chip.
+        let square_cells = layouter.namespace(|| "square").assign_region(
+            || "square",
+            |mut region| {
+                let item_columns = permutation_chip.config().get_item_columns();
+                let mut cells = vec![];
+                for (idx, entry) in self.square.iter().flatten().enumerate() {
+                    cells.push(
+                        region
+                            .assign_advice(
+                                || format!("{idx}-th entry of the square"),
+                                item_columns[idx],
+                                0,
+                                || *entry,
+                            )
+                            .map(Number)?,
+                    );
+                }
+                Ok(cells)
+            },
+        )?;
+        let cell_at =
+            |(col_idx, row_idx): (usize, usize)| square_cells[col_idx * N + row_idx].clone();
+
+        // The entries of the square, once sorted,
+        // have to be equal to the numbers `1, ..., N_SQUARED`.
+        let sorted_cells = permutation_chip.apply_permutation(
+            layouter.namespace(|| "sorting the entries"),
+            core::array::from_fn(|idx| square_cells[idx].clone()),
+            self.sorting_permutation,
+        )?;
+        layouter.namespace(|| "distinct entries").assign_region(
+            || "distinct entries",
+            |mut region| {
+                let entries = region.assign_array_to_column::<N_SQUARED, _>(
+                    config.entries_column,
+                    0,
+                    core::array::from_fn(|idx| Value::known(F::from(idx as u64 + 1))),
+                )?;
+                for (sorted, entry) in sorted_cells.iter().zip(entries.iter()) {
+                    region.constrain_equal(sorted.cell(), entry.cell())?;
+                }
+                Ok(())
+            },
+        )?;
+
+        // Every line has to sum to the public magic constant.
+        for (line_idx, line) in Self::lines().into_iter().enumerate() {
+            let mut sum = cell_at(line[0]);
+            for position in line.into_iter().skip(1) {
+                sum = add_chip.add(
+                    layouter.namespace(|| format!("sum of line {line_idx}")),
+                    sum,
+                    cell_at(position),
+                )?;
+            }
+            layouter
+                .namespace(|| format!("magic constant of line {line_idx}"))
+                .constrain_instance(sum.cell(), config.instance, 0)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied};
+    use halo2_proofs::pasta::Fp;
+
+    const POW_2_EXP_MAX_ROWS: u32 = 6;
+
+    /// The Lo Shu square.
+    const LO_SHU: [[usize; 3]; 3] = [[2, 7, 6], [9, 5, 1], [4, 3, 8]];
+
+    fn magic_constant_instance() -> Vec<Vec<Fp>> {
+        vec![vec![Fp::from(
+            MagicSquareCircuit::<Fp, 3, 9>::magic_constant(),
+        )]]
+    }
+
+    #[test]
+    /// Test that the Lo Shu square is accepted.
+    fn mock_magic_square() {
+        let circuit =
+            MagicSquareCircuit::<Fp, 3, 9>::try_new(LO_SHU).expect("the entries are distinct");
+        assert_satisfied(POW_2_EXP_MAX_ROWS, &circuit, magic_constant_instance());
+    }
+
+    #[test]
+    /// Test that a square with distinct entries, whose lines
+    /// do not sum to the same constant, is rejected.
+    fn mock_magic_square_bad_sums() {
+        // We swap the last two entries of the last column.
+        let square = [[2, 7, 6], [9, 5, 1], [4, 8, 3]];
+        let circuit =
+            MagicSquareCircuit::<Fp, 3, 9>::try_new(square).expect("the entries are distinct");
+        assert_rejected(POW_2_EXP_MAX_ROWS, &circuit, magic_constant_instance());
+    }
+
+    #[test]
+    /// Test that squares whose entries are not `1..=N_SQUARED` are refused.
+    fn magic_square_try_new() {
+        assert!(
+            MagicSquareCircuit::<Fp, 3, 9>::try_new([[2, 7, 6], [9, 5, 1], [4, 3, 7]]).is_err()
+        );
+        assert!(
+            MagicSquareCircuit::<Fp, 3, 9>::try_new([[2, 7, 6], [9, 5, 1], [4, 3, 0]]).is_err()
+        );
+        assert!(
+            MagicSquareCircuit::<Fp, 3, 9>::try_new([[2, 7, 6], [9, 5, 1], [4, 3, 10]]).is_err()
+        );
+        assert!(MagicSquareCircuit::<Fp, 3, 8>::try_new(LO_SHU).is_err());
+        // An entry repeated more times than a byte can count.
+        assert!(MagicSquareCircuit::<Fp, 16, 256>::try_new([[1; 16]; 16]).is_err());
+    }
+}
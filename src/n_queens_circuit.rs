@@ -0,0 +1,263 @@
+use crate::{
+    permutation_chip::{PConfig, PermutationChip},
+    utilities::{inverse_permutation, is_permutation, RegionSequenceAssignment},
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+/// A circuit that proves that the public placement of `N` queens on
+/// a `N x N` chessboard is such that no two queens attack each other.
+///
+/// The queen in the `i`-th row sits in the column `placement[i]`.
+/// Since `placement` is a permutation, no two queens share a row or a column,
+/// so the circuit only has to take care of the diagonals.
+pub struct NQueensCircuit<F: ff::PrimeField, const N: usize> {
+    placement: [usize; N],
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: ff::PrimeField, const N: usize> NQueensCircuit<F, N> {
+    /// Builds a circuit from a placement, without checking it.
+    /// It is up to the caller to guarantee that `placement` is a permutation,
+    /// and that there are at least two queens.
+    pub fn new_unchecked(placement: [usize; N]) -> Self {
+        Self {
+            placement,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Builds a circuit from a placement, checking that it is a permutation.
+    /// Diagonal conflicts are not checked here, since ruling them out
+    /// is the whole point of the circuit.
+    ///
+    /// Fails if `N < 2`, since the circuit gives the permutation chip
+    /// `N / 2` swap selector columns, and the chip needs at least one.
+    pub fn try_new(placement: [usize; N]) -> Result<Self, ()> {
+        if N < 2 || !is_permutation::<N>(placement) {
+            return Err(());
+        }
+        Ok(Self::new_unchecked(placement))
+    }
+}
+
+impl<F: ff::PrimeField, const N: usize> Default for NQueensCircuit<F, N> {
+    fn default() -> Self {
+        Self::new_unchecked(core::array::from_fn(|i| i))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NQueensConfig<const N: usize> {
+    pconfig: PConfig<N>,
+    /// The column where the row indices `0, ..., N - 1` are laid out.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    row_indices_column: Column<Fixed>,
+    /// The columns where the couples of column indices `(p[i], p[j])`,
+    /// along with the witness of their diagonal distinctness, are laid out.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    diagonal_columns: [Column<Advice>; 3],
+    /// The column where the distance `j - i` between the rows of two queens is laid out.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    row_distance_column: Column<Fixed>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_diag: Selector,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    instance: Column<Instance>,
+}
+
+impl<F: ff::PrimeField, const N: usize> halo2_proofs::plonk::Circuit<F> for NQueensCircuit<F, N> {
+    type Config = NQueensConfig<N>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let row_indices_column = meta.fixed_column();
+        meta.enable_equality(row_indices_column);
+
+        let item_columns = [(); N].map(|_| meta.advice_column());
+        let swap_selector_columns = (0..N / 2).map(|_| meta.advice_column()).collect();
+
+        let diagonal_columns = [(); 3].map(|_| meta.advice_column());
+        for col in diagonal_columns.iter().take(2) {
+            meta.enable_equality(*col);
+        }
+        let row_distance_column = meta.fixed_column();
+
+        let s_diag = meta.selector();
+
+        meta.create_gate("distinct diagonals", |meta| {
+            let s_diag = meta.query_selector(s_diag);
+            let [left, right, inverse] =
+                diagonal_columns.map(|col| meta.query_advice(col, Rotation::cur()));
+            let distance = meta.query_fixed(row_distance_column);
+
+            // Two queens share a diagonal if and only if
+            // `p[i] - p[j] == j - i` or `p[i] - p[j] == i - j`,
+            // that is, if and only if `(p[i] - p[j])^2 - (j - i)^2 == 0`.
+            // We prove that the quantity is not zero by exhibiting its inverse.
+            let column_distance = left - right;
+            vec![
+                s_diag
+                    * ((column_distance.clone() * column_distance - distance.clone() * distance)
+                        * inverse
+                        - Expression::Constant(F::ONE)),
+            ]
+        });
+
+        NQueensConfig {
+            pconfig: PermutationChip::configure(meta, item_columns, swap_selector_columns),
+            row_indices_column,
+            diagonal_columns,
+            row_distance_column,
+            s_diag,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let permutation_chip = PermutationChip::<N, F>::construct(config.pconfig);
+
+        // We declare the row indices, which are the items to be permuted.
+        let row_indices = layouter.namespace(|| "row indices").assign_region(
+            || "row indices",
+            |mut region| {
+                region.assign_array_to_column::<N, _>(
+                    config.row_indices_column,
+                    0,
+                    core::array::from_fn(|i| Value::known(F::from(i as u64))),
+                )
+            },
+        )?;
+
+        // The permutation chip puts the `i`-th item in the position
+        // `permutation[i]`, so to obtain the row indices laid out as
+        // `placement`, we have to feed it the inverse permutation.
+        let placement_cells = permutation_chip.apply_permutation(
+            layouter.namespace(|| "queens placement"),
+            row_indices,
+            inverse_permutation(self.placement),
+        )?;
+
+        layouter.namespace(|| "distinct diagonals").assign_region(
+            || "distinct diagonals",
+            |mut region| {
+                let [left_column, right_column, inverse_column] = config.diagonal_columns;
+
+                let pairs = (0..N).flat_map(|i| (i + 1..N).map(move |j| (i, j)));
+                for (row, (i, j)) in pairs.enumerate() {
+                    config.s_diag.enable(&mut region, row)?;
+
+                    let left = placement_cells[i].copy_advice(
+                        || format!("column of queen {i}"),
+                        &mut region,
+                        left_column,
+                        row,
+                    )?;
+                    let right = placement_cells[j].copy_advice(
+                        || format!("column of queen {j}"),
+                        &mut region,
+                        right_column,
+                        row,
+                    )?;
+
+                    let distance = F::from((j - i) as u64);
+                    region.assign_fixed(
+                        || format!("row distance between queens {i} and {j}"),
+                        config.row_distance_column,
+                        row,
+                        || Value::known(distance),
+                    )?;
+
+                    // If the queens share a diagonal, there is no inverse,
+                    // and we assign zero, which does not satisfy the gate.
+                    let inverse = left.value().zip(right.value()).map(|(left, right)| {
+                        let column_distance = *left - *right;
+                        Option::<F>::from((column_distance.square() - distance.square()).invert())
+                            .unwrap_or(F::ZERO)
+                    });
+                    region.assign_advice(
+                        || format!("diagonal distinctness witness for queens {i} and {j}"),
+                        inverse_column,
+                        row,
+                        || inverse,
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+
+        let mut output_layouter = layouter.namespace(|| "public placement");
+        for (idx, cell) in placement_cells.iter().map(|n| n.cell()).enumerate() {
+            output_layouter.constrain_instance(cell, config.instance, idx)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    const POW_2_EXP_MAX_ROWS: u32 = 5;
+
+    fn placement_instance<const N: usize>(placement: [usize; N]) -> Vec<Vec<Fp>> {
+        vec![Vec::from(placement.map(|x| Fp::from(x as u64)))]
+    }
+
+    #[test]
+    /// Test that a valid solution to the 6-queens problem is accepted.
+    fn mock_n_queens() {
+        let placement = [1, 3, 5, 0, 2, 4];
+        let circuit =
+            NQueensCircuit::<Fp, 6>::try_new(placement).expect("the placement is a permutation");
+
+        let prover = MockProver::run(POW_2_EXP_MAX_ROWS, &circuit, placement_instance(placement))
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+    }
+
+    #[test]
+    /// Test that a placement with two queens on the same diagonal is rejected,
+    /// even though it is a permutation.
+    fn mock_n_queens_diagonal_conflict() {
+        // The queens in the first two rows share a diagonal.
+        let placement = [0, 1, 3, 5, 2, 4];
+        let circuit =
+            NQueensCircuit::<Fp, 6>::try_new(placement).expect("the placement is a permutation");
+
+        let prover = MockProver::run(POW_2_EXP_MAX_ROWS, &circuit, placement_instance(placement))
+            .expect("Proof generation goes wrong");
+        assert!(prover.verify().is_err(), "Bogus placement was accepted");
+    }
+
+    #[test]
+    /// Test that a placement that is not a permutation is refused.
+    fn n_queens_try_new() {
+        assert!(NQueensCircuit::<Fp, 4>::try_new([1, 3, 0, 2]).is_ok());
+        assert!(NQueensCircuit::<Fp, 4>::try_new([1, 3, 3, 2]).is_err());
+        assert!(NQueensCircuit::<Fp, 4>::try_new([1, 3, 4, 2]).is_err());
+        assert!(NQueensCircuit::<Fp, 1>::try_new([0]).is_err());
+        assert!(NQueensCircuit::<Fp, 0>::try_new([]).is_err());
+    }
+}
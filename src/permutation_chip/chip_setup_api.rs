@@ -6,40 +6,265 @@ impl<const N_OBJECTS: usize, F: ff::Field> PermutationChip<N_OBJECTS, F> {
     /// where `array` is an array of cells that represent `input_items`
     /// after `permutation` has been applied to them,
     /// i.e. such that, for every `i`, it holds
-    /// `array[i] = input_items[permutation[i]]`
+    /// `array[permutation[i]] = input_items[i]`
     pub fn apply_permutation(
         &self,
         mut layouter: impl Layouter<F>,
         input_items: [Number<F>; N_OBJECTS],
         permutation: [usize; N_OBJECTS],
     ) -> Result<[Number<F>; N_OBJECTS], Error> {
+        layouter
+            .assign_region(
+                || prefixed_name(self.name_prefix, "load input"),
+                |mut region| {
+                    apply_permutation_region_assignment(
+                        self,
+                        &input_items,
+                        permutation,
+                        None,
+                        &mut region,
+                        0,
+                    )
+                },
+            )
+            .map(|(output_items, _, _)| output_items)
+    }
+
+    /// Same as `apply_permutation`, but also returns a cell constrained
+    /// to be equal to the parity of `permutation`, i.e. `0` if the permutation
+    /// is even, and `1` if it is odd.
+    ///
+    /// `parity_config` has to be the output of `configure_parity`,
+    /// called on the configuration of this chip.
+    pub fn apply_permutation_with_parity(
+        &self,
+        mut layouter: impl Layouter<F>,
+        parity_config: &ParityConfig,
+        input_items: [Number<F>; N_OBJECTS],
+        permutation: [usize; N_OBJECTS],
+    ) -> Result<([Number<F>; N_OBJECTS], Number<F>), Error> {
+        layouter
+            .assign_region(
+                || prefixed_name(self.name_prefix, "load input"),
+                |mut region| {
+                    apply_permutation_region_assignment(
+                        self,
+                        &input_items,
+                        permutation,
+                        Some(parity_config),
+                        &mut region,
+                        0,
+                    )
+                },
+            )
+            .map(|(output_items, parity, _)| {
+                (
+                    output_items,
+                    parity.expect("we asked for the parity to be computed"),
+                )
+            })
+    }
+
+    /// Same as `apply_permutation`, but the permutation is derived from
+    /// the values of `input_items` and `target_items`, and the output cells
+    /// are constrained to be equal to `target_items`, so that the chip alone
+    /// enforces `target_items` to be a reordering of `input_items`.
+    ///
+    /// If the values are not known, or `target_items` is not a reordering of
+    /// `input_items`, the identity permutation is applied, so that the layout
+    /// does not depend on the witness. In the latter case,
+    /// the equality constraints are not satisfied.
+    pub fn apply_permutation_to_target(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input_items: [Number<F>; N_OBJECTS],
+        target_items: [Number<F>; N_OBJECTS],
+    ) -> Result<[Number<F>; N_OBJECTS], Error> {
+        let permutation = matching_permutation(&input_items, &target_items)
+            .unwrap_or(core::array::from_fn(|idx| idx));
+
+        let output_items = self.apply_permutation(
+            layouter.namespace(|| prefixed_name(self.name_prefix, "permutation to target")),
+            input_items,
+            permutation,
+        )?;
+
+        layouter.assign_region(
+            || prefixed_name(self.name_prefix, "equality to target"),
+            |mut region| {
+                for (output, target) in output_items.iter().zip(target_items.iter()) {
+                    region.constrain_equal(output.cell(), target.cell())?;
+                }
+                Ok(())
+            },
+        )?;
+
+        Ok(output_items)
+    }
+
+    /// Same as `apply_permutation`, but `permutations[idx]` is applied to
+    /// `inputs[idx]`, for every `idx`. All the permutations are laid out in
+    /// a single region, one after the other, each one taking
+    /// `rows_per_permutation` rows, instead of one region per permutation.
+    ///
+    /// The batch saves regions, not rows: the last item cell of a permutation
+    /// lies in the first item column of its last row, which the input row of
+    /// the next permutation needs, so no two permutations can share a row.
+    pub fn apply_permutations_batched(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: &[[Number<F>; N_OBJECTS]],
+        permutations: &[[usize; N_OBJECTS]],
+    ) -> Result<Vec<[Number<F>; N_OBJECTS]>, Error> {
+        if inputs.len() != permutations.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let rows_per_permutation = self.config.rows_per_permutation();
+
         layouter.assign_region(
-            || "load input",
-            |region| apply_permutation_region_assignment(self, &input_items, permutation, region),
+            || prefixed_name(self.name_prefix, "load batched inputs"),
+            |mut region| {
+                inputs
+                    .iter()
+                    .zip(permutations.iter())
+                    .enumerate()
+                    .map(|(idx, (input_items, permutation))| {
+                        apply_permutation_region_assignment(
+                            self,
+                            input_items,
+                            *permutation,
+                            None,
+                            &mut region,
+                            idx * rows_per_permutation,
+                        )
+                        .map(|(output_items, _, _)| output_items)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Same as `apply_permutation`, but the permutation is applied twice,
+    /// with the same swaps, and the final cells are constrained to be
+    /// equal to `input_items`, so that the chip enforces the permutation
+    /// to be an involution, at least on the values of `input_items`.
+    /// The cells obtained after the first application are returned.
+    ///
+    /// Both applications are laid out in a single region, and their swap
+    /// selectors are constrained to be equal, so the swap selector columns
+    /// of the chip need to have equality enabled.
+    pub fn apply_involution(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input_items: [Number<F>; N_OBJECTS],
+        permutation: [usize; N_OBJECTS],
+    ) -> Result<[Number<F>; N_OBJECTS], Error> {
+        let rows_per_permutation = self.config.rows_per_permutation();
+
+        layouter.assign_region(
+            || prefixed_name(self.name_prefix, "load involution input"),
+            |mut region| {
+                let (output_items, _, swap_selectors) = apply_permutation_region_assignment(
+                    self,
+                    &input_items,
+                    permutation,
+                    None,
+                    &mut region,
+                    0,
+                )?;
+                let (final_items, _, final_swap_selectors) = apply_permutation_region_assignment(
+                    self,
+                    &output_items,
+                    permutation,
+                    None,
+                    &mut region,
+                    rows_per_permutation,
+                )?;
+
+                for (first, second) in swap_selectors.iter().zip(final_swap_selectors.iter()) {
+                    region.constrain_equal(first.cell(), second.cell())?;
+                }
+                for (input, last) in input_items.iter().zip(final_items.iter()) {
+                    region.constrain_equal(input.cell(), last.cell())?;
+                }
+                Ok(output_items)
+            },
         )
     }
 }
 
+/// If the values of all the cells are known, and `target_items` is
+/// a reordering of `input_items`, returns the permutation that moves
+/// every input item to the position of an equal target item.
+/// Repeated values are matched to the target positions in order.
+fn matching_permutation<const N_OBJECTS: usize, F: ff::Field>(
+    input_items: &[Number<F>; N_OBJECTS],
+    target_items: &[Number<F>; N_OBJECTS],
+) -> Option<[usize; N_OBJECTS]> {
+    let target_values = target_items
+        .iter()
+        .map(Number::known_value)
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut used = [false; N_OBJECTS];
+    let mut permutation = [0; N_OBJECTS];
+    for (idx, input_item) in input_items.iter().enumerate() {
+        let input_value = input_item.known_value()?;
+        let position = (0..N_OBJECTS)
+            .find(|&position| !used[position] && target_values[position] == input_value)?;
+        used[position] = true;
+        permutation[idx] = position;
+    }
+    Some(permutation)
+}
+
 /// A helper function to be used in
 /// `PermutationChip::<N_OBJECTS, F>::apply_permutation`.
 /// Its main purpose is to increase readability by reducing indentation.
+///
+/// The permutation is laid out starting from the row `offset` of the region.
+/// Along with the output items and the optional parity,
+/// the swap selector cells are returned, in the order of the swap schedule.
 fn apply_permutation_region_assignment<const N_OBJECTS: usize, F: ff::Field>(
     chip: &PermutationChip<N_OBJECTS, F>,
     input_items: &[Number<F>; N_OBJECTS],
     permutation: [usize; N_OBJECTS],
-    mut region: Region<'_, F>,
-) -> Result<[Number<F>; N_OBJECTS], Error> {
+    parity_config: Option<&ParityConfig>,
+    region: &mut Region<'_, F>,
+    offset: usize,
+) -> Result<([Number<F>; N_OBJECTS], Option<Number<F>>, Vec<Number<F>>), Error> {
     // We enable the selector gate that activates all the constraints in
     // the permutation chip.
-    chip.config.s_perm.enable(&mut region, 0)?;
+    chip.config.s_perm.enable(region, offset)?;
+
+    // If required, we keep track of the parity of the swaps applied so far,
+    // starting from `0` in the first row of the parity column.
+    let assign_parity = |region: &mut Region<'_, F>, row_idx: usize, parity: bool| {
+        let config = parity_config.expect("the parity is only assigned if required");
+        region
+            .assign_advice(
+                || format!("parity after {row_idx} swaps"),
+                config.parity_column,
+                offset + row_idx,
+                || Value::known(if parity { F::ONE } else { F::ZERO }),
+            )
+            .map(Number)
+    };
+    let mut parity = false;
+    let mut parity_cell = None;
+    if let Some(config) = parity_config {
+        config.s_parity.enable(region, offset)?;
+        parity_cell = Some(assign_parity(region, 0, parity)?);
+    }
 
     // We load the input cells in the first row of the region.
     for (idx, input_item) in input_items.iter().enumerate().take(N_OBJECTS) {
         input_item.copy_advice(
             || "input items",
-            &mut region,
+            region,
             chip.config.item_columns[idx],
-            0,
+            offset,
         )?;
     }
 
@@ -49,18 +274,23 @@ fn apply_permutation_region_assignment<const N_OBJECTS: usize, F: ff::Field>(
     // The first row is entirely occupied by the input items of the circuit,
     // so the iterator skips it.
     let mut next_free_cell = DivModCounter::<N_OBJECTS>::new_const_mod(1, 0)
-        .map(|(row_idx, col_idx)| (chip.config.item_columns[col_idx], row_idx));
+        .map(|(row_idx, col_idx)| (chip.config.item_columns[col_idx], offset + row_idx));
 
     // An iterator that visits the cells of `swap_selector_columns`, row by row.
     let mut next_swap_selector =
-        DivModCounter::new_runtime_mod(0, 0, chip.config.swap_selector_columns.len())
-            .map(|(row_idx, col_idx)| (chip.config.swap_selector_columns[col_idx], row_idx));
+        DivModCounter::new_runtime_mod(0, 0, chip.config.swap_selector_columns.len()).map(
+            |(row_idx, col_idx)| (chip.config.swap_selector_columns[col_idx], offset + row_idx),
+        );
 
+    let mut swap_selectors = vec![];
     let mut item_tracker: [Number<F>; N_OBJECTS] = (0..N_OBJECTS)
         .map(|idx| input_items[idx].clone())
         .f_collect("the number of items is correct");
 
-    for (swap_is_applied, idx1, idx2) in from_permutation_to_bubble_sort_swap_schedule(permutation)
+    for (swap_idx, (swap_is_applied, idx1, idx2)) in
+        from_permutation_to_bubble_sort_swap_schedule(permutation)
+            .into_iter()
+            .enumerate()
     {
         let (col1, row1) = next_free_cell.next().expect("the iterator never ends");
         let (col2, row2) = next_free_cell.next().expect("the iterator never ends");
@@ -103,12 +333,21 @@ fn apply_permutation_region_assignment<const N_OBJECTS: usize, F: ff::Field>(
 
         // We assign the boolean value that will be used by the constraint
         // system to enforce the swaps
-        region.assign_advice(
-            || format!("swap selector for indices {}, {}", idx1, idx2),
-            s_col,
-            s_row,
-            || Value::known(if swap_is_applied { F::ONE } else { F::ZERO }),
-        )?;
+        swap_selectors.push(
+            region
+                .assign_advice(
+                    || format!("swap selector for indices {}, {}", idx1, idx2),
+                    s_col,
+                    s_row,
+                    || Value::known(if swap_is_applied { F::ONE } else { F::ZERO }),
+                )
+                .map(Number)?,
+        );
+
+        if parity_config.is_some() {
+            parity ^= swap_is_applied;
+            parity_cell = Some(assign_parity(region, swap_idx + 1, parity)?);
+        }
     }
-    Ok(item_tracker)
+    Ok((item_tracker, parity_cell, swap_selectors))
 }
@@ -2,21 +2,93 @@ use super::*;
 
 impl<const N_OBJECTS: usize, F: ff::Field> PermutationChip<N_OBJECTS, F> {
     pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, so that the regions of
+    /// different chips in the same circuit can be told apart,
+    /// e.g. in the diagnostics of the `MockProver`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
         Self {
             config,
+            name_prefix,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// The number of swap selector columns to give to `configure`
+    /// for `n_item_columns` items, i.e. the smallest number of columns
+    /// such that the swap selectors do not take more rows than the items do.
+    ///
+    /// Each permutation of `n` items applies `n * (n - 1) / 2` swaps,
+    /// each one with its own swap selector cell, while the items take about
+    /// `n + 1` rows. Fewer swap selector columns make each permutation take more rows,
+    /// while more columns do not save any row, since the items take them anyway.
+    pub const fn recommended_swap_selector_columns(n_item_columns: usize) -> usize {
+        if n_item_columns < 2 {
+            return 1;
+        }
+        let nr_swaps = n_item_columns * (n_item_columns - 1) / 2;
+        let item_rows = (n_item_columns + 2 * nr_swaps) / n_item_columns + 1;
+
+        let mut nr_columns = 1;
+        while nr_swaps / nr_columns + 1 > item_rows {
+            nr_columns += 1;
+        }
+        nr_columns
+    }
+
+    /// The degree of the permutation gate, i.e. the largest degree
+    /// of its constraints, as a polynomial in the queried cells.
+    ///
+    /// Every swap is constrained by the selector, times the swap selector,
+    /// times a difference of items, so the degree does not depend on
+    /// the number of objects. With less than two objects there are no swaps,
+    /// and the gate has no constraints.
+    #[allow(dead_code)]
+    pub const fn gate_degree() -> usize {
+        if N_OBJECTS < 2 {
+            0
+        } else {
+            3
+        }
+    }
+
+    /// `swap_selector_columns` has to contain at least one column, and at most
+    /// one per swap, since the other ones would never be used.
+    /// The swap selectors are laid out row by row over these columns,
+    /// so their number is a tradeoff between columns and rows,
+    /// see `recommended_swap_selector_columns`.
+    ///
+    /// Panics if the number of swap selector columns is not valid, see `try_configure`.
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
         item_columns: [Column<Advice>; N_OBJECTS],
         swap_selector_columns: Vec<Column<Advice>>,
     ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
-        assert!(
-            !swap_selector_columns.is_empty(),
-            "At least one column to allocate swap selectors is needed."
-        );
+        Self::try_configure(meta, item_columns, swap_selector_columns)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `configure`, but if the number of swap selector columns
+    /// is not valid, an error is returned, and `meta` is left untouched.
+    pub fn try_configure(
+        meta: &mut ConstraintSystem<F>,
+        item_columns: [Column<Advice>; N_OBJECTS],
+        swap_selector_columns: Vec<Column<Advice>>,
+    ) -> Result<<Self as halo2_proofs::circuit::Chip<F>>::Config, ConfigError> {
+        let max_columns = bubble_sort_swap_schedule::<N_OBJECTS>().len().max(1);
+        if !(1..=max_columns).contains(&swap_selector_columns.len()) {
+            return Err(ConfigError::SwapSelectorColumns {
+                found_columns: swap_selector_columns.len(),
+                max_columns,
+                recommended_columns: Self::recommended_swap_selector_columns(N_OBJECTS),
+            });
+        }
 
         for col in item_columns {
             meta.enable_equality(col);
@@ -45,26 +117,21 @@ impl<const N_OBJECTS: usize, F: ff::Field> PermutationChip<N_OBJECTS, F> {
             // An iterator that visits the cells of `item_columns`, row by row.
             // The first row is entirely occupied by the input items of the circuit,
             // so the iterator skips it.
-            let mut next_free_cell =
-                DivModCounter::<N_OBJECTS>::new_const_mod(1, 0).map(|(row_idx, col_idx)| {
+            let mut next_free_cell = DivModCounter::<N_OBJECTS>::new_const_mod(1, 0)
+                .cell_positions(&item_columns)
+                .map(|(column, rotation)| {
                     (
-                        item_columns[col_idx],
-                        Rotation(row_idx as i32),
-                        ask_meta
-                            .borrow_mut()
-                            .query_advice(item_columns[col_idx], Rotation(row_idx as i32)),
+                        column,
+                        rotation,
+                        ask_meta.borrow_mut().query_advice(column, rotation),
                     )
                 });
 
             // An iterator that visits the cells of `swap_selector_columns`, row by row.
             let mut next_swap_selector =
-                DivModCounter::<0, true>::new_runtime_mod(0, 0, swap_selector_columns.len()).map(
-                    |(row_idx, col_idx)| {
-                        ask_meta
-                            .borrow_mut()
-                            .query_advice(swap_selector_columns[col_idx], Rotation(row_idx as i32))
-                    },
-                );
+                DivModCounter::<0, true>::new_runtime_mod(0, 0, swap_selector_columns.len())
+                    .cell_positions(&swap_selector_columns)
+                    .map(|(column, rotation)| ask_meta.borrow_mut().query_advice(column, rotation));
 
             for (swap_idx1, swap_idx2) in swap_schedule {
                 let (next_idx1_col, next_idx1_row, next_idx1_value) =
@@ -116,11 +183,64 @@ impl<const N_OBJECTS: usize, F: ff::Field> PermutationChip<N_OBJECTS, F> {
             constraints
         });
 
-        PConfig {
+        Ok(PConfig {
             item_columns,
             swap_selector_columns,
             output_item_positions,
             s_perm,
+        })
+    }
+
+    /// Configures a gate that computes the parity of the permutation
+    /// applied by the chip configured in `config`, i.e. the number of
+    /// the swaps applied, modulo 2.
+    ///
+    /// The parity of the swaps applied so far is laid out in `parity_column`,
+    /// starting from `0`, one row at a time, so the gate spans one row
+    /// more than the number of swaps in the schedule.
+    pub fn configure_parity(
+        meta: &mut ConstraintSystem<F>,
+        config: &PConfig<N_OBJECTS>,
+        parity_column: Column<Advice>,
+    ) -> ParityConfig {
+        meta.enable_equality(parity_column);
+
+        let s_parity = meta.selector();
+
+        meta.create_gate("permutation parity", |meta| {
+            let s_parity = meta.query_selector(s_parity);
+
+            // The swap selectors are visited in the same order
+            // as in the "object permutation" gate.
+            let swap_selectors: Vec<_> =
+                DivModCounter::<0, true>::new_runtime_mod(0, 0, config.swap_selector_columns.len())
+                    .cell_positions(&config.swap_selector_columns)
+                    .take(bubble_sort_swap_schedule::<N_OBJECTS>().len())
+                    .map(|(column, rotation)| meta.query_advice(column, rotation))
+                    .collect();
+
+            let mut parity = meta.query_advice(parity_column, Rotation::cur());
+            // No swap applied yet
+            let mut constraints = vec![s_parity.clone() * parity.clone()];
+
+            for (swap_idx, swap_selector) in swap_selectors.into_iter().enumerate() {
+                let next_parity = meta.query_advice(parity_column, Rotation(swap_idx as i32 + 1));
+
+                // Since both `parity` and `swap_selector` are boolean,
+                // this is their XOR, which is boolean as well.
+                let xor = parity.clone() + swap_selector.clone()
+                    - Expression::Constant(F::ONE.double()) * parity * swap_selector;
+                constraints.push(s_parity.clone() * (next_parity.clone() - xor));
+
+                parity = next_parity;
+            }
+
+            constraints
+        });
+
+        ParityConfig {
+            parity_column,
+            s_parity,
         }
     }
 }
@@ -1,4 +1,7 @@
-use crate::{utilities::DivModCounter, Number};
+use crate::{
+    utilities::{prefixed_name, ConfigError, DivModCounter},
+    Number,
+};
 
 use std::cell::RefCell;
 
@@ -8,6 +11,8 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
 use try_collect::ForceCollect;
 
 /// in this module, we implement the functions needed to setup
@@ -19,23 +24,42 @@ mod gate_implementation;
 #[derive(Debug, Clone)]
 pub(crate) struct PermutationChip<const N_OBJECTS: usize, F: ff::Field> {
     config: PConfig<N_OBJECTS>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
     _marker: std::marker::PhantomData<F>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct PConfig<const N_OBJECTS: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
     pub item_columns: [Column<Advice>; N_OBJECTS],
 
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::vec"))]
     swap_selector_columns: Vec<Column<Advice>>,
 
     /// This field states where, relative to the start of the gate region,
     /// the permutated items are located.
     #[allow(dead_code)]
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
     output_item_positions: [(Column<Advice>, Rotation); N_OBJECTS],
 
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
     s_perm: Selector,
 }
 
+/// The configuration of the optional gate that computes
+/// the parity of the permutation applied by the chip.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct ParityConfig {
+    /// The column where the parity of the swaps applied so far is laid out.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    parity_column: Column<Advice>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_parity: Selector,
+}
+
 impl<const N_OBJECTS: usize> PConfig<N_OBJECTS> {
     pub fn get_item_columns(&self) -> &[Column<Advice>; N_OBJECTS] {
         &self.item_columns
@@ -45,6 +69,41 @@ impl<const N_OBJECTS: usize> PConfig<N_OBJECTS> {
     pub fn get_output_item_relative_positions(&self) -> &[(Column<Advice>, Rotation); N_OBJECTS] {
         &self.output_item_positions
     }
+
+    /// How many rows a single permutation takes,
+    /// when it is laid out by the chip, i.e. one more than
+    /// the last row of its `occupied_cells`.
+    /// The optional parity column is not taken into account.
+    pub fn rows_per_permutation(&self) -> usize {
+        self.occupied_cells()
+            .into_iter()
+            .map(|(_, row_idx)| row_idx + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// a single permutation assigns, relative to the first row of its region,
+    /// in the order in which they are assigned. The item columns come first,
+    /// in the order of `item_columns`, followed by the swap selector columns.
+    /// The optional parity column is not taken into account.
+    pub fn occupied_cells(&self) -> Vec<(usize, usize)> {
+        let nr_swaps = bubble_sort_swap_schedule::<N_OBJECTS>().len();
+        // The same counters as in `apply_permutation_region_assignment`.
+        let mut next_free_cell = DivModCounter::<N_OBJECTS>::new_const_mod(1, 0);
+        let mut next_swap_selector =
+            DivModCounter::new_runtime_mod(0, 0, self.swap_selector_columns.len());
+
+        let mut cells = Vec::from_iter((0..N_OBJECTS).map(|col_idx| (col_idx, 0)));
+        for _ in 0..nr_swaps {
+            for (row_idx, col_idx) in (&mut next_free_cell).take(2) {
+                cells.push((col_idx, row_idx));
+            }
+            let (row_idx, col_idx) = next_swap_selector.next().expect("the iterator never ends");
+            cells.push((N_OBJECTS + col_idx, row_idx));
+        }
+        cells
+    }
 }
 
 impl<const N_OBJECTS: usize, F: ff::Field> halo2_proofs::circuit::Chip<F>
@@ -100,3 +159,513 @@ fn from_permutation_to_bubble_sort_swap_schedule<const N_OBJECTS: usize>(
 
     bubble_sort_schedule
 }
+
+/// The values of the swap selectors the chip assigns to apply `permutation`,
+/// in the order in which they are assigned, i.e. whether each swap
+/// attempted by bubble sort is applied or not.
+/// Useful to debug the chip, or to build its witness somewhere else.
+pub fn swap_selector_values<const N_OBJECTS: usize>(permutation: [usize; N_OBJECTS]) -> Vec<bool> {
+    Vec::from_iter(
+        from_permutation_to_bubble_sort_swap_schedule(permutation)
+            .into_iter()
+            .map(|(swap_is_applied, _, _)| swap_is_applied),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::PermutationsIter;
+    use halo2_proofs::{
+        circuit::{Chip, SimpleFloorPlanner},
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    /// A circuit that applies each one of `permutations` to the numbers
+    /// `1, ..., N_OBJECTS`, and checks, cell by cell, that the `i`-th input
+    /// ends up in the output position `permutation[i]`.
+    ///
+    /// If `batched` is set, the permutations are applied with
+    /// `apply_permutations_batched`, otherwise with `apply_permutation`.
+    struct ApplyPermutationCircuit<const N_OBJECTS: usize> {
+        permutations: Vec<[usize; N_OBJECTS]>,
+        batched: bool,
+    }
+
+    impl<const N_OBJECTS: usize> Circuit<Fp> for ApplyPermutationCircuit<N_OBJECTS> {
+        type Config = PConfig<N_OBJECTS>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                permutations: self.permutations.clone(),
+                batched: self.batched,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let item_columns = [(); N_OBJECTS].map(|_| meta.advice_column());
+            let swap_selector_columns = (0..N_OBJECTS / 2).map(|_| meta.advice_column()).collect();
+            PermutationChip::configure(meta, item_columns, swap_selector_columns)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let permutation_chip = PermutationChip::<N_OBJECTS, Fp>::construct(config);
+
+            let input_cells: [Number<Fp>; N_OBJECTS] = layouter.assign_region(
+                || "input items",
+                |mut region| {
+                    let item_columns = permutation_chip.config().get_item_columns();
+                    let mut cells = vec![];
+                    for (idx, column) in item_columns.iter().enumerate() {
+                        cells.push(
+                            region
+                                .assign_advice(
+                                    || format!("{idx}-th input item"),
+                                    *column,
+                                    0,
+                                    || Value::known(Fp::from(idx as u64 + 1)),
+                                )
+                                .map(Number)?,
+                        );
+                    }
+                    Ok(core::array::from_fn(|idx| cells[idx].clone()))
+                },
+            )?;
+
+            let outputs = if self.batched {
+                permutation_chip.apply_permutations_batched(
+                    layouter.namespace(|| "batched permutations"),
+                    &vec![input_cells.clone(); self.permutations.len()],
+                    &self.permutations,
+                )?
+            } else {
+                let mut outputs = vec![];
+                for permutation in self.permutations.iter() {
+                    outputs.push(permutation_chip.apply_permutation(
+                        layouter.namespace(|| "permutation"),
+                        input_cells.clone(),
+                        *permutation,
+                    )?);
+                }
+                outputs
+            };
+
+            layouter.assign_region(
+                || "output check",
+                |mut region| {
+                    for (permutation, output_cells) in self.permutations.iter().zip(outputs.iter())
+                    {
+                        for (idx, input) in input_cells.iter().enumerate() {
+                            let output = &output_cells[permutation[idx]];
+                            output
+                                .value()
+                                .zip(input.value())
+                                .assert_if_known(|(output, input)| output == input);
+                            region.constrain_equal(output.cell(), input.cell())?;
+                        }
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    /// Runs `ApplyPermutationCircuit` for every permutation of `N_OBJECTS` items.
+    fn check_all_permutations<const N_OBJECTS: usize>(k: u32) {
+        for permutation in PermutationsIter::<N_OBJECTS> {
+            crate::utilities::assert_satisfied(
+                k,
+                &ApplyPermutationCircuit {
+                    permutations: vec![permutation],
+                    batched: false,
+                },
+                vec![],
+            );
+        }
+    }
+
+    #[test]
+    /// Test that, for every permutation of up to 5 items, the output cells
+    /// of `apply_permutation` contain the input items in the expected order.
+    fn apply_permutation_exhaustive() {
+        check_all_permutations::<2>(4);
+        check_all_permutations::<3>(4);
+        check_all_permutations::<4>(5);
+        check_all_permutations::<5>(5);
+    }
+
+    #[test]
+    /// Test that applying all the permutations of 4 items in a single batch
+    /// gives the same outputs as applying them one at a time,
+    /// and that the batch fits in the expected number of rows.
+    fn apply_permutations_batched() {
+        const POW_OF_2_MAX_ROWS: u32 = 8;
+
+        let permutations = Vec::from_iter(PermutationsIter::<4>);
+        for batched in [false, true] {
+            crate::utilities::assert_satisfied(
+                POW_OF_2_MAX_ROWS,
+                &ApplyPermutationCircuit {
+                    permutations: permutations.clone(),
+                    batched,
+                },
+                vec![],
+            );
+        }
+
+        // With 4 items, there are 6 swaps, laid out in 3 rows after the
+        // input row, plus the row with the single item cell of the last swap.
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let config = ApplyPermutationCircuit::<4>::configure(&mut meta);
+        assert_eq!(config.rows_per_permutation(), 5);
+    }
+
+    #[test]
+    /// Test that laying out as many permutations of 9 items as there are
+    /// groups in a 9x9 sudoku, in a single batch, uses a single region
+    /// for all of them, but as many rows as laying them out one at a time,
+    /// and needs the same `K`.
+    ///
+    /// Every permutation takes a block of `rows_per_permutation` rows
+    /// either way, so the batch saves regions, not rows.
+    fn batched_sudoku_layout_rows() {
+        use crate::utilities::{
+            assert_satisfied, circuit_stats, recommended_k, used_regions, used_rows,
+        };
+
+        let permutations = Vec::from_iter(PermutationsIter::<9>.into_iter().take(27));
+        let [unbatched, batched] = [false, true].map(|batched| ApplyPermutationCircuit {
+            permutations: permutations.clone(),
+            batched,
+        });
+
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let rows_per_permutation =
+            ApplyPermutationCircuit::<9>::configure(&mut meta).rows_per_permutation();
+        // The input items and the output check take one region each.
+        assert_eq!(
+            used_regions(&unbatched).expect("layout should not fail"),
+            2 + 27
+        );
+        assert_eq!(
+            used_regions(&batched).expect("layout should not fail"),
+            2 + 1
+        );
+
+        // The input items take the first row.
+        assert_eq!(
+            used_rows(&unbatched).expect("layout should not fail"),
+            1 + 27 * rows_per_permutation
+        );
+        assert_eq!(
+            used_rows(&batched).expect("layout should not fail"),
+            used_rows(&unbatched).expect("layout should not fail")
+        );
+        assert_eq!(
+            recommended_k(&batched).expect("layout should not fail"),
+            recommended_k(&unbatched).expect("layout should not fail")
+        );
+        assert_eq!(
+            circuit_stats(&batched).expect("layout should not fail"),
+            circuit_stats(&unbatched).expect("layout should not fail")
+        );
+
+        assert_satisfied(
+            recommended_k(&batched).expect("layout should not fail").0,
+            &batched,
+            vec![],
+        );
+    }
+
+    /// A circuit that constrains `target` to be a reordering
+    /// of the numbers `1, ..., 4`, with `apply_permutation_to_target`.
+    struct TargetCircuit {
+        target: [u64; 4],
+    }
+
+    impl Circuit<Fp> for TargetCircuit {
+        type Config = PConfig<4>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                target: self.target,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            ApplyPermutationCircuit::<4>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let item_columns = *config.get_item_columns();
+            let permutation_chip = PermutationChip::<4, Fp>::construct(config);
+
+            let (input_cells, target_cells) = layouter.assign_region(
+                || "input and target items",
+                |mut region| {
+                    let mut assign_row = |row: usize, values: [u64; 4]| {
+                        let mut cells = vec![];
+                        for (idx, value) in values.into_iter().enumerate() {
+                            cells.push(
+                                region
+                                    .assign_advice(
+                                        || format!("{idx}-th item of row {row}"),
+                                        item_columns[idx],
+                                        row,
+                                        || Value::known(Fp::from(value)),
+                                    )
+                                    .map(Number)?,
+                            );
+                        }
+                        Ok::<[Number<Fp>; 4], Error>(core::array::from_fn(|idx| cells[idx].clone()))
+                    };
+                    Ok((assign_row(0, [1, 2, 3, 4])?, assign_row(1, self.target)?))
+                },
+            )?;
+
+            permutation_chip
+                .apply_permutation_to_target(
+                    layouter.namespace(|| "permutation to target"),
+                    input_cells,
+                    target_cells,
+                )
+                .map(|_| ())
+        }
+    }
+
+    #[test]
+    /// Test that the identity permutation applies none of the swaps
+    /// it is laid out with, i.e. that all its swap selectors are `F::ZERO`.
+    fn identity_swap_schedule() {
+        fn check<const N_OBJECTS: usize>() {
+            let schedule = from_permutation_to_bubble_sort_swap_schedule::<N_OBJECTS>(
+                core::array::from_fn(|idx| idx),
+            );
+            assert_eq!(
+                schedule.len(),
+                bubble_sort_swap_schedule::<N_OBJECTS>().len()
+            );
+            assert!(schedule
+                .iter()
+                .all(|(swap_is_applied, _, _)| !swap_is_applied));
+        }
+        check::<1>();
+        check::<2>();
+        check::<7>();
+    }
+
+    #[test]
+    /// Test that no swap selector is set for the identity permutation,
+    /// and that, for every permutation of 5 items, including the full reversal,
+    /// the number of set swap selectors is the number of inversions.
+    fn swap_selector_values_inversions() {
+        const N_OBJECTS: usize = 5;
+
+        let identity: [usize; N_OBJECTS] = core::array::from_fn(|idx| idx);
+        let values = swap_selector_values(identity);
+        assert_eq!(values.len(), bubble_sort_swap_schedule::<N_OBJECTS>().len());
+        assert!(values.iter().all(|swap_is_applied| !swap_is_applied));
+
+        let reversal: [usize; N_OBJECTS] = core::array::from_fn(|idx| N_OBJECTS - 1 - idx);
+        let nr_applied_swaps =
+            |p: [usize; N_OBJECTS]| swap_selector_values(p).into_iter().filter(|x| *x).count();
+        assert_eq!(nr_applied_swaps(reversal), N_OBJECTS * (N_OBJECTS - 1) / 2);
+
+        for permutation in PermutationsIter::<N_OBJECTS> {
+            let inversions = (0..N_OBJECTS)
+                .flat_map(|i| (i + 1..N_OBJECTS).map(move |j| (i, j)))
+                .filter(|&(i, j)| permutation[i] > permutation[j])
+                .count();
+            assert_eq!(nr_applied_swaps(permutation), inversions);
+        }
+    }
+
+    #[test]
+    /// Test that `apply_permutation_to_target` accepts a reordering
+    /// of the input items, and rejects wrong targets.
+    fn apply_permutation_to_target() {
+        const POW_OF_2_MAX_ROWS: u32 = 5;
+
+        for target in [[1, 2, 3, 4], [3, 1, 4, 2], [4, 3, 2, 1]] {
+            crate::utilities::assert_satisfied(
+                POW_OF_2_MAX_ROWS,
+                &TargetCircuit { target },
+                vec![],
+            );
+        }
+        for target in [[3, 1, 4, 4], [3, 1, 4, 5], [0, 0, 0, 0]] {
+            crate::utilities::assert_rejected(POW_OF_2_MAX_ROWS, &TargetCircuit { target }, vec![]);
+        }
+    }
+
+    /// A circuit that applies a permutation with two chips,
+    /// constructed with different name prefixes.
+    struct TwoChipsCircuit;
+
+    impl Circuit<Fp> for TwoChipsCircuit {
+        type Config = PConfig<3>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            ApplyPermutationCircuit::<3>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let input_cells: [Number<Fp>; 3] = layouter.assign_region(
+                || "input items",
+                |mut region| {
+                    let mut cells = vec![];
+                    for (idx, column) in config.get_item_columns().iter().enumerate() {
+                        cells.push(
+                            region
+                                .assign_advice(
+                                    || format!("{idx}-th input item"),
+                                    *column,
+                                    0,
+                                    || Value::known(Fp::from(idx as u64 + 1)),
+                                )
+                                .map(Number)?,
+                        );
+                    }
+                    Ok(core::array::from_fn(|idx| cells[idx].clone()))
+                },
+            )?;
+
+            for name_prefix in ["first chip", "second chip"] {
+                PermutationChip::<3, Fp>::construct_with_name_prefix(config.clone(), name_prefix)
+                    .apply_permutation(
+                    layouter.namespace(|| name_prefix),
+                    input_cells.clone(),
+                    [2, 0, 1],
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    /// Test that the name prefixes of the chips
+    /// appear in the regions recorded by the `MockProver`.
+    fn permutation_chip_name_prefix() {
+        let prover = halo2_proofs::dev::MockProver::run(5, &TwoChipsCircuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+
+        let layout = format!("{prover:?}");
+        assert!(layout.contains("first chip: load input"));
+        assert!(layout.contains("second chip: load input"));
+    }
+
+    #[test]
+    /// Test that, for 9 objects, the recommended number of swap selector
+    /// columns lays out a permutation in fewer rows than a single column does,
+    /// and that configuring the chip with too many columns panics.
+    fn permutation_chip_swap_selector_columns() {
+        let rows_with_swap_selector_columns = |nr_columns: usize| {
+            let mut meta = ConstraintSystem::<Fp>::default();
+            let item_columns = [(); 9].map(|_| meta.advice_column());
+            let swap_selector_columns = (0..nr_columns).map(|_| meta.advice_column()).collect();
+            PermutationChip::<9, Fp>::configure(&mut meta, item_columns, swap_selector_columns)
+                .rows_per_permutation()
+        };
+
+        assert_eq!(
+            PermutationChip::<9, Fp>::recommended_swap_selector_columns(9),
+            9 / 2
+        );
+        assert_eq!(rows_with_swap_selector_columns(1), 37);
+        assert_eq!(rows_with_swap_selector_columns(9 / 2), 10);
+
+        assert!(std::panic::catch_unwind(|| rows_with_swap_selector_columns(0)).is_err());
+        assert!(std::panic::catch_unwind(|| rows_with_swap_selector_columns(37)).is_err());
+    }
+
+    #[test]
+    /// Test that `gate_degree` is the degree of the constraint system
+    /// of a freshly configured chip, when it is larger than the degree 3
+    /// required by the permutation argument, with and without swaps.
+    fn permutation_gate_degree() {
+        macro_rules! check_degree {
+            ($($N: literal),*) => {$({
+                let mut cs = ConstraintSystem::<Fp>::default();
+                let item_columns = [(); $N].map(|_| cs.advice_column());
+                let swap_selector_columns = vec![cs.advice_column()];
+                PermutationChip::<$N, Fp>::configure(&mut cs, item_columns, swap_selector_columns);
+                assert_eq!(cs.degree(), PermutationChip::<$N, Fp>::gate_degree().max(3));
+            })*};
+        }
+        check_degree!(1, 2, 5, 9);
+
+        assert_eq!(PermutationChip::<1, Fp>::gate_degree(), 0);
+        assert_eq!(PermutationChip::<5, Fp>::gate_degree(), 3);
+    }
+
+    #[test]
+    /// Test the coordinates of the cells occupied by a permutation
+    /// for a known configuration, and that they are distinct and fit
+    /// in the rows taken by a permutation, for a range of configurations.
+    fn permutation_chip_occupied_cells() {
+        fn config<const N: usize>(nr_swap_selector_columns: usize) -> PConfig<N> {
+            let mut meta = ConstraintSystem::<Fp>::default();
+            let item_columns = [(); N].map(|_| meta.advice_column());
+            let swap_selector_columns = (0..nr_swap_selector_columns)
+                .map(|_| meta.advice_column())
+                .collect();
+            PermutationChip::<N, Fp>::configure(&mut meta, item_columns, swap_selector_columns)
+        }
+
+        // The inputs, then two items and a swap selector for each of the 3 swaps.
+        assert_eq!(
+            config::<3>(1).occupied_cells(),
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (0, 2),
+                (1, 2),
+                (3, 2),
+                (2, 2),
+                (0, 3),
+                (3, 3)
+            ]
+        );
+
+        macro_rules! check_occupied_cells {
+            ($(<$N: literal, $N_SWAP_SELECTOR_COLUMNS: literal>),*) => {$({
+                let config = config::<$N>($N_SWAP_SELECTOR_COLUMNS);
+                let cells = config.occupied_cells();
+                assert_eq!(cells.len(), $N + 3 * bubble_sort_swap_schedule::<$N>().len());
+                assert_eq!(
+                    cells.iter().collect::<std::collections::BTreeSet<_>>().len(),
+                    cells.len()
+                );
+                assert!(cells.iter().all(|&(col, row)| col < $N + $N_SWAP_SELECTOR_COLUMNS
+                    && row < config.rows_per_permutation()));
+            })*};
+        }
+        check_occupied_cells!(<1, 1>, <2, 1>, <4, 1>, <4, 2>, <9, 4>, <9, 9>);
+    }
+}
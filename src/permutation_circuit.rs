@@ -1,6 +1,9 @@
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
 use crate::{
-    permutation_chip::{PConfig, PermutationChip},
-    Number,
+    permutation_chip::{PConfig, ParityConfig, PermutationChip},
+    utilities::{inverse_permutation, is_permutation},
+    CommittedPermutationCircuit, Number,
 };
 
 use halo2_proofs::{
@@ -10,12 +13,23 @@ use halo2_proofs::{
 use try_collect::{ForceCollect, TryCollect, TryFromIterator};
 
 /// A circuit that proves that the input and output values are a permutation of one another.
-pub struct PermutationCircuit<F: ff::Field, const N_OBJECTS: usize> {
+///
+/// If `EXPOSE_PARITY` is set, the parity of the permutation, i.e. `0` if it is
+/// even and `1` if it is odd, is exposed in the instance column as well,
+/// right after the output values.
+#[derive(Clone, Debug)]
+pub struct PermutationCircuit<
+    F: ff::Field,
+    const N_OBJECTS: usize,
+    const EXPOSE_PARITY: bool = false,
+> {
     input_items: [Value<F>; N_OBJECTS],
     permutation: [usize; N_OBJECTS],
 }
 
-impl<F: ff::Field, const N_OBJECTS: usize> PermutationCircuit<F, N_OBJECTS> {
+impl<F: ff::Field, const N_OBJECTS: usize, const EXPOSE_PARITY: bool>
+    PermutationCircuit<F, N_OBJECTS, EXPOSE_PARITY>
+{
     pub fn new_unchecked(
         input_items: [Value<F>; N_OBJECTS],
         permutation: [usize; N_OBJECTS],
@@ -26,6 +40,13 @@ impl<F: ff::Field, const N_OBJECTS: usize> PermutationCircuit<F, N_OBJECTS> {
         }
     }
 
+    /// Builds a circuit applying the identity permutation to `input_items`,
+    /// so that no swap is applied, and every swap selector is `F::ZERO`.
+    /// Useful as a baseline to measure the overhead of the permutation chip.
+    pub fn identity(input_items: [Value<F>; N_OBJECTS]) -> Self {
+        Self::new_unchecked(input_items, core::array::from_fn(|idx| idx))
+    }
+
     pub fn try_new<I: IntoIterator, J: IntoIterator>(
         input_items: I,
         permutation: J,
@@ -54,20 +75,36 @@ impl<F: ff::Field, const N_OBJECTS: usize> PermutationCircuit<F, N_OBJECTS> {
         })
     }
 
+    /// The public output the circuit proves against, i.e.
+    /// the input items after the permutation has been applied to them.
+    ///
+    /// The permutation chip moves the `i`-th input item in the position
+    /// `permutation[i]`, so the output is `input_items` rearranged by
+    /// the inverse of `permutation`.
+    pub fn expected_public_output(&self) -> [Value<F>; N_OBJECTS] {
+        inverse_permutation(self.permutation).map(|idx| self.input_items[idx])
+    }
+
     fn permutation_cosistency_check(p: [usize; N_OBJECTS]) -> Result<[usize; N_OBJECTS], ()> {
-        let mut q: [u8; N_OBJECTS] = [0; N_OBJECTS];
-        for i in p {
-            q[i] += 1;
-        }
-        if !q.into_iter().all(|count| count == 1) {
-            Err(())
-        } else {
+        if is_permutation::<N_OBJECTS>(p) {
             Ok(p)
+        } else {
+            Err(())
         }
     }
 }
 
-impl<F: ff::Field, const N_OBJECTS: usize> Default for PermutationCircuit<F, N_OBJECTS> {
+impl<F: ff::Field, const N_OBJECTS: usize> PermutationCircuit<F, N_OBJECTS> {
+    /// Turns the circuit into one that keeps the output secret, and only
+    /// exposes a commitment to it, see `CommittedPermutationCircuit`.
+    pub fn hide_output(self) -> CommittedPermutationCircuit<F, N_OBJECTS> {
+        CommittedPermutationCircuit::new(self)
+    }
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize, const EXPOSE_PARITY: bool> Default
+    for PermutationCircuit<F, N_OBJECTS, EXPOSE_PARITY>
+{
     fn default() -> Self {
         Self {
             input_items: [Value::unknown(); N_OBJECTS],
@@ -77,13 +114,23 @@ impl<F: ff::Field, const N_OBJECTS: usize> Default for PermutationCircuit<F, N_O
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PCircuitConfig<const N_OBJECTS: usize> {
     pconfig: PConfig<N_OBJECTS>,
+    /// Only present if the parity of the permutation is exposed.
+    parity_config: Option<ParityConfig>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
     instance: Column<Instance>,
 }
 
-impl<F: ff::Field, const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<F>
-    for PermutationCircuit<F, N_OBJECTS>
+impl<const N_OBJECTS: usize> PCircuitConfig<N_OBJECTS> {
+    pub(crate) fn instance_column(&self) -> Column<Instance> {
+        self.instance
+    }
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize, const EXPOSE_PARITY: bool>
+    halo2_proofs::plonk::Circuit<F> for PermutationCircuit<F, N_OBJECTS, EXPOSE_PARITY>
 {
     type Config = PCircuitConfig<N_OBJECTS>;
     type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
@@ -101,8 +148,15 @@ impl<F: ff::Field, const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<F>
             .f_collect("the number of items is correct");
         let swap_selector_columns = (0..N_OBJECTS / 2).map(|_| meta.advice_column()).collect();
 
+        let pconfig = PermutationChip::configure(meta, item_columns, swap_selector_columns);
+        let parity_config = EXPOSE_PARITY.then(|| {
+            let parity_column = meta.advice_column();
+            PermutationChip::configure_parity(meta, &pconfig, parity_column)
+        });
+
         PCircuitConfig {
-            pconfig: PermutationChip::configure(meta, item_columns, swap_selector_columns),
+            pconfig,
+            parity_config,
             instance,
         }
     }
@@ -112,6 +166,51 @@ impl<F: ff::Field, const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<F>
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
+        let instance = config.instance;
+        let (permutation_cells, parity_cell) =
+            self.assign_permutation(config, layouter.namespace(|| "permutation"))?;
+
+        let mut output_layouter = layouter.namespace(|| "public output assignment");
+        for (idx, cell) in permutation_cells.iter().enumerate().take(N_OBJECTS) {
+            output_layouter.constrain_instance(cell.cell(), instance, idx)?;
+        }
+        if let Some(parity_cell) = parity_cell {
+            output_layouter.constrain_instance(parity_cell.cell(), instance, N_OBJECTS)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize, const EXPOSE_PARITY: bool>
+    PermutationCircuit<F, N_OBJECTS, EXPOSE_PARITY>
+{
+    /// Assigns the input items, and permutes them with the permutation chip.
+    /// Returns the cells holding the permuted items, and, if it is exposed,
+    /// the cell holding the parity of the permutation.
+    pub(crate) fn assign_permutation(
+        &self,
+        config: PCircuitConfig<N_OBJECTS>,
+        layouter: impl Layouter<F>,
+    ) -> Result<([Number<F>; N_OBJECTS], Option<Number<F>>), Error> {
+        self.assign_permutation_with_inputs(config, layouter)
+            .map(|(_, output_cells, parity_cell)| (output_cells, parity_cell))
+    }
+
+    /// Same as `assign_permutation`, but the cells holding the input items
+    /// are returned as well, before the other ones.
+    pub(crate) fn assign_permutation_with_inputs(
+        &self,
+        config: PCircuitConfig<N_OBJECTS>,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<
+        (
+            [Number<F>; N_OBJECTS],
+            [Number<F>; N_OBJECTS],
+            Option<Number<F>>,
+        ),
+        Error,
+    > {
         let permutation_chip = PermutationChip::<N_OBJECTS, F>::construct(config.pconfig);
 
         // We assign the input values to the first row of the `item_columns` advice columns
@@ -142,18 +241,21 @@ impl<F: ff::Field, const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<F>
         // Now we call the chip's API to assign all the values required to
         // obtain the output permutation, and to prove that it is indeed
         // the required permutation
-        let permutation_cells = permutation_chip.apply_permutation(
-            layouter.namespace(|| "permutation chip assignment"),
-            input_cells,
-            self.permutation,
-        )?;
-
-        let mut output_layouter = layouter.namespace(|| "public output assignment");
-        for (idx, cell) in permutation_cells.iter().enumerate().take(N_OBJECTS) {
-            output_layouter.constrain_instance(cell.0.cell(), config.instance, idx)?;
-        }
-
-        Ok(())
+        let layouter = layouter.namespace(|| "permutation chip assignment");
+        let (output_cells, parity_cell) = match &config.parity_config {
+            Some(parity_config) => permutation_chip
+                .apply_permutation_with_parity(
+                    layouter,
+                    parity_config,
+                    input_cells.clone(),
+                    self.permutation,
+                )
+                .map(|(output_cells, parity_cell)| (output_cells, Some(parity_cell))),
+            None => permutation_chip
+                .apply_permutation(layouter, input_cells.clone(), self.permutation)
+                .map(|output_cells| (output_cells, None)),
+        }?;
+        Ok((input_cells, output_cells, parity_cell))
     }
 }
 
@@ -161,13 +263,14 @@ impl<F: ff::Field, const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<F>
 mod tests {
     use super::*;
 
-    use crate::utilities::{inverse_permutation, PermutationsIter};
+    use crate::utilities::PermutationsIter;
 
     #[test]
     /// Test the permutation circuit with the mock prover, which prints out errors and warnings.
     /// We prove that every possible permutation of 7 items is correctly proved.
     fn mock_permutation() {
-        use halo2_proofs::{dev::MockProver, pasta::Fp};
+        use crate::utilities::assert_satisfied;
+        use halo2_proofs::pasta::Fp;
 
         let objects: [Value<Fp>; 7] = core::array::from_fn(|n| Value::known(Fp::from(n as u64)));
 
@@ -179,10 +282,189 @@ mod tests {
             let permutation_output =
                 Vec::from(inverse_permutation(permutation).map(|x| Fp::from(x as u64)));
 
-            let prover = MockProver::run(POW_2_EXP_MAX_ROWS, &circuit, vec![permutation_output])
-                .expect("Proof generation goes wrong");
+            assert_satisfied(POW_2_EXP_MAX_ROWS, &circuit, vec![permutation_output]);
+        }
+    }
+
+    #[test]
+    /// Test that the identity circuit applies no swap, so that every swap
+    /// selector is `F::ZERO`, and outputs its inputs, even exposing
+    /// the parity of the permutation, which is even as no swap is applied.
+    fn mock_permutation_identity() {
+        use crate::{
+            permutation_chip::swap_selector_values,
+            utilities::{assert_rejected, assert_satisfied},
+        };
+        use halo2_proofs::pasta::Fp;
+
+        const N_OBJECTS: usize = 7;
+        const K: u32 = 5;
+
+        let inputs: [Fp; N_OBJECTS] = core::array::from_fn(|n| Fp::from(3 * n as u64 + 1));
 
-            assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+        let circuit = PermutationCircuit::<Fp, N_OBJECTS>::identity(inputs.map(Value::known));
+        let swap_selectors = swap_selector_values(circuit.permutation);
+        assert_eq!(swap_selectors.len(), N_OBJECTS * (N_OBJECTS - 1) / 2);
+        assert!(swap_selectors
+            .iter()
+            .all(|swap_is_applied| !swap_is_applied));
+        for (output, input) in circuit.expected_public_output().iter().zip(inputs) {
+            output.assert_if_known(|output| *output == input);
+        }
+        assert_satisfied(K, &circuit, vec![Vec::from(inputs)]);
+
+        let mut swapped_inputs = inputs;
+        swapped_inputs.swap(0, 1);
+        assert_rejected(K, &circuit, vec![Vec::from(swapped_inputs)]);
+
+        let circuit = PermutationCircuit::<Fp, N_OBJECTS, true>::identity(inputs.map(Value::known));
+        let mut instance = Vec::from(inputs);
+        instance.push(Fp::from(0));
+        assert_satisfied(K, &circuit, vec![instance]);
+    }
+
+    #[test]
+    /// Test that `mock_prove_auto_k` picks a `K` that is large enough
+    /// to prove the permutation circuit, and that it still reports
+    /// the verification failures of a wrong output.
+    fn mock_permutation_auto_k() {
+        use crate::utilities::{mock_prove_auto_k, recommended_k};
+        use halo2_proofs::pasta::Fp;
+
+        let objects: [Value<Fp>; 7] = core::array::from_fn(|n| Value::known(Fp::from(n as u64)));
+
+        for permutation in [
+            [0, 1, 2, 3, 4, 5, 6],
+            [6, 5, 4, 3, 2, 1, 0],
+            [1, 2, 0, 4, 3, 6, 5],
+        ] {
+            let circuit = PermutationCircuit::<Fp, 7>::new_unchecked(objects, permutation);
+            // The circuit is proved with `K = 5` in `mock_permutation`.
+            assert!(recommended_k(&circuit).expect("layout should not fail").0 <= 5);
+
+            let output = Vec::from(inverse_permutation(permutation).map(|x| Fp::from(x as u64)));
+            assert!(mock_prove_auto_k(&circuit, vec![output.clone()]).is_ok());
+
+            let mut wrong_output = output;
+            wrong_output.swap(0, 1);
+            assert!(mock_prove_auto_k(&circuit, vec![wrong_output]).is_err());
+        }
+    }
+
+    #[test]
+    /// Test that the expected public output agrees with the instances
+    /// we build by inverting the permutation.
+    fn permutation_expected_public_output() {
+        use halo2_proofs::pasta::Fp;
+
+        const N_OBJECTS: usize = 5;
+
+        let objects: [Value<Fp>; N_OBJECTS] =
+            core::array::from_fn(|n| Value::known(Fp::from(n as u64)));
+
+        for permutation in PermutationsIter::<N_OBJECTS> {
+            let circuit = PermutationCircuit::<Fp, N_OBJECTS>::new_unchecked(objects, permutation);
+            let instance = inverse_permutation(permutation).map(|x| Fp::from(x as u64));
+
+            for (output, expected) in circuit.expected_public_output().into_iter().zip(instance) {
+                output.assert_if_known(|output| *output == expected);
+            }
+        }
+
+        // Without witness values, the output is unknown as well.
+        let circuit = PermutationCircuit::<Fp, N_OBJECTS>::default();
+        for output in circuit.expected_public_output() {
+            output.assert_if_known(|_| false);
+        }
+    }
+
+    /// A circuit that permutes the items of a `PermutationCircuit`,
+    /// and reads the permuted values back out of the assigned cells.
+    struct PermutedValuesCircuit<const N_OBJECTS: usize>(
+        PermutationCircuit<halo2_proofs::pasta::Fp, N_OBJECTS>,
+    );
+
+    impl<const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<halo2_proofs::pasta::Fp>
+        for PermutedValuesCircuit<N_OBJECTS>
+    {
+        type Config = PCircuitConfig<N_OBJECTS>;
+        type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self(PermutationCircuit::default())
+        }
+
+        fn configure(meta: &mut ConstraintSystem<halo2_proofs::pasta::Fp>) -> Self::Config {
+            <PermutationCircuit<_, N_OBJECTS> as halo2_proofs::plonk::Circuit<_>>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<halo2_proofs::pasta::Fp>,
+        ) -> Result<(), Error> {
+            let (permutation_cells, _) = self.0.assign_permutation(config, layouter)?;
+
+            for (cell, expected) in permutation_cells
+                .iter()
+                .zip(self.0.expected_public_output())
+            {
+                let mut expected_value = None;
+                expected.map(|value| expected_value = Some(value));
+                assert_eq!(cell.known_value(), expected_value);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    /// Test that the values read out of the permuted cells are the expected ones.
+    fn permutation_known_values() {
+        use halo2_proofs::pasta::Fp;
+
+        const N_OBJECTS: usize = 5;
+        const K: u32 = 4;
+
+        let objects: [Value<Fp>; N_OBJECTS] =
+            core::array::from_fn(|n| Value::known(Fp::from(10 * n as u64)));
+        let circuit = PermutedValuesCircuit(PermutationCircuit::<Fp, N_OBJECTS>::new_unchecked(
+            objects,
+            [1, 2, 0, 4, 3],
+        ));
+
+        crate::utilities::assert_satisfied(K, &circuit, vec![vec![]]);
+    }
+
+    #[test]
+    /// Test that the exposed parity is `0` for even permutations,
+    /// and `1` for odd ones, and that a wrong parity is rejected.
+    fn mock_permutation_parity() {
+        use crate::utilities::{assert_rejected, assert_satisfied, permutation_sign};
+        use halo2_proofs::pasta::Fp;
+
+        const N_OBJECTS: usize = 5;
+        const K: u32 = 5;
+
+        let objects: [Value<Fp>; N_OBJECTS] =
+            core::array::from_fn(|n| Value::known(Fp::from(n as u64)));
+
+        for permutation in [
+            [0, 1, 2, 3, 4],
+            [1, 0, 2, 3, 4],
+            [1, 2, 0, 4, 3],
+            [4, 3, 2, 1, 0],
+        ] {
+            let circuit =
+                PermutationCircuit::<Fp, N_OBJECTS, true>::new_unchecked(objects, permutation);
+            let parity = (permutation_sign(permutation) == -1) as u64;
+
+            let mut instance =
+                Vec::from(inverse_permutation(permutation).map(|x| Fp::from(x as u64)));
+            instance.push(Fp::from(parity));
+            assert_satisfied(K, &circuit, vec![instance.clone()]);
+
+            instance[N_OBJECTS] = Fp::from(1 - parity);
+            assert_rejected(K, &circuit, vec![instance]);
         }
     }
 
@@ -246,4 +528,496 @@ mod tests {
             assert!(verifier.verify(instance_slices.iter().map(|a| a.as_slice()), transcript.as_slice()))
         }
     }
+
+    #[test]
+    /// Test the permutation circuit with actual prover and verifier,
+    /// using the Pallas wrapper aliases, so that the circuit is defined
+    /// over `Fq` rather than `Fp`.
+    /// We prove that every possible permutation of 5 items is correctly proved.
+    fn permutation_pallas() {
+        use halo2_proofs::{pasta::Fq, poly::commitment::Params};
+
+        use crate::utilities::{PallasProverWrapper, PallasVerifierWrapper};
+
+        const N_OBJECTS: usize = 5;
+        const FACTORIAL: usize = 120;
+        const K: u32 = 4;
+
+        let objects: [Value<Fq>; N_OBJECTS] =
+            core::array::from_fn(|n| Value::known(Fq::from(n as u64)));
+
+        let circuit_wiring = PermutationCircuit::<Fq, N_OBJECTS>::default();
+        let mut prover = PallasProverWrapper::initialize_prover(Params::new(K), circuit_wiring)
+            .expect("prover setup should not fail");
+
+        let instances: [[Fq; N_OBJECTS]; FACTORIAL] = PermutationsIter::<N_OBJECTS>
+            .into_iter()
+            .map(|permutation| inverse_permutation(permutation).map(|x| Fq::from(x as u64)))
+            .f_collect("the number of items is correct");
+        let instance_slices: [[&[Fq]; 1]; FACTORIAL] =
+            core::array::from_fn(|i| [instances[i].as_slice()]);
+
+        for (instance, permutation) in instance_slices.iter().zip(PermutationsIter::<N_OBJECTS>) {
+            let circuit =
+                PermutationCircuit::<Fq, N_OBJECTS>::new_unchecked(objects.clone(), permutation);
+
+            prover.add_item(circuit, instance.as_slice());
+        }
+
+        let transcript = prover.prove().expect("proof generation should not fail");
+
+        let mut verifier = PallasVerifierWrapper::from(prover);
+        assert!(verifier.verify(
+            instance_slices.iter().map(|a| a.as_slice()),
+            transcript.as_slice()
+        ));
+    }
+
+    #[test]
+    /// Test that the public parameters written by a prover can be read back
+    /// to set up a new prover and verifier that work as the original ones.
+    /// The keys cannot be serialized at the halo2 version we depend on,
+    /// so they are generated again, and have to match the original ones.
+    fn permutation_parameters_round_trip() {
+        use halo2_proofs::pasta::Fp;
+
+        use crate::utilities::{ProverWrapper, VerifierWrapper};
+
+        const N_OBJECTS: usize = 5;
+        const K: u32 = 4;
+
+        let objects: [Value<Fp>; N_OBJECTS] =
+            core::array::from_fn(|n| Value::known(Fp::from(n as u64)));
+        let permutation = [3, 0, 4, 1, 2];
+        let instance = inverse_permutation(permutation).map(|x| Fp::from(x as u64));
+        let instance = [instance.as_slice()];
+
+        let original_prover = ProverWrapper::initialize_parameters_and_prover(
+            K,
+            PermutationCircuit::<Fp, N_OBJECTS>::default(),
+        )
+        .expect("prover setup should not fail");
+
+        let mut serialized_parameters = vec![];
+        original_prover
+            .write_parameters_to(&mut serialized_parameters)
+            .expect("parameters serialization should not fail");
+
+        let mut prover: ProverWrapper<_> = ProverWrapper::read_parameters_and_initialize_prover(
+            &mut serialized_parameters.as_slice(),
+            PermutationCircuit::<Fp, N_OBJECTS>::default(),
+        )
+        .expect("prover setup from serialized parameters should not fail");
+        prover.add_item(
+            PermutationCircuit::new_unchecked(objects, permutation),
+            instance.as_slice(),
+        );
+        let transcript = prover.prove().expect("proof generation should not fail");
+
+        let mut verifier: VerifierWrapper<_> =
+            VerifierWrapper::read_parameters_and_initialize_verifier(
+                &mut serialized_parameters.as_slice(),
+                PermutationCircuit::<Fp, N_OBJECTS>::default(),
+            )
+            .expect("verifier setup from serialized parameters should not fail");
+        assert_eq!(
+            verifier.pinned_verifying_key(),
+            VerifierWrapper::<_>::from(original_prover).pinned_verifying_key()
+        );
+        assert!(verifier.verify([instance.as_slice()], transcript.as_slice()));
+    }
+
+    #[test]
+    /// Test that a verifier built with `from_inner_parts`, out of the public
+    /// parameters serialized by a prover and of the verifying key it hands
+    /// over, verifies the proofs of the prover without the circuit wiring.
+    fn permutation_verifier_from_inner_parts() {
+        use halo2_proofs::{pasta::Fp, poly::commitment::Params};
+
+        use crate::utilities::VerifierWrapper;
+
+        let permutations = [[3, 0, 4, 1, 2]];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let prover = prover_with_items(4, &permutations, &instances);
+        let transcript = prover.prove().expect("proof generation should not fail");
+
+        let mut serialized_parameters = vec![];
+        prover
+            .write_parameters_to(&mut serialized_parameters)
+            .expect("parameters serialization should not fail");
+        let verifying_key = prover.proving_key().get_vk().clone();
+
+        let mut verifier = VerifierWrapper::<PermutationCircuit<Fp, 5>>::from_inner_parts(
+            Params::read(&mut serialized_parameters.as_slice())
+                .expect("parameters deserialization should not fail"),
+            verifying_key,
+        );
+        assert!(verifier.verify([instances[0].as_slice()], transcript.as_slice()));
+    }
+
+    #[test]
+    /// Test that a verifier detects whether its verifying key was generated
+    /// for the circuit it is used with, or for a differently configured one.
+    fn permutation_vk_mismatch() {
+        use halo2_proofs::{pasta::Fp, plonk::keygen_vk, poly::commitment::Params};
+
+        use crate::utilities::VerifierWrapper;
+
+        const N_OBJECTS: usize = 5;
+        const K: u32 = 5;
+
+        let public_parameters = Params::new(K);
+        let circuit_wiring = PermutationCircuit::<Fp, N_OBJECTS>::default();
+
+        let verifier = VerifierWrapper::initialize_verifier(
+            public_parameters.clone(),
+            PermutationCircuit::default(),
+        )
+        .expect("verifier setup should not fail");
+        assert!(verifier.check_vk_matches(&circuit_wiring));
+
+        // The parity column changes the configuration of the circuit.
+        let stale_verifying_key = keygen_vk(
+            &public_parameters,
+            &PermutationCircuit::<Fp, N_OBJECTS, true>::default(),
+        )
+        .expect("key generation should not fail");
+        let stale_verifier = VerifierWrapper::<PermutationCircuit<Fp, N_OBJECTS>>::from_inner_parts(
+            public_parameters,
+            stale_verifying_key,
+        );
+        assert!(!stale_verifier.check_vk_matches(&circuit_wiring));
+    }
+
+    /// The instances of the items proving `permutations`, in the same order.
+    fn item_instances(permutations: &[[usize; 5]]) -> Vec<[halo2_proofs::pasta::Fp; 5]> {
+        Vec::from_iter(permutations.iter().map(|&permutation| {
+            inverse_permutation(permutation).map(|x| halo2_proofs::pasta::Fp::from(x as u64))
+        }))
+    }
+
+    /// Sets up a prover for the permutation circuit of 5 objects, with `2^k` rows,
+    /// and adds to it one item for each of `permutations`, whose instance
+    /// is the one in the same position in `instances`, see `item_instances`.
+    /// The instances are owned by the caller, and borrowed by the prover.
+    fn prover_with_items<'i>(
+        k: u32,
+        permutations: &[[usize; 5]],
+        instances: &'i [[&'i [halo2_proofs::pasta::Fp]; 1]],
+    ) -> crate::utilities::ProverWrapper<'i, PermutationCircuit<halo2_proofs::pasta::Fp, 5>> {
+        use halo2_proofs::pasta::Fp;
+
+        let objects: [Value<Fp>; 5] = core::array::from_fn(|n| Value::known(Fp::from(n as u64)));
+
+        let mut prover = crate::utilities::ProverWrapper::initialize_parameters_and_prover(
+            k,
+            PermutationCircuit::default(),
+        )
+        .expect("prover setup should not fail");
+        for (&permutation, instance) in permutations.iter().zip(instances) {
+            prover.add_item(
+                PermutationCircuit::new_unchecked(objects, permutation),
+                instance.as_slice(),
+            );
+        }
+        prover
+    }
+
+    #[test]
+    /// Test that a prover set up with too many rows can be downsized,
+    /// and still proves and verifies, but that it can not grow.
+    fn permutation_prover_downsize() {
+        use crate::utilities::{DownsizeError, VerifierWrapper};
+
+        let circuit_wiring = PermutationCircuit::default();
+        let permutations = [[3, 0, 4, 1, 2]];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let mut prover = prover_with_items(10, &permutations, &instances);
+
+        prover
+            .downsize(5, &circuit_wiring)
+            .expect("downsizing should not fail");
+        assert_eq!(prover.public_parameters().get_g().len(), 1 << 5);
+        assert!(matches!(
+            prover.downsize(6, &circuit_wiring),
+            Err(DownsizeError::LargerK {
+                current_k: 5,
+                new_k: 6
+            })
+        ));
+
+        let transcript = prover.prove().expect("proof generation should not fail");
+        let mut verifier = VerifierWrapper::from(prover);
+        assert!(verifier.verify([instances[0].as_slice()], transcript.as_slice()));
+    }
+
+    #[test]
+    /// Test that proving twice with identically seeded RNGs
+    /// yields the same transcript.
+    fn permutation_seeded_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        const SEED: u64 = 42;
+
+        let permutations = [[1, 2, 0, 4, 3]];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let prover = prover_with_items(4, &permutations, &instances);
+
+        let transcript1 = prover
+            .prove_with_rng(StdRng::seed_from_u64(SEED))
+            .expect("proof generation should not fail");
+        let transcript2 = prover
+            .prove_with_rng(StdRng::seed_from_u64(SEED))
+            .expect("proof generation should not fail");
+
+        assert_eq!(transcript1, transcript2);
+    }
+
+    #[test]
+    #[cfg(feature = "batch")]
+    /// Test that several independent proofs are verified together,
+    /// and that the batch is rejected if any of the proofs is corrupted.
+    fn permutation_batch_verification() {
+        use crate::utilities::VerifierWrapper;
+
+        let permutations = [[1, 2, 0, 4, 3], [4, 3, 2, 1, 0], [0, 1, 2, 3, 4]];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let prover = prover_with_items(4, &permutations, &instances);
+
+        // Every permutation is proved on its own.
+        let mut transcripts = prover
+            .prove_each_with_rng(|_| rand::rngs::OsRng)
+            .expect("proof generation should not fail");
+
+        let mut verifier = VerifierWrapper::from(prover);
+        assert!(verifier.verify_batch(
+            instances
+                .iter()
+                .zip(transcripts.iter())
+                .map(|(instance, transcript)| ([instance.as_slice()], transcript.as_slice()))
+        ));
+
+        let last_byte = transcripts[1].len() - 1;
+        transcripts[1][last_byte] ^= 1;
+        assert!(!verifier.verify_batch(
+            instances
+                .iter()
+                .zip(transcripts.iter())
+                .map(|(instance, transcript)| ([instance.as_slice()], transcript.as_slice()))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    /// Test that the items proved in parallel verify individually,
+    /// and that their transcripts match the ones proved sequentially.
+    ///
+    /// The `rayon` feature is not enabled by default, so this test only runs
+    /// with `cargo test --features rayon`.
+    fn permutation_parallel_proving() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use crate::utilities::VerifierWrapper;
+
+        let permutations = [[1, 2, 0, 4, 3], [4, 3, 2, 1, 0], [0, 1, 2, 3, 4]];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let prover = prover_with_items(4, &permutations, &instances);
+
+        let rng_for_item = |idx: usize| StdRng::seed_from_u64(idx as u64);
+        let parallel_transcripts = prover
+            .prove_each_parallel_with_rng(rng_for_item)
+            .expect("proof generation should not fail");
+        let sequential_transcripts = prover
+            .prove_each_with_rng(rng_for_item)
+            .expect("proof generation should not fail");
+        assert_eq!(parallel_transcripts, sequential_transcripts);
+
+        let mut verifier = VerifierWrapper::from(prover);
+        for (instance, transcript) in instances.iter().zip(parallel_transcripts.iter()) {
+            assert!(verifier.verify([instance.as_slice()], transcript.as_slice()));
+        }
+    }
+
+    #[test]
+    /// Test that proving in chunks reports the progress after every chunk,
+    /// and that the proof only verifies when it is chunked in the same way,
+    /// and when nothing follows the last chunk.
+    fn permutation_chunked_proving() {
+        use crate::utilities::VerifierWrapper;
+
+        const CHUNK_SIZE: usize = 2;
+
+        let permutations = [
+            [1, 2, 0, 4, 3],
+            [4, 3, 2, 1, 0],
+            [0, 1, 2, 3, 4],
+            [2, 0, 1, 3, 4],
+            [3, 4, 0, 1, 2],
+        ];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let prover = prover_with_items(4, &permutations, &instances);
+
+        let mut reports = vec![];
+        let transcript = prover
+            .prove_in_chunks(CHUNK_SIZE, |done, total| reports.push((done, total)))
+            .expect("proof generation should not fail");
+        assert_eq!(reports, vec![(2, 5), (4, 5), (5, 5)]);
+
+        let mut verifier = VerifierWrapper::from(prover);
+        let instances = || instances.iter().map(|instance| instance.as_slice());
+        assert!(verifier.verify_in_chunks(instances(), CHUNK_SIZE, transcript.as_slice()));
+        assert!(!verifier.verify_in_chunks(instances(), CHUNK_SIZE + 1, transcript.as_slice()));
+
+        let mut padded_transcript = transcript.clone();
+        padded_transcript.push(0);
+        assert!(!verifier.verify_in_chunks(instances(), CHUNK_SIZE, padded_transcript.as_slice()));
+    }
+
+    #[test]
+    /// Test that verifying a truncated transcript reports a transcript error.
+    fn permutation_truncated_transcript() {
+        use crate::utilities::VerifierWrapper;
+
+        let permutations = [[2, 4, 1, 0, 3]];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let prover = prover_with_items(4, &permutations, &instances);
+        let instance = instances[0].as_slice();
+        let transcript = prover.prove().expect("proof generation should not fail");
+
+        let mut verifier = VerifierWrapper::from(prover);
+        verifier
+            .verify_detailed([instance], transcript.as_slice())
+            .expect("proof verification should not fail");
+
+        match verifier.verify_detailed([instance], &transcript[..transcript.len() / 2]) {
+            Err(Error::Transcript(err)) => {
+                assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof)
+            }
+            other => panic!("expected a transcript error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    /// Test the permutation circuit with actual prover and verifier,
+    /// using Keccak256 based transcripts.
+    fn permutation_keccak() {
+        use crate::utilities::VerifierWrapper;
+
+        let permutations = [[4, 0, 3, 1, 2]];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let prover = prover_with_items(4, &permutations, &instances);
+        let instance = instances[0].as_slice();
+        let transcript = prover
+            .prove_keccak()
+            .expect("proof generation should not fail");
+
+        let mut verifier = VerifierWrapper::from(prover);
+        assert!(verifier.verify_keccak([instance], transcript.as_slice()));
+        // A Keccak256 transcript is not a valid Blake2b transcript.
+        assert!(!verifier.verify([instance], transcript.as_slice()));
+    }
+
+    #[test]
+    /// Test that a proof written to a file can be verified from the file.
+    fn permutation_verify_from_path() {
+        use crate::utilities::VerifierWrapper;
+
+        let permutations = [[0, 3, 1, 4, 2]];
+        let instances = item_instances(&permutations);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+        let prover = prover_with_items(4, &permutations, &instances);
+        let instance = instances[0].as_slice();
+        let transcript = prover.prove().expect("proof generation should not fail");
+
+        // The process id keeps concurrent test runs from sharing the file.
+        let path = std::env::temp_dir().join(format!(
+            "halo2_playground_permutation_verify_from_path_{}.bin",
+            std::process::id()
+        ));
+        std::fs::write(&path, &transcript).expect("writing the proof should not fail");
+
+        let mut verifier = VerifierWrapper::from(prover);
+        let verified = verifier.verify_from_path([instance], &path);
+        std::fs::remove_file(&path).expect("removing the proof should not fail");
+        assert!(verified);
+
+        // The file does not exist anymore
+        assert!(!verifier.verify_from_path([instance], &path));
+    }
+
+    #[test]
+    /// Test that a proof of the permutation circuit, bundled with its
+    /// verifying key and instance, can be written, read back on its own,
+    /// and verified against trusted public parameters, and that bundles
+    /// with a wrong instance, with a different verifying key,
+    /// or with too large a `K`, are rejected.
+    fn permutation_proof_bundle() {
+        use halo2_proofs::{
+            pasta::{EqAffine, Fp},
+            poly::commitment::Params,
+        };
+
+        use crate::utilities::ProofBundle;
+
+        const K: u32 = 5;
+
+        type Bundle = ProofBundle<EqAffine>;
+        type Wiring = PermutationCircuit<Fp, 5>;
+
+        let permutations = [[3, 0, 4, 1, 2]];
+        let instances = item_instances(&permutations);
+        let blob = {
+            let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+            let prover = prover_with_items(K, &permutations, &instances);
+            let transcript = prover.prove().expect("proof generation should not fail");
+
+            let mut blob = vec![];
+            ProofBundle::from_prover(&prover, transcript)
+                .write(&mut blob)
+                .expect("writing to a vector should not fail");
+            blob
+        };
+
+        // From here on, only the blob is available.
+        assert!(Bundle::verify_bundle::<Wiring>(&blob, K));
+        assert!(!Bundle::verify_bundle::<Wiring>(&blob, K - 1));
+        assert!(Bundle::read(&mut blob.as_slice(), K - 1).is_err());
+        assert!(!Bundle::verify_bundle::<Wiring>(&blob[..blob.len() - 1], K));
+
+        let bundle = Bundle::read(&mut blob.as_slice(), K).expect("the bundle was written");
+        assert_eq!(bundle.k(), K);
+        assert_eq!(bundle.instances(), [vec![instances[0].to_vec()]]);
+
+        let public_parameters = Params::new(K);
+        assert!(bundle.verify::<Wiring>(&public_parameters));
+        assert!(!bundle.verify::<Wiring>(&Params::new(K + 1)));
+        // The parity column changes the verifying key.
+        assert!(!bundle.verify::<PermutationCircuit<Fp, 5, true>>(&public_parameters));
+
+        let mut wrong_instance = bundle.instances().to_vec();
+        wrong_instance[0][0].swap(0, 1);
+        let wrong_bundle = Bundle::new(
+            bundle.k(),
+            bundle.pinned_verifying_key().to_owned(),
+            wrong_instance,
+            bundle.transcript().to_vec(),
+        );
+        assert!(!wrong_bundle.verify::<Wiring>(&public_parameters));
+
+        let wrong_key_bundle = Bundle::new(
+            bundle.k(),
+            String::new(),
+            bundle.instances().to_vec(),
+            bundle.transcript().to_vec(),
+        );
+        assert!(!wrong_key_bundle.verify::<Wiring>(&public_parameters));
+    }
 }
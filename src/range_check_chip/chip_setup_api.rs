@@ -0,0 +1,17 @@
+use super::*;
+
+impl<F: ff::PrimeField, const BITS: usize> RangeCheckChip<F, BITS> {
+    /// Constrains `value` to be in the range `[0, 2^BITS)`.
+    pub fn constrain_in_range(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Number<F>,
+    ) -> Result<(), Error> {
+        BitDecompositionChip::<F, BITS>::construct_with_name_prefix(
+            self.config().bit_decomposition_config.clone(),
+            self.name_prefix,
+        )
+        .decompose(layouter, value)
+        .map(|_bits| ())
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+impl<F: ff::PrimeField, const BITS: usize> RangeCheckChip<F, BITS> {
+    pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
+        Self {
+            config,
+            name_prefix,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `constrain_in_range` assigns, relative to the first row of its region,
+    /// see `BitDecompositionChip::occupied_cells`.
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        BitDecompositionChip::<F, BITS>::occupied_cells()
+    }
+
+    /// The range check reuses the bit decomposition gate:
+    /// a number is in `[0, 2^BITS)` if and only if it can be
+    /// decomposed in `BITS` bits.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        input_column: Column<Advice>,
+        bits_column: Column<Advice>,
+    ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
+        Self::try_configure(meta, input_column, bits_column).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `configure`, with the errors of `BitDecompositionChip::try_configure`.
+    pub fn try_configure(
+        meta: &mut ConstraintSystem<F>,
+        input_column: Column<Advice>,
+        bits_column: Column<Advice>,
+    ) -> Result<<Self as halo2_proofs::circuit::Chip<F>>::Config, ConfigError> {
+        Ok(RCConfig {
+            bit_decomposition_config: BitDecompositionChip::<F, BITS>::try_configure(
+                meta,
+                input_column,
+                bits_column,
+            )?,
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use halo2_proofs::{
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error},
+};
+
+use crate::{
+    bit_decomposition_chip::{BDConfig, BitDecompositionChip},
+    utilities::ConfigError,
+    Number,
+};
+
+/// in this module, we implement the functions needed to
+/// constrain a number to be in range.
+mod chip_setup_api;
+/// in this module, we implement the gate logic.
+mod gate_implementation;
+
+#[derive(Debug, Clone)]
+pub struct RangeCheckChip<F: ff::PrimeField, const BITS: usize> {
+    config: RCConfig<BITS>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
+    _marker: std::marker::PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RCConfig<const BITS: usize> {
+    /// The range check is enforced by decomposing
+    /// the number in `BITS` bits.
+    pub bit_decomposition_config: BDConfig<BITS>,
+}
+
+impl<F: ff::PrimeField, const BITS: usize> halo2_proofs::circuit::Chip<F>
+    for RangeCheckChip<F, BITS>
+{
+    type Config = RCConfig<BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    /// A circuit that proves that a secret number is in `[0, 2^BITS)`.
+    struct RangeCheckCircuit<const BITS: usize> {
+        value: Value<Fp>,
+    }
+
+    impl<const BITS: usize> Circuit<Fp> for RangeCheckCircuit<BITS> {
+        type Config = RCConfig<BITS>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let input_column = meta.advice_column();
+            let bits_column = meta.advice_column();
+            RangeCheckChip::<Fp, BITS>::configure(meta, input_column, bits_column)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = RangeCheckChip::<Fp, BITS>::construct(config);
+
+            let value = layouter.assign_region(
+                || "value",
+                |mut region| {
+                    region
+                        .assign_advice(
+                            || "value",
+                            chip.config().bit_decomposition_config.input_column,
+                            0,
+                            || self.value,
+                        )
+                        .map(Number)
+                },
+            )?;
+
+            chip.constrain_in_range(layouter.namespace(|| "range check"), value)
+        }
+    }
+
+    #[test]
+    /// Test the range check chip with the mock prover,
+    /// with the biggest number in range, and with the smallest out of range.
+    fn mock_range_check() {
+        const POW_OF_2_MAX_ROWS: u32 = 5;
+
+        let circuit = RangeCheckCircuit::<8> {
+            value: Value::known(Fp::from(255)),
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+
+        let circuit = RangeCheckCircuit::<8> {
+            value: Value::known(Fp::from(256)),
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert!(prover.verify().is_err(), "Bogous proof was accepted");
+    }
+
+    #[test]
+    /// Test that the range check chip occupies the cells
+    /// of the bit decomposition it relies on, each one once.
+    fn range_check_chip_occupied_cells() {
+        use crate::bit_decomposition_chip::BitDecompositionChip;
+
+        let cells = RangeCheckChip::<Fp, 8>::occupied_cells();
+        assert_eq!(cells, BitDecompositionChip::<Fp, 8>::occupied_cells());
+        assert_eq!(
+            cells
+                .iter()
+                .collect::<std::collections::BTreeSet<_>>()
+                .len(),
+            cells.len()
+        );
+    }
+}
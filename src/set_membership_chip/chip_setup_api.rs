@@ -0,0 +1,87 @@
+use halo2_proofs::circuit::Region;
+
+use super::*;
+
+impl<F: ff::Field, const N_SYMBOLS: usize, const MUL_BATCH_SIZE: usize, const N_COLUMNS: usize>
+    SetMembershipChip<F, N_SYMBOLS, MUL_BATCH_SIZE, N_COLUMNS>
+{
+    /// Loads `symbols` in the circuit, and constrains `input_cell`
+    /// to be equal to one of them.
+    pub fn constrain_membership(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input_cell: Number<F>,
+        symbols: [F; N_SYMBOLS],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || prefixed_name(self.name_prefix, "assign set membership chip advice"),
+            |mut region| {
+                let config = self.config();
+
+                // We activate the gate
+                config.s_member.enable(&mut region, 0)?;
+
+                // We lay out the symbols in the symbols column,
+                // as expected by the gate implementation,
+                // and constrain them to be constants.
+                for (idx, symbol) in symbols.iter().enumerate() {
+                    region.assign_advice_from_constant(
+                        || format!("{idx}-th symbol"),
+                        config.symbols_column,
+                        idx,
+                        *symbol,
+                    )?;
+                }
+
+                // We build `assign_new_cell`, a closure that, given a value,
+                // allocates it in the next available advice cell. The order of
+                // the cells is consistent with the one in the gate implementation.
+                let mut cell_counter = DivModCounter::new_runtime_mod(0, 0, N_COLUMNS)
+                    .map(|(div, res)| (config.columns[res], div));
+                let mut assign_new_cell = |region: &mut Region<'_, F>, value| {
+                    let (column, offset) = cell_counter.next().expect("the iterator never ends");
+                    region
+                        .assign_advice(|| "set membership advice cell", column, offset, || value)
+                        .map(Number)
+                };
+
+                let input_value = input_cell.value().cloned();
+
+                // All the constraints are defined in terms of the value
+                // in the first allocated cell, so we copy the input there.
+                let local_copy_of_input_cell = assign_new_cell(&mut region, input_value)?;
+                region.constrain_equal(input_cell.cell(), local_copy_of_input_cell.cell())?;
+
+                // An iterator that yields the sequence
+                // of the terms to be multiplied together.
+                let mut factors = symbols
+                    .into_iter()
+                    .map(|symbol| input_value - Value::known(symbol));
+                // A closure that integrates `batch_size`
+                // new factors in the product.
+                let mut product_batch = |product_so_far, batch_size| {
+                    (&mut factors)
+                        .take(batch_size)
+                        .fold(product_so_far, |prod, e| prod * e)
+                };
+
+                let mut product = Value::known(F::ONE);
+
+                // As in the gate implementation, we add factors in groups of
+                // `mul_batch_size`, until possible
+                for _batch_nr in 0..N_SYMBOLS / MUL_BATCH_SIZE {
+                    product = product_batch(product, MUL_BATCH_SIZE);
+                    assign_new_cell(&mut region, product)?;
+                }
+
+                // Then, we apply a smaller batch for the remaining terms.
+                if N_SYMBOLS % MUL_BATCH_SIZE != 0 {
+                    product = product_batch(product, N_SYMBOLS % MUL_BATCH_SIZE);
+                    assign_new_cell(&mut region, product)?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+impl<F: ff::Field, const N_SYMBOLS: usize, const MUL_BATCH_SIZE: usize, const N_COLUMNS: usize>
+    SetMembershipChip<F, N_SYMBOLS, MUL_BATCH_SIZE, N_COLUMNS>
+{
+    pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
+        Self {
+            config,
+            name_prefix,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `constrain_membership` assigns, relative to the first row of its region,
+    /// in the order in which they are assigned. The column index is the
+    /// position of the column in `SMConfig::columns`,
+    /// and `symbols_column` has index `N_COLUMNS`.
+    ///
+    /// The symbols come first, followed by the copy of the input and by the
+    /// product of each batch of factors, laid out by the `DivModCounter`
+    /// as in the gate, which skips the first cell.
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        let mut cells = Vec::from_iter((0..N_SYMBOLS).map(|row_idx| (N_COLUMNS, row_idx)));
+        cells.extend(
+            DivModCounter::new_runtime_mod(0, 0, N_COLUMNS)
+                .take(1 + N_SYMBOLS.div_ceil(MUL_BATCH_SIZE))
+                .map(|(div, remainder)| (remainder, div)),
+        );
+        cells
+    }
+
+    /// The symbols are assigned to `symbols_column` out of constants,
+    /// that the floor planner lays out in `constant_column`, so that
+    /// they are part of the verifying key.
+    ///
+    /// Panics if the parameters are not valid, see `try_configure`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: [Column<Advice>; N_COLUMNS],
+        symbols_column: Column<Advice>,
+        constant_column: Column<Fixed>,
+    ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
+        Self::try_configure(meta, columns, symbols_column, constant_column)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as `configure`, but if `N_COLUMNS` or `MUL_BATCH_SIZE` are zero,
+    /// an error is returned, and `meta` is left untouched.
+    pub fn try_configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: [Column<Advice>; N_COLUMNS],
+        symbols_column: Column<Advice>,
+        constant_column: Column<Fixed>,
+    ) -> Result<<Self as halo2_proofs::circuit::Chip<F>>::Config, ConfigError> {
+        if N_COLUMNS == 0 {
+            return Err(ConfigError::NoColumns);
+        }
+        if MUL_BATCH_SIZE == 0 {
+            return Err(ConfigError::EmptyMultiplicationBatch);
+        }
+
+        let s_member = meta.selector();
+
+        for col in columns.iter() {
+            meta.enable_equality(*col);
+        }
+        meta.enable_equality(symbols_column);
+        meta.enable_constant(constant_column);
+
+        meta.create_gate("set membership gate", |meta| {
+            let s_member = meta.query_selector(s_member);
+
+            // The symbols are laid out in the first `N_SYMBOLS` rows
+            // of `symbols_column`, relative to the start of the gate region.
+            // Fixed columns can only be queried at the current row,
+            // this is why the symbols are not laid out in a fixed column.
+            let symbols: Vec<_> = (0..N_SYMBOLS)
+                .map(|idx| meta.query_advice(symbols_column, Rotation(idx as i32)))
+                .collect();
+
+            let mut next_cell_iter = DivModCounter::new_runtime_mod(0, 0, N_COLUMNS)
+                .cell_positions(&columns)
+                .map(|(column, rotation)| meta.query_advice(column, rotation));
+            let mut next_cell = || next_cell_iter.next().expect("the iterator never ends");
+
+            let value_cell = next_cell();
+
+            // The product of the factors `value - symbol`.
+            let (mut constraints, last_cell) = batched_running_product(
+                &s_member,
+                symbols
+                    .into_iter()
+                    .map(|symbol| value_cell.clone() - symbol),
+                MUL_BATCH_SIZE,
+                next_cell,
+            );
+
+            // The value is one of the symbols if and only if
+            // the product of all the factors vanishes.
+            // If there are no symbols, the product is `F::ONE`,
+            // and no value can satisfy the gate.
+            constraints.push(s_member * last_cell);
+
+            constraints
+        });
+
+        Ok(SMConfig {
+            columns,
+            symbols_column,
+            s_member,
+        })
+    }
+}
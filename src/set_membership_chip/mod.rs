@@ -0,0 +1,55 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{Chip, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    utilities::{batched_running_product, prefixed_name, ConfigError, DivModCounter},
+    Number,
+};
+
+mod chip_setup_api;
+mod gate_implementation;
+
+#[derive(Debug, Clone)]
+pub struct SetMembershipChip<
+    F: ff::Field,
+    const N_SYMBOLS: usize,
+    const MUL_BATCH_SIZE: usize,
+    const N_COLUMNS: usize,
+> {
+    config: SMConfig<N_COLUMNS>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SMConfig<const N_COLUMNS: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    pub columns: [Column<Advice>; N_COLUMNS],
+    /// The symbols are laid out in this column, constrained to be constants.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    pub symbols_column: Column<Advice>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_member: Selector,
+}
+
+impl<F: ff::Field, const N_SYMBOLS: usize, const MUL_BATCH_SIZE: usize, const N_COLUMNS: usize>
+    halo2_proofs::circuit::Chip<F> for SetMembershipChip<F, N_SYMBOLS, MUL_BATCH_SIZE, N_COLUMNS>
+{
+    type Config = SMConfig<N_COLUMNS>;
+    type Loaded = ();
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
@@ -0,0 +1,170 @@
+use halo2_proofs::{
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{ConstraintSystem, Error},
+};
+
+use crate::{
+    set_membership_chip::{SMConfig, SetMembershipChip},
+    Number,
+};
+
+/// How many factors `value - symbol` are multiplied together in a single constraint.
+const MUL_BATCH_SIZE: usize = 3;
+
+/// How many advice columns are used to lay out the partial products.
+const N_COLUMNS: usize = 2;
+
+/// A circuit that proves that a secret value is one of the `N` public symbols,
+/// without revealing which one.
+///
+/// The symbols are constrained to be constants, so they are part of the
+/// circuit's verifying key, as it happens in `SudokuCircuit`.
+#[derive(Clone, Debug)]
+pub struct SetMembershipCircuit<F: ff::Field, const N: usize> {
+    value: Value<F>,
+    symbols: [F; N],
+}
+
+impl<F: ff::Field, const N: usize> SetMembershipCircuit<F, N> {
+    pub fn new(value: F, symbols: [F; N]) -> Self {
+        Self {
+            value: Value::known(value),
+            symbols,
+        }
+    }
+
+    /// Given a symbols array, outputs an instance of the circuit
+    /// without witness values
+    pub fn circuit_wiring_from_symbols(symbols: [F; N]) -> Self {
+        Self {
+            value: Value::unknown(),
+            symbols,
+        }
+    }
+}
+
+impl<F: ff::Field, const N: usize> halo2_proofs::plonk::Circuit<F> for SetMembershipCircuit<F, N> {
+    type Config = SMConfig<N_COLUMNS>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::circuit_wiring_from_symbols(self.symbols)
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let columns = [(); N_COLUMNS].map(|_| meta.advice_column());
+        let symbols_column = meta.advice_column();
+        let constant_column = meta.fixed_column();
+
+        SetMembershipChip::<F, N, MUL_BATCH_SIZE, N_COLUMNS>::configure(
+            meta,
+            columns,
+            symbols_column,
+            constant_column,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let membership_chip =
+            SetMembershipChip::<F, N, MUL_BATCH_SIZE, N_COLUMNS>::construct(config);
+
+        let input_item = layouter
+            .namespace(|| "allocation of input item")
+            .assign_region(
+                || "allocation of input item",
+                |mut region| {
+                    region
+                        .assign_advice(
+                            || "input item",
+                            membership_chip.config().columns[0],
+                            0,
+                            || self.value,
+                        )
+                        .map(Number)
+                },
+            )?;
+
+        membership_chip.constrain_membership(
+            layouter.namespace(|| "set membership"),
+            input_item,
+            self.symbols,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    const POW_OF_2_MAX_ROWS: u32 = 5;
+
+    fn symbols() -> [Fp; 7] {
+        [2_u64, 3, 5, 7, 11, 13, 17].map(Fp::from)
+    }
+
+    #[test]
+    /// Test that every element of the set is accepted as a member.
+    fn mock_set_membership() {
+        for value in symbols() {
+            let circuit = SetMembershipCircuit::new(value, symbols());
+
+            let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+                .expect("Proof generation goes wrong");
+            assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+        }
+    }
+
+    #[test]
+    /// Test that values outside of the set are rejected.
+    fn mock_set_membership_outsider() {
+        for value in [0_u64, 1, 4, 6, 19].map(Fp::from) {
+            let circuit = SetMembershipCircuit::new(value, symbols());
+
+            let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+                .expect("Proof generation goes wrong");
+            assert!(prover.verify().is_err(), "Bogous proof was accepted");
+        }
+    }
+
+    #[test]
+    /// Test the coordinates of the cells occupied by the set membership chip
+    /// for a known configuration, and that they are distinct and fit
+    /// in the columns of the chip, for a range of configurations.
+    fn set_membership_chip_occupied_cells() {
+        // The 7 symbols, the input copy, two full batches, and a batch with one factor.
+        assert_eq!(
+            SetMembershipChip::<Fp, 7, MUL_BATCH_SIZE, N_COLUMNS>::occupied_cells(),
+            vec![
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (2, 3),
+                (2, 4),
+                (2, 5),
+                (2, 6),
+                (1, 0),
+                (0, 1),
+                (1, 1),
+                (0, 2)
+            ]
+        );
+
+        macro_rules! check_occupied_cells {
+            ($(<$N_SYMBOLS: literal, $MUL_BATCH_SIZE: literal, $N_COLUMNS: literal>),*) => {$({
+                let cells =
+                    SetMembershipChip::<Fp, $N_SYMBOLS, $MUL_BATCH_SIZE, $N_COLUMNS>::occupied_cells();
+                assert_eq!(
+                    cells.iter().collect::<std::collections::BTreeSet<_>>().len(),
+                    cells.len()
+                );
+                assert!(cells.iter().all(|&(col, _)| col <= $N_COLUMNS));
+            })*};
+        }
+        check_occupied_cells!(<0, 1, 1>, <1, 1, 1>, <7, 3, 2>, <9, 9, 4>, <20, 1, 3>);
+    }
+}
@@ -0,0 +1,316 @@
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    permutation_chip::{PConfig, PermutationChip},
+    utilities::running_accumulator_gates,
+    Number,
+};
+
+use halo2_proofs::{
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+/// A circuit that proves that two secret sequences are a reordering
+/// of one another, i.e. a verifiable shuffle.
+///
+/// Both the input and the output sequences are kept secret, and each of
+/// them is only committed to, by exposing its sum and its sum of squares.
+/// The instance column contains, in this order, the sum and the sum of
+/// squares of the input values, and the sum and the sum of squares of the
+/// output values, see `commitments`.
+pub struct ShuffleCircuit<F: ff::Field, const N_OBJECTS: usize> {
+    input_items: [Value<F>; N_OBJECTS],
+    output_items: [Value<F>; N_OBJECTS],
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> ShuffleCircuit<F, N_OBJECTS> {
+    /// Builds a circuit without checking its inputs.
+    /// Whether the output items are a reordering of the input ones
+    /// is checked by the circuit.
+    pub fn new_unchecked(
+        input_items: [Value<F>; N_OBJECTS],
+        output_items: [Value<F>; N_OBJECTS],
+    ) -> Self {
+        Self {
+            input_items,
+            output_items,
+        }
+    }
+
+    /// Builds a circuit from two sequences.
+    /// Returns an error if the sequences are not a reordering of one another.
+    pub fn try_new(input_items: [F; N_OBJECTS], output_items: [F; N_OBJECTS]) -> Result<Self, ()> {
+        let mut used = [false; N_OBJECTS];
+        for input in input_items.iter() {
+            // Repeated values are matched to the output positions in order.
+            let position = (0..N_OBJECTS)
+                .find(|&position| !used[position] && output_items[position] == *input)
+                .ok_or(())?;
+            used[position] = true;
+        }
+
+        Ok(Self::new_unchecked(
+            input_items.map(Value::known),
+            output_items.map(Value::known),
+        ))
+    }
+
+    /// The sum and the sum of squares of `items`,
+    /// i.e. the public commitment to a sequence.
+    pub fn commitments(items: &[F; N_OBJECTS]) -> [F; 2] {
+        items
+            .iter()
+            .fold([F::ZERO; 2], |[sum, sum_of_squares], item| {
+                [sum + item, sum_of_squares + item.square()]
+            })
+    }
+
+    /// The values the instance column has to contain
+    /// for the given input and output sequences.
+    pub fn instance(input_items: &[F; N_OBJECTS], output_items: &[F; N_OBJECTS]) -> Vec<F> {
+        Vec::from_iter(
+            Self::commitments(input_items)
+                .into_iter()
+                .chain(Self::commitments(output_items)),
+        )
+    }
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> Default for ShuffleCircuit<F, N_OBJECTS> {
+    fn default() -> Self {
+        Self::new_unchecked([Value::unknown(); N_OBJECTS], [Value::unknown(); N_OBJECTS])
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShuffleConfig<const N_OBJECTS: usize> {
+    pconfig: PConfig<N_OBJECTS>,
+    /// The columns where each item, the sum of the items up to it,
+    /// and the sum of their squares, are laid out, in this order.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    commitment_columns: [Column<Advice>; 3],
+    /// Enabled on the first item of a sequence.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_first: Selector,
+    /// Enabled on every item of a sequence but the first one.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_next: Selector,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    instance: Column<Instance>,
+}
+
+impl<F: ff::Field, const N_OBJECTS: usize> halo2_proofs::plonk::Circuit<F>
+    for ShuffleCircuit<F, N_OBJECTS>
+{
+    type Config = ShuffleConfig<N_OBJECTS>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        assert!(N_OBJECTS > 0, "There is nothing to shuffle.");
+
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let item_columns = [(); N_OBJECTS].map(|_| meta.advice_column());
+        let swap_selector_columns = (0..N_OBJECTS / 2).map(|_| meta.advice_column()).collect();
+
+        let commitment_columns = [(); 3].map(|_| meta.advice_column());
+        for col in commitment_columns {
+            meta.enable_equality(col);
+        }
+
+        let s_first = meta.selector();
+        let s_next = meta.selector();
+
+        running_accumulator_gates(
+            meta,
+            ["first item commitment", "next item commitment"],
+            [s_first, s_next],
+            [commitment_columns[1], commitment_columns[2]],
+            |meta, [sum, sum_of_squares]| {
+                let item = meta.query_advice(commitment_columns[0], Rotation::cur());
+                [sum + item.clone(), sum_of_squares + item.clone() * item]
+            },
+        );
+
+        ShuffleConfig {
+            pconfig: PermutationChip::configure(meta, item_columns, swap_selector_columns),
+            commitment_columns,
+            s_first,
+            s_next,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let permutation_chip = PermutationChip::<N_OBJECTS, F>::construct(config.pconfig);
+
+        let mut load_items = |name: &'static str, items: [Value<F>; N_OBJECTS]| {
+            layouter.namespace(|| name).assign_region(
+                || name,
+                |mut region| {
+                    let item_columns = permutation_chip.config().get_item_columns();
+                    let mut cells = vec![];
+                    for (idx, value) in items.into_iter().enumerate() {
+                        cells.push(
+                            region
+                                .assign_advice(
+                                    || format!("{idx}-th {name}"),
+                                    item_columns[idx],
+                                    0,
+                                    || value,
+                                )
+                                .map(Number)?,
+                        );
+                    }
+                    Ok::<[Number<F>; N_OBJECTS], Error>(core::array::from_fn(|idx| {
+                        cells[idx].clone()
+                    }))
+                },
+            )
+        };
+        let input_cells = load_items("input item", self.input_items)?;
+        let output_cells = load_items("output item", self.output_items)?;
+
+        // The input items, once permuted, have to be equal to the output items.
+        permutation_chip.apply_permutation_to_target(
+            layouter.namespace(|| "shuffling the input"),
+            input_cells.clone(),
+            output_cells.clone(),
+        )?;
+
+        // We accumulate the sum and the sum of squares of each sequence,
+        // and expose the final values.
+        for (sequence_idx, (name, cells)) in [("input", input_cells), ("output", output_cells)]
+            .into_iter()
+            .enumerate()
+        {
+            let [sum, sum_of_squares] = layouter
+                .namespace(|| format!("{name} commitment"))
+                .assign_region(
+                    || format!("{name} commitment"),
+                    |mut region| {
+                        let [item_column, sum_column, sum_of_squares_column] =
+                            config.commitment_columns;
+
+                        let mut sum = Value::known(F::ZERO);
+                        let mut sum_of_squares = Value::known(F::ZERO);
+                        let mut accumulator_cells = None;
+                        for (row, cell) in cells.iter().enumerate() {
+                            if row == 0 {
+                                config.s_first.enable(&mut region, row)?;
+                            } else {
+                                config.s_next.enable(&mut region, row)?;
+                            }
+
+                            let item = cell.copy_advice(
+                                || format!("{row}-th {name} item"),
+                                &mut region,
+                                item_column,
+                                row,
+                            )?;
+                            sum = sum + item.value().copied();
+                            sum_of_squares = sum_of_squares + item.value().map(|x| x.square());
+
+                            accumulator_cells = Some([
+                                region.assign_advice(
+                                    || format!("{row}-th partial sum of the {name} items"),
+                                    sum_column,
+                                    row,
+                                    || sum,
+                                )?,
+                                region.assign_advice(
+                                    || {
+                                        format!(
+                                            "{row}-th partial sum of squares of the {name} items"
+                                        )
+                                    },
+                                    sum_of_squares_column,
+                                    row,
+                                    || sum_of_squares,
+                                )?,
+                            ]);
+                        }
+                        Ok(accumulator_cells.expect("there is at least one item"))
+                    },
+                )?;
+
+            let mut output_layouter = layouter.namespace(|| format!("public {name} commitment"));
+            output_layouter.constrain_instance(sum.cell(), config.instance, 2 * sequence_idx)?;
+            output_layouter.constrain_instance(
+                sum_of_squares.cell(),
+                config.instance,
+                2 * sequence_idx + 1,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied};
+    use halo2_proofs::pasta::Fp;
+
+    const N_OBJECTS: usize = 5;
+    const K: u32 = 5;
+
+    fn sequences() -> ([Fp; N_OBJECTS], [Fp; N_OBJECTS]) {
+        ([3, 1, 4, 1, 5].map(Fp::from), [1, 5, 3, 4, 1].map(Fp::from))
+    }
+
+    #[test]
+    /// Test that a genuine shuffle is accepted.
+    fn mock_shuffle() {
+        let (input, output) = sequences();
+        let circuit = ShuffleCircuit::<Fp, N_OBJECTS>::try_new(input, output)
+            .expect("the output is a shuffle of the input");
+
+        assert_satisfied(K, &circuit, vec![ShuffleCircuit::instance(&input, &output)]);
+    }
+
+    #[test]
+    /// Test that altered values are rejected, both when the commitments
+    /// agree with the altered values, and when they agree with the original ones.
+    fn mock_shuffle_altered_values() {
+        let (input, output) = sequences();
+
+        let mut altered_output = output;
+        altered_output[2] = Fp::from(9);
+        assert!(ShuffleCircuit::<Fp, N_OBJECTS>::try_new(input, altered_output).is_err());
+
+        let circuit = ShuffleCircuit::<Fp, N_OBJECTS>::new_unchecked(
+            input.map(Value::known),
+            altered_output.map(Value::known),
+        );
+        assert_rejected(
+            K,
+            &circuit,
+            vec![ShuffleCircuit::instance(&input, &altered_output)],
+        );
+        assert_rejected(K, &circuit, vec![ShuffleCircuit::instance(&input, &output)]);
+
+        // The values are a genuine shuffle, but the commitments are not theirs.
+        let circuit = ShuffleCircuit::<Fp, N_OBJECTS>::try_new(input, output)
+            .expect("the output is a shuffle of the input");
+        assert_rejected(
+            K,
+            &circuit,
+            vec![ShuffleCircuit::instance(&altered_output, &altered_output)],
+        );
+    }
+}
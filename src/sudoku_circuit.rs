@@ -1,6 +1,11 @@
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
 use crate::{
-    permutation_chip::PermutationChip, sudoku_problem_chip::SudokuProblemChip,
-    utilities::RegionSequenceAssignment,
+    line_permutation_gadget::LinePermutationGadget,
+    permutation_chip::PermutationChip,
+    sudoku_problem_chip::SudokuProblemChip,
+    utilities::{sudoku_line_iter, transpose_value_grid, RegionSequenceAssignment},
+    Number, SudokuPartCircuit,
 };
 
 use halo2_proofs::{
@@ -9,12 +14,34 @@ use halo2_proofs::{
     plonk::{Column, ConstraintSystem, Error, Fixed, Instance},
 };
 use std::collections::{BTreeMap, BTreeSet};
-use try_collect::ForceCollect;
 
 /// A circuit that proves that the input and output values are a permutation of one another.
+///
+/// The symbols are sorted by their byte representation whichever constructor
+/// the circuit is built with, including `circuit_wiring_from_symbols`,
+/// so the order in which they are given does not matter: circuits built
+/// from the same symbols in different orders lay out the same symbols column,
+/// and assign the same witness.
+///
+/// The circuit can also prove that a single solution is compatible with
+/// several problems at once, see `try_new_multi`. In that case, the
+/// `idx`-th problem is laid out in the rows `idx * SIZE..(idx + 1) * SIZE`
+/// of the instance columns.
+///
+/// `SIZE` has to be `SIZE_SQRT * SIZE_SQRT`. Building or configuring
+/// a circuit with inconsistent constants fails to compile,
+/// see `SIZE_IS_SQUARE`:
+///
+/// ```compile_fail
+/// use halo2_proofs::{circuit::Value, pasta::Fp};
+/// use zk_halo2_playground::SudokuCircuit;
+///
+/// let symbols = core::array::from_fn(|n| Fp::from(n as u64 + 1));
+/// let _ = SudokuCircuit::<Fp, 9, 2>::new_unchecked(Value::unknown(), Value::unknown(), symbols);
+/// ```
 #[derive(Clone, Debug)]
 pub struct SudokuCircuit<F: ff::Field, const SIZE: usize, const SIZE_SQRT: usize> {
-    problem: Value<[[F; SIZE]; SIZE]>,
+    problems: Vec<Value<[[F; SIZE]; SIZE]>>,
     solution: Value<[[F; SIZE]; SIZE]>,
     symbols: [F; SIZE],
 }
@@ -22,15 +49,19 @@ pub struct SudokuCircuit<F: ff::Field, const SIZE: usize, const SIZE_SQRT: usize
 impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize>
     SudokuCircuit<F, SIZE, SIZE_SQRT>
 {
+    /// Builds a circuit without checking its inputs.
+    /// The symbols are still sorted, see `canonical_symbols`, so that the circuit
+    /// lays out the same symbols column as `circuit_wiring_from_symbols`.
     pub fn new_unchecked(
         problem: Value<[[F; SIZE]; SIZE]>,
         solution: Value<[[F; SIZE]; SIZE]>,
         symbols: [F; SIZE],
     ) -> Self {
+        let () = Self::SIZE_IS_SQUARE;
         Self {
-            problem,
+            problems: vec![problem],
             solution,
-            symbols,
+            symbols: Self::canonical_symbols(symbols),
         }
     }
 
@@ -39,11 +70,17 @@ impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize>
         solution: [[F; SIZE]; SIZE],
         symbols: [F; SIZE],
     ) -> Result<Self, ()> {
-        // We check that the compile time constants are consistent
-        if SIZE != SIZE_SQRT * SIZE_SQRT {
-            return Err(());
-        }
+        Self::try_new_with_validated_symbols(problem, solution, &Self::validate_symbols(symbols)?)
+    }
 
+    /// Checks that `symbols` can be used as the symbols of a sudoku,
+    /// i.e. that they do not contain duplicates, and that `F::ZERO`,
+    /// which marks the empty cells of a problem, is not a symbol.
+    ///
+    /// The result can be reused to build many circuits sharing
+    /// the same symbols with `try_new_with_validated_symbols`.
+    /// The validated symbols are sorted, see `canonical_symbols`.
+    pub fn validate_symbols(symbols: [F; SIZE]) -> Result<ValidatedSymbols<F, SIZE>, ()> {
         // We check that the provided symbols do not contain duplicates
         let mut duplicate_detector = BTreeSet::<Vec<u8>>::new();
         for s in symbols {
@@ -59,10 +96,67 @@ impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize>
             return Err(());
         }
 
-        // We check that the problem contains only symbols or `F::ZERO` entries
-        if !problem.iter().all(|col| {
+        Ok(ValidatedSymbols {
+            symbols: Self::canonical_symbols(symbols),
+            symbol_set: duplicate_detector,
+        })
+    }
+
+    /// Sorts `symbols` by their byte representation, so that the ordinal
+    /// of each symbol, which determines the permutations applied in the circuit
+    /// and the layout of the symbols column, does not depend on the order
+    /// the symbols are given in.
+    fn canonical_symbols(mut symbols: [F; SIZE]) -> [F; SIZE] {
+        symbols.sort_by(|left, right| left.to_repr().as_ref().cmp(right.to_repr().as_ref()));
+        symbols
+    }
+
+    /// Same as `try_new`, but the checks on the symbols are skipped,
+    /// since they were already performed by `validate_symbols`.
+    /// The problem and solution grids are still checked.
+    pub fn try_new_with_validated_symbols(
+        problem: [[F; SIZE]; SIZE],
+        solution: [[F; SIZE]; SIZE],
+        validated_symbols: &ValidatedSymbols<F, SIZE>,
+    ) -> Result<Self, ()> {
+        Self::try_new_multi_with_validated_symbols(vec![problem], solution, validated_symbols)
+    }
+
+    /// Same as `try_new`, but the circuit proves that `solution`
+    /// is compatible with each one of `problems`.
+    /// The solution grid is only checked to be a sudoku solution once,
+    /// which is cheaper than proving each problem separately.
+    pub fn try_new_multi(
+        problems: Vec<[[F; SIZE]; SIZE]>,
+        solution: [[F; SIZE]; SIZE],
+        symbols: [F; SIZE],
+    ) -> Result<Self, ()> {
+        Self::try_new_multi_with_validated_symbols(
+            problems,
+            solution,
+            &Self::validate_symbols(symbols)?,
+        )
+    }
+
+    /// Same as `try_new_multi`, but the checks on the symbols are skipped,
+    /// since they were already performed by `validate_symbols`.
+    pub fn try_new_multi_with_validated_symbols(
+        problems: Vec<[[F; SIZE]; SIZE]>,
+        solution: [[F; SIZE]; SIZE],
+        validated_symbols: &ValidatedSymbols<F, SIZE>,
+    ) -> Result<Self, ()> {
+        let () = Self::SIZE_IS_SQUARE;
+
+        if problems.is_empty() {
+            return Err(());
+        }
+
+        let symbol_set = &validated_symbols.symbol_set;
+
+        // We check that the problems contain only symbols or `F::ZERO` entries
+        if !problems.iter().flatten().all(|col| {
             col.iter()
-                .all(|n| *n == F::ZERO || duplicate_detector.contains(n.to_repr().as_ref()))
+                .all(|n| *n == F::ZERO || symbol_set.contains(n.to_repr().as_ref()))
         }) {
             return Err(());
         }
@@ -70,59 +164,371 @@ impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize>
         // We check that the solution only contains symbols
         if !solution.iter().all(|col| {
             col.iter()
-                .all(|n| duplicate_detector.contains(n.to_repr().as_ref()))
+                .all(|n| symbol_set.contains(n.to_repr().as_ref()))
         }) {
             return Err(());
         }
 
         Ok(Self {
-            problem: Value::known(problem),
+            problems: problems.into_iter().map(Value::known).collect(),
             solution: Value::known(solution),
-            symbols,
+            symbols: validated_symbols.symbols,
         })
     }
+
+    /// If the solution is known, returns the permutations that `synthesize`
+    /// applies to the columns, rows, and regions of the solution grid,
+    /// to align each of them to the symbols.
+    /// Each permutation is labeled with the group of cells it applies to.
+    ///
+    /// This does not involve the proving system, and is meant to find out
+    /// which group of cells is wrong, when a proof fails.
+    /// If the solution contains a value that is not a symbol,
+    /// `None` is returned as well.
+    pub fn debug_permutations(&self) -> Option<Vec<(String, [usize; SIZE])>> {
+        let symbol_to_ordinal = self.symbol_to_ordinal();
+
+        // `Value` does not allow to extract its content,
+        // so we move it out through a side effect.
+        let mut permutations = None;
+        self.solution.map(|solution| {
+            permutations = Self::permutation_groups()
+                .into_iter()
+                .map(|(label, positions)| {
+                    let permutation = positions
+                        .into_iter()
+                        .map(|(col_idx, row_idx)| {
+                            symbol_to_ordinal
+                                .get(solution[col_idx][row_idx].to_repr().as_ref())
+                                .copied()
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    Some((
+                        label,
+                        permutation
+                            .try_into()
+                            .expect("the number of items is correct"),
+                    ))
+                })
+                .collect();
+        });
+        permutations
+    }
+
+    /// Checks, without involving the proving system, that `solution`
+    /// is a valid sudoku solution, i.e. that each of its columns, rows,
+    /// and regions contains every one of `symbols` exactly once.
+    pub fn is_valid_solution(solution: &[[F; SIZE]; SIZE], symbols: &[F; SIZE]) -> bool {
+        if SIZE != SIZE_SQRT * SIZE_SQRT {
+            return false;
+        }
+
+        let symbol_to_ordinal = Self::symbol_to_ordinal_from_symbols(symbols);
+
+        Self::permutation_groups()
+            .into_iter()
+            .all(|(_, positions)| {
+                let mut seen = [false; SIZE];
+                positions.into_iter().all(|(col_idx, row_idx)| {
+                    match symbol_to_ordinal.get(solution[col_idx][row_idx].to_repr().as_ref()) {
+                        Some(&ordinal) if !seen[ordinal] => {
+                            seen[ordinal] = true;
+                            true
+                        }
+                        _ => false,
+                    }
+                })
+            })
+    }
+
+    /// Finds a solution of `problem`, in which the empty cells are
+    /// marked by `F::ZERO`, by backtracking over its empty cells.
+    ///
+    /// Returns `None` if the problem contains a value that is neither
+    /// a symbol nor `F::ZERO`, if its clues are in conflict with each other,
+    /// or if it has no solution. If the problem has many solutions,
+    /// one of them is returned.
+    pub fn solve(problem: &[[F; SIZE]; SIZE], symbols: &[F; SIZE]) -> Option<[[F; SIZE]; SIZE]> {
+        if SIZE != SIZE_SQRT * SIZE_SQRT {
+            return None;
+        }
+
+        let symbol_to_ordinal = Self::symbol_to_ordinal_from_symbols(symbols);
+        let region_of = |col_idx: usize, row_idx: usize| {
+            (col_idx / SIZE_SQRT) * SIZE_SQRT + row_idx / SIZE_SQRT
+        };
+
+        // `used[0]`, `used[1]`, `used[2]` keep track of the ordinals
+        // that appear in each column, row, and region, respectively.
+        let mut used = [[[false; SIZE]; SIZE]; 3];
+        let mut ordinals = [[None; SIZE]; SIZE];
+        let mut empty_cells = vec![];
+        for (col_idx, column) in problem.iter().enumerate() {
+            for (row_idx, value) in column.iter().enumerate() {
+                if *value == F::ZERO {
+                    empty_cells.push((col_idx, row_idx));
+                    continue;
+                }
+                let ordinal = *symbol_to_ordinal.get(value.to_repr().as_ref())?;
+                for (group_used, group_idx) in
+                    used.iter_mut()
+                        .zip([col_idx, row_idx, region_of(col_idx, row_idx)])
+                {
+                    if group_used[group_idx][ordinal] {
+                        return None;
+                    }
+                    group_used[group_idx][ordinal] = true;
+                }
+                ordinals[col_idx][row_idx] = Some(ordinal);
+            }
+        }
+
+        // We fill the empty cells in order, and when a cell cannot
+        // be filled, we go back to the previous one and try its next ordinal.
+        let mut next_candidate = vec![0; empty_cells.len()];
+        let mut cell_nr = 0;
+        while cell_nr < empty_cells.len() {
+            let (col_idx, row_idx) = empty_cells[cell_nr];
+            let group_indices = [col_idx, row_idx, region_of(col_idx, row_idx)];
+
+            // We free the ordinal that was previously tried for this cell, if any.
+            if let Some(ordinal) = ordinals[col_idx][row_idx].take() {
+                for (group_used, group_idx) in used.iter_mut().zip(group_indices) {
+                    group_used[group_idx][ordinal] = false;
+                }
+            }
+
+            let candidate = (next_candidate[cell_nr]..SIZE).find(|&ordinal| {
+                used.iter()
+                    .zip(group_indices)
+                    .all(|(group_used, group_idx)| !group_used[group_idx][ordinal])
+            });
+            match candidate {
+                Some(ordinal) => {
+                    for (group_used, group_idx) in used.iter_mut().zip(group_indices) {
+                        group_used[group_idx][ordinal] = true;
+                    }
+                    ordinals[col_idx][row_idx] = Some(ordinal);
+                    next_candidate[cell_nr] = ordinal + 1;
+                    cell_nr += 1;
+                }
+                None => {
+                    next_candidate[cell_nr] = 0;
+                    cell_nr = cell_nr.checked_sub(1)?;
+                }
+            }
+        }
+
+        Some(
+            ordinals.map(|column| {
+                column.map(|ordinal| symbols[ordinal.expect("every cell is filled")])
+            }),
+        )
+    }
+
+    /// Builds a circuit proving that `problem` is solvable, solving it with `solve`.
+    /// Only the problem is made public, and the solution is kept secret.
+    ///
+    /// Returns an error if the symbols are not valid, see `validate_symbols`,
+    /// or if the problem has no solution.
+    pub fn prove_solvable(problem: [[F; SIZE]; SIZE], symbols: [F; SIZE]) -> Result<Self, ()> {
+        let validated_symbols = Self::validate_symbols(symbols)?;
+        let solution = Self::solve(&problem, validated_symbols.symbols()).ok_or(())?;
+        Self::try_new_with_validated_symbols(problem, solution, &validated_symbols)
+    }
+
+    /// Maps the byte representation of each symbol to its index.
+    fn symbol_to_ordinal(&self) -> BTreeMap<Vec<u8>, usize> {
+        Self::symbol_to_ordinal_from_symbols(&self.symbols)
+    }
+
+    /// Same as `symbol_to_ordinal`, for arbitrary symbols.
+    fn symbol_to_ordinal_from_symbols(symbols: &[F; SIZE]) -> BTreeMap<Vec<u8>, usize> {
+        BTreeMap::from_iter(
+            symbols
+                .iter()
+                .enumerate()
+                .map(|(idx, sym)| (sym.to_repr().as_ref().to_owned(), idx)),
+        )
+    }
+
+    /// Lays out the whole sudoku circuit, as `synthesize` does,
+    /// and returns the cells of the solution grid, so that circuits
+    /// extending the sudoku one can impose further constraints on them.
+    pub(crate) fn synthesize_sudoku(
+        &self,
+        config: SudokuConfig<SIZE>,
+        layouter: impl Layouter<F>,
+    ) -> Result<[[Number<F>; SIZE]; SIZE], Error> {
+        let all_lines = Vec::from_iter(0..3 * SIZE);
+        self.synthesize_sudoku_lines(config, layouter, &all_lines)
+    }
+
+    /// Same as `synthesize_sudoku`, but only the lines with the given indices,
+    /// in the order of `sudoku_line_iter`, are constrained to contain
+    /// every symbol once. The grids are laid out, and checked to be
+    /// compatible, in full.
+    pub(crate) fn synthesize_sudoku_lines(
+        &self,
+        config: SudokuConfig<SIZE>,
+        mut layouter: impl Layouter<F>,
+        line_indices: &[usize],
+    ) -> Result<[[Number<F>; SIZE]; SIZE], Error> {
+        let (solution_cells, symbol_cells) =
+            self.synthesize_grids(&config, layouter.namespace(|| "sudoku grids"))?;
+
+        let solution_lines = Self::solution_lines(&solution_cells);
+        let lines = line_indices
+            .iter()
+            .map(|&line_idx| {
+                solution_lines
+                    .get(line_idx)
+                    .cloned()
+                    .ok_or(Error::Synthesis)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // We constrain each of the selected columns, rows, and regions
+        // to contain every one of symbols[0], ..., symbols[SIZE - 1] once.
+        LinePermutationGadget::<SIZE, F>::construct(config.permutation_config)
+            .enforce_lines_are_symbol_permutations(
+                layouter.namespace(|| "permutating columns, rows, and regions"),
+                &lines,
+                &symbol_cells,
+            )?;
+
+        Ok(solution_cells)
+    }
+}
+
+/// A set of sudoku symbols that passed the checks of
+/// `SudokuCircuit::validate_symbols`.
+#[derive(Clone, Debug)]
+pub struct ValidatedSymbols<F: ff::PrimeField, const SIZE: usize> {
+    symbols: [F; SIZE],
+    /// The byte representations of the symbols,
+    /// used to check that grid entries are symbols.
+    symbol_set: BTreeSet<Vec<u8>>,
+}
+
+impl<F: ff::PrimeField, const SIZE: usize> ValidatedSymbols<F, SIZE> {
+    pub fn symbols(&self) -> &[F; SIZE] {
+        &self.symbols
+    }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SudokuConfig<const SIZE: usize> {
     permutation_config: crate::permutation_chip::PConfig<SIZE>,
     grid_compatibility_config: crate::sudoku_problem_chip::SPConfig<SIZE>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
     public_problem_columns: [Column<Instance>; SIZE],
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
     sudoku_symbols_column: Column<Fixed>,
 }
 
-impl<F: ff::Field, const SIZE: usize, const SIZE_SQRT: usize> SudokuCircuit<F, SIZE, SIZE_SQRT> {
+impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize>
+    SudokuCircuit<F, SIZE, SIZE_SQRT>
+{
     /// Given a symbols array, outputs an instance of the circuit
-    /// without witness values
+    /// without witness values.
+    /// The symbols are sorted, see `canonical_symbols`.
     pub fn circuit_wiring_from_symbols(symbols: [F; SIZE]) -> Self {
+        Self::circuit_wiring_from_symbols_multi(symbols, 1)
+    }
+
+    /// Splits the circuit in `n_parts` circuits, each one constraining
+    /// a contiguous chunk of the `3 * SIZE` lines of the solution, in the order
+    /// of `sudoku_line_iter`, and exposing the solution cells it uses,
+    /// see `SudokuPartCircuit`. Every line ends up in exactly one part.
+    ///
+    /// `n_parts` is clamped to `1..=3 * SIZE`, so that no part is empty.
+    pub fn split_by_lines(&self, n_parts: usize) -> Vec<SudokuPartCircuit<F, SIZE, SIZE_SQRT>> {
+        let n_lines = 3 * SIZE;
+        let n_parts = n_parts.clamp(1, n_lines);
+        // The sizes of the chunks differ at most by one.
+        Vec::from_iter((0..n_parts).map(|part_idx| {
+            let lines = (part_idx * n_lines / n_parts)..((part_idx + 1) * n_lines / n_parts);
+            SudokuPartCircuit::new(self.clone(), Vec::from_iter(lines))
+        }))
+    }
+
+    /// Same as `circuit_wiring_from_symbols`, for a circuit that
+    /// proves the compatibility of a solution with `nr_problems` problems.
+    pub fn circuit_wiring_from_symbols_multi(symbols: [F; SIZE], nr_problems: usize) -> Self {
+        let () = Self::SIZE_IS_SQUARE;
         Self {
-            problem: Value::unknown(),
+            problems: vec![Value::unknown(); nr_problems],
             solution: Value::unknown(),
-            symbols,
+            symbols: Self::canonical_symbols(symbols),
         }
     }
 }
 
-// This is the only implementation happening for
-// F: ff::PrimeField instead of F: ff::Field.
-// The (tiny) loss of generality is due to the fact that
-// if we want to efficiently compute the witness for the sudoku,
-// then we have to be able to put an order relationship
-// among field elements, which is not possible with F: ff::Field
-// but can be done by using the binary representation of F
-// instances if F: ff::PrimeField.
-impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize> halo2_proofs::plonk::Circuit<F>
-    for SudokuCircuit<F, SIZE, SIZE_SQRT>
-{
-    type Config = SudokuConfig<SIZE>;
-    type FloorPlanner = SimpleFloorPlanner;
+impl<F: ff::Field, const SIZE: usize, const SIZE_SQRT: usize> SudokuCircuit<F, SIZE, SIZE_SQRT> {
+    /// Checks that the compile time constants are consistent,
+    /// i.e. that `SIZE == SIZE_SQRT * SIZE_SQRT`.
+    ///
+    /// The check happens when the constant is evaluated, i.e. when a function
+    /// that mentions it is instantiated, so that `cargo build` rejects
+    /// inconsistent constants, while `cargo check` may not.
+    pub const SIZE_IS_SQUARE: () = assert!(
+        SIZE == SIZE_SQRT * SIZE_SQRT,
+        "SIZE has to be the square of SIZE_SQRT"
+    );
+
+    /// If the problem is known, returns the grid that the circuit
+    /// constrains its public problem columns against, i.e. the public
+    /// instance of the proof, where the `idx`-th column of the grid
+    /// is the `idx`-th instance column. Blanks are `F::ZERO`.
+    ///
+    /// Returns `None` for circuits that prove the compatibility
+    /// of the solution with several problems.
+    pub fn problem_instance(&self) -> Option<[[F; SIZE]; SIZE]> {
+        let problem = match self.problems.as_slice() {
+            [problem] => problem,
+            _ => return None,
+        };
 
-    fn without_witnesses(&self) -> Self {
-        Self::circuit_wiring_from_symbols(self.symbols)
+        // `Value` does not allow to extract its content,
+        // so we move it out through a side effect.
+        let mut problem_instance = None;
+        problem.map(|problem| problem_instance = Some(problem));
+        problem_instance
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        assert_eq!(SIZE_SQRT * SIZE_SQRT, SIZE);
+    /// The symbols the circuit lays out in its symbols column, in order.
+    ///
+    /// The constructors sort them, see `canonical_symbols`,
+    /// so these may not be in the order they were given in.
+    pub fn symbols(&self) -> &[F; SIZE] {
+        &self.symbols
+    }
+
+    /// The groups of cells of a solution grid that have to contain
+    /// every symbol exactly once, that is, the columns, the rows, and
+    /// the regions of the grid, in this order.
+    ///
+    /// Each group is labeled, and consists of the `(col_idx, row_idx)`
+    /// positions of its cells.
+    fn permutation_groups() -> Vec<(String, [(usize, usize); SIZE])> {
+        Vec::from_iter(sudoku_line_iter::<SIZE, SIZE_SQRT>().enumerate().map(
+            |(line_idx, positions)| {
+                let label = match line_idx / SIZE {
+                    0 => format!("column {}", positions[0].0),
+                    1 => format!("row {}", positions[0].1),
+                    // A region is labeled by the position of its first cell.
+                    _ => format!("region ({}, {})", positions[0].0, positions[0].1),
+                };
+                (label, positions)
+            },
+        ))
+    }
+
+    /// The configuration shared by `SudokuCircuit` and `OrderedSudokuCircuit`.
+    pub(crate) fn configure_sudoku(meta: &mut ConstraintSystem<F>) -> SudokuConfig<SIZE> {
+        let () = Self::SIZE_IS_SQUARE;
 
         let public_problem_columns = [(); SIZE].map(|_| meta.instance_column());
         for col in public_problem_columns {
@@ -147,27 +553,19 @@ impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize> halo2_proofs:
         }
     }
 
-    fn synthesize(
+    /// Lays out the symbols, the problems, and the solution, constraining
+    /// the problems to the instance columns and to be compatible with the solution.
+    /// Returns the cells of the solution grid, and the cells of the symbols.
+    fn synthesize_grids(
         &self,
-        config: Self::Config,
+        config: &SudokuConfig<SIZE>,
         mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        let symbol_to_ordinal = BTreeMap::from_iter(
-            self.symbols
-                .into_iter()
-                .enumerate()
-                .map(|(idx, sym)| (sym.to_repr().as_ref().to_owned(), idx)),
-        );
-
+    ) -> Result<([[Number<F>; SIZE]; SIZE], [Number<F>; SIZE]), Error> {
         let grid_compatibility_chip =
             crate::sudoku_problem_chip::SudokuProblemChip::<SIZE, F>::construct(
-                config.grid_compatibility_config,
+                config.grid_compatibility_config.clone(),
             );
 
-        let permutation_chip = crate::permutation_chip::PermutationChip::<SIZE, F>::construct(
-            config.permutation_config,
-        );
-
         // First thing, we have to declare the symbols that can go in a sudoku cell.
         // In practice, those will be encoded as the field element generated from 1 up to SIZE
         let symbol_cells = layouter.namespace(|| "symbols declaration").assign_region(
@@ -185,148 +583,245 @@ impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize> halo2_proofs:
         // they are compatible (i.e. they describe the same sudoku problem).
         // We make sure they are compatible by feeding them
         // in the SudokuProblemChip chip.
-        let crate::sudoku_problem_chip::SudokuProblemAssignment {
-            problem_grid: problem_cells,
-            solution_grid: solution_cells,
-        } = grid_compatibility_chip.enforce_grid_compatibility(
-            layouter.namespace(|| "sudoku problem setup and problem-solution compatibility"),
-            self.problem
-                .transpose_array()
-                .map(|column| column.transpose_array()),
-            self.solution
-                .transpose_array()
-                .map(|column| column.transpose_array()),
-        )?;
+        // Every problem comes with its own copy of the solution grid,
+        // and we only keep the first one around.
+        let mut solution_cells = None;
+        for (problem_idx, problem) in self.problems.iter().enumerate() {
+            let crate::sudoku_problem_chip::SudokuProblemAssignment {
+                problem_grid: problem_cells,
+                solution_grid: solution_copy_cells,
+            } = grid_compatibility_chip.enforce_grid_compatibility(
+                layouter.namespace(|| {
+                    format!("sudoku problem {problem_idx} setup and problem-solution compatibility")
+                }),
+                transpose_value_grid(*problem),
+                transpose_value_grid(self.solution),
+            )?;
 
-        // We impose an equality constraint between the public output, and the `problem_cells`
-        for (public_column, advice_column) in
-            config.public_problem_columns.into_iter().zip(problem_cells)
-        {
-            for (row_idx, problem_cell) in advice_column.into_iter().map(|n| n.0.cell()).enumerate()
+            // We impose an equality constraint between the public output, and the `problem_cells`
+            for (public_column, advice_column) in
+                config.public_problem_columns.into_iter().zip(problem_cells)
             {
-                layouter.constrain_instance(problem_cell, public_column, row_idx)?;
+                for (row_idx, problem_cell) in
+                    advice_column.into_iter().map(|n| n.cell()).enumerate()
+                {
+                    layouter.constrain_instance(
+                        problem_cell,
+                        public_column,
+                        problem_idx * SIZE + row_idx,
+                    )?;
+                }
             }
-        }
 
-        // from an `F` value we can build an `usize` value via the
-        // symbol_to_ordinal map.
-        // This way, we can obtain an array of usize from an array of F.
-        // If the array contains all the symbols once, then
-        // the array we obtain is a permutation. This permutation
-        // is exactly the one needed to sort the symbols, and then
-        // compare them with the values in the symbols column.
-        let get_permutation =
-            |input: [F; SIZE]| input.map(|x| symbol_to_ordinal[x.to_repr().as_ref()]);
-
-        // We are going to apply a permutation to the cells of each of the solution's
-        // rows, columns, and regions, to make each one of them equal to
-        // symbols[0], ..., symbols[SIZE - 1]
-        //
-        // We are going to collect the output cells in this vectos, which we will later
-        // use to enforce equality over them.
-        let permutation_outputs = self
-            .solution
-            .zip(Value::known(Vec::with_capacity(3 * SIZE)))
-            .map(|(solution, mut permutation_outputs)| {
-                // For each column, we obtain its permutation that aligns it to the symbols
-                for col_idx in 0..SIZE {
-                    let col = solution[col_idx];
-                    let alloc_col = solution_cells[col_idx].clone();
-
-                    permutation_outputs.push(permutation_chip.apply_permutation(
-                        layouter.namespace(|| "permutating column"),
-                        alloc_col,
-                        get_permutation(col),
-                    ));
-                }
-                // We do the same for the rows
-                for row_idx in 0..SIZE {
-                    let row = solution.map(|col| col[row_idx]);
-                    let alloc_row = (0..SIZE)
-                        .map(|col_idx| solution_cells[col_idx][row_idx].clone())
-                        .f_collect("the number of items is correct");
-
-                    permutation_outputs.push(permutation_chip.apply_permutation(
-                        layouter.namespace(|| "permutating row"),
-                        alloc_row,
-                        get_permutation(row),
-                    ));
+            // All the copies of the solution grid have to be the same.
+            match &solution_cells {
+                None => solution_cells = Some(solution_copy_cells),
+                Some(solution_cells) => {
+                    layouter
+                        .namespace(|| format!("shared solution of sudoku problem {problem_idx}"))
+                        .assign_region(
+                            || "shared solution",
+                            |mut region| {
+                                for (left, right) in solution_cells
+                                    .iter()
+                                    .flatten()
+                                    .zip(solution_copy_cells.iter().flatten())
+                                {
+                                    region.constrain_equal(left.cell(), right.cell())?;
+                                }
+                                Ok(())
+                            },
+                        )?;
                 }
+            }
+        }
+        let solution_cells = solution_cells.ok_or(Error::Synthesis)?;
 
-                // And we do the same for the regions
-                for region_col_offset in (0..SIZE_SQRT).map(|i| i * SIZE_SQRT) {
-                    for region_row_offset in (0..SIZE_SQRT).map(|i| i * SIZE_SQRT) {
-                        // An iterator over the grid positions that compose a sudoku region.
-                        // for example, if SIZE == 4, SIZE_SQRT == 2,
-                        // region_col_offset == 2, region_row_offset == 0,
-                        // the iterator visits the cells marked in the image below,
-                        // in the visualized order
-                        // |-------|
-                        // | | |0|2|
-                        // |-------|
-                        // | | |1|3|
-                        // |-------|
-                        // | | | | |
-                        // |-------|
-                        // | | | | |
-                        // |-------|
-                        let region_index_iter = (0..SIZE).map(|idx| {
-                            (
-                                region_col_offset + idx / SIZE_SQRT,
-                                region_row_offset + idx % SIZE_SQRT,
-                            )
-                        });
-
-                        let region = region_index_iter
-                            .clone()
-                            .map(|(col_idx, row_idx)| solution[col_idx][row_idx])
-                            .f_collect("the number of items is correct");
-                        let alloc_region = region_index_iter
-                            .map(|(col_idx, row_idx)| solution_cells[col_idx][row_idx].clone())
-                            .f_collect("the number of items is correct");
-
-                        permutation_outputs.push(permutation_chip.apply_permutation(
-                            layouter.namespace(|| "permutating region"),
-                            alloc_region,
-                            get_permutation(region),
-                        ));
-                    }
-                }
-                Result::<Vec<_>, _>::from_iter(permutation_outputs)
-            });
+        Ok((solution_cells, symbol_cells))
+    }
 
-        // If the result is known and is an error, we propagate the error.
-        // This propagation method loses information about the error type,
-        // but it is better than nothing.
-        permutation_outputs.error_if_known_and(|result| result.is_err())?;
-        // From now on we are sure that if `permutation_outputs` is known,
-        // then it is not an error, and we can unwrap it.
-        let permutation_outputs = permutation_outputs.map(
-            |result|
-            result.expect("if this was an error, the previous call to `error_if_known_and` would have returned an error.")
-        );
+    /// The columns, rows, and regions of the solution grid,
+    /// in the order given by `permutation_groups`.
+    fn solution_lines(solution_cells: &[[Number<F>; SIZE]; SIZE]) -> Vec<[Number<F>; SIZE]> {
+        Vec::from_iter(
+            Self::permutation_groups()
+                .into_iter()
+                .map(|(_, positions)| {
+                    positions.map(|(col_idx, row_idx)| solution_cells[col_idx][row_idx].clone())
+                }),
+        )
+    }
+}
 
-        // Now we impose equality constraints among all permutation_outputs
-        permutation_outputs
-            .map(|permutation_outputs| {
-                layouter
-                    .namespace(|| "permutation equality constraints")
-                    .assign_region(
-                        || "permutation equality constraints",
-                        |mut region| {
-                            // For each permutation result, we constrain it to be equal to the loaded symbols.
-                            for p_out in permutation_outputs.iter() {
-                                for (left, right) in p_out.iter().zip(symbol_cells.iter()) {
-                                    region.constrain_equal(left.cell(), right.cell())?;
-                                }
-                            }
-                            Ok(())
-                        },
-                    )
-            })
-            // Same as before, the only way to unwrap an error from within a Value
-            // seems to be this `error_if_known_and` hack.
-            .error_if_known_and(|result| result.is_err())
+// This is the only implementation happening for
+// F: ff::PrimeField instead of F: ff::Field.
+// The (tiny) loss of generality is due to the fact that
+// if we want to efficiently compute the witness for the sudoku,
+// then we have to be able to put an order relationship
+// among field elements, which is not possible with F: ff::Field
+// but can be done by using the binary representation of F
+// instances if F: ff::PrimeField.
+// See `OrderedSudokuCircuit` for fields without such a representation.
+impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize> halo2_proofs::plonk::Circuit<F>
+    for SudokuCircuit<F, SIZE, SIZE_SQRT>
+{
+    type Config = SudokuConfig<SIZE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::circuit_wiring_from_symbols_multi(self.symbols, self.problems.len())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Self::configure_sudoku(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.synthesize_sudoku(config, layouter).map(|_| ())
+    }
+}
+
+/// Same as `SudokuCircuit`, for fields whose elements cannot be ordered
+/// by their byte representation, i.e. for `F: ff::Field`.
+///
+/// The ordering of the symbols is given explicitly by `symbol_ordinal`,
+/// which maps each symbol to its index in the symbols column.
+/// It is used to compute the permutations that align the columns, rows,
+/// and regions of the solution to the symbols.
+///
+/// The ordering only drives the witness generation, so it cannot make an
+/// invalid solution pass. However, it has to be injective over the symbols,
+/// and map them into `0..SIZE`, otherwise valid solutions are rejected.
+/// This is checked by `try_new`.
+#[derive(Clone, Debug)]
+pub struct OrderedSudokuCircuit<F: ff::Field, const SIZE: usize, const SIZE_SQRT: usize> {
+    circuit: SudokuCircuit<F, SIZE, SIZE_SQRT>,
+    symbol_ordinal: fn(&F) -> usize,
+}
+
+impl<F: ff::Field, const SIZE: usize, const SIZE_SQRT: usize>
+    OrderedSudokuCircuit<F, SIZE, SIZE_SQRT>
+{
+    pub fn try_new(
+        problem: [[F; SIZE]; SIZE],
+        solution: [[F; SIZE]; SIZE],
+        symbols: [F; SIZE],
+        symbol_ordinal: fn(&F) -> usize,
+    ) -> Result<Self, ()> {
+        let () = SudokuCircuit::<F, SIZE, SIZE_SQRT>::SIZE_IS_SQUARE;
+
+        let symbols = Self::ordered_symbols(symbols, symbol_ordinal)?;
+
+        // We check that `F::ZERO` is not a symbol
+        if symbols.contains(&F::ZERO) {
+            return Err(());
+        }
+
+        // We check that the problem contains only symbols or `F::ZERO` entries
+        if !problem
+            .iter()
+            .flatten()
+            .all(|n| *n == F::ZERO || symbols.contains(n))
+        {
+            return Err(());
+        }
+
+        // We check that the solution only contains symbols
+        if !solution.iter().flatten().all(|n| symbols.contains(n)) {
+            return Err(());
+        }
+
+        Ok(Self {
+            circuit: SudokuCircuit {
+                problems: vec![Value::known(problem)],
+                solution: Value::known(solution),
+                symbols,
+            },
+            symbol_ordinal,
+        })
+    }
+
+    /// Given a symbols array and their ordering, outputs an instance
+    /// of the circuit without witness values.
+    /// Fails if the ordering is not injective over the symbols,
+    /// or maps some symbol out of `0..SIZE`.
+    pub fn circuit_wiring_from_symbols(
+        symbols: [F; SIZE],
+        symbol_ordinal: fn(&F) -> usize,
+    ) -> Result<Self, ()> {
+        let () = SudokuCircuit::<F, SIZE, SIZE_SQRT>::SIZE_IS_SQUARE;
+        Ok(Self {
+            circuit: SudokuCircuit {
+                problems: vec![Value::unknown()],
+                solution: Value::unknown(),
+                symbols: Self::ordered_symbols(symbols, symbol_ordinal)?,
+            },
+            symbol_ordinal,
+        })
+    }
+
+    /// Sorts `symbols` so that the `idx`-th symbol has ordinal `idx`.
+    /// Fails if `symbol_ordinal` does not map the symbols
+    /// one-to-one into `0..SIZE`.
+    fn ordered_symbols(
+        symbols: [F; SIZE],
+        symbol_ordinal: fn(&F) -> usize,
+    ) -> Result<[F; SIZE], ()> {
+        let mut ordered_symbols = [None; SIZE];
+        for s in symbols {
+            match ordered_symbols.get_mut(symbol_ordinal(&s)) {
+                Some(slot @ None) => *slot = Some(s),
+                _ => return Err(()),
+            }
+        }
+        Ok(ordered_symbols.map(|s| s.expect("every ordinal is taken exactly once")))
+    }
+}
+
+impl<F: ff::Field, const SIZE: usize, const SIZE_SQRT: usize> halo2_proofs::plonk::Circuit<F>
+    for OrderedSudokuCircuit<F, SIZE, SIZE_SQRT>
+{
+    type Config = SudokuConfig<SIZE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: SudokuCircuit {
+                problems: vec![Value::unknown(); self.circuit.problems.len()],
+                solution: Value::unknown(),
+                symbols: self.circuit.symbols,
+            },
+            symbol_ordinal: self.symbol_ordinal,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SudokuCircuit::<F, SIZE, SIZE_SQRT>::configure_sudoku(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let (solution_cells, symbol_cells) = self
+            .circuit
+            .synthesize_grids(&config, layouter.namespace(|| "sudoku grids"))?;
+
+        // Values that are not symbols do not get an ordinal,
+        // whatever `symbol_ordinal` maps them to.
+        let symbols = self.circuit.symbols;
+        let symbol_ordinal = self.symbol_ordinal;
+        LinePermutationGadget::<SIZE, F>::construct(config.permutation_config)
+            .enforce_lines_are_symbol_permutations_with_ordering(
+                layouter.namespace(|| "permutating columns, rows, and regions"),
+                &SudokuCircuit::<F, SIZE, SIZE_SQRT>::solution_lines(&solution_cells),
+                &symbol_cells,
+                |value| {
+                    let ordinal = symbol_ordinal(value);
+                    (symbols.get(ordinal) == Some(value)).then_some(ordinal)
+                },
+            )
     }
 }
 
@@ -365,6 +860,21 @@ mod tests {
         (symbols, grids_iter)
     }
 
+    /// Draws `SIZE` distinct non-zero field elements from `rng`,
+    /// i.e. symbols that `try_new` accepts.
+    fn random_distinct_symbols<F: ff::PrimeField, const SIZE: usize, R: rand::Rng>(
+        rng: &mut R,
+    ) -> [F; SIZE] {
+        let mut symbols = Vec::with_capacity(SIZE);
+        while symbols.len() < SIZE {
+            let symbol = F::random(&mut *rng);
+            if symbol != F::ZERO && !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+        core::array::from_fn(|idx| symbols[idx])
+    }
+
     /// Helper function to generate symbols and a list of problems
     /// The return value is a tuple, laid out as
     /// `(symbols, impl Iterator<Item = (solution, problem)>)`
@@ -512,16 +1022,25 @@ mod tests {
 
         // Due to the awkward nested slice arguments the halo prover and verifier require,
         // we have to format the instance input.
-        let instance_slices = Vec::from_iter(sudoku_problems.iter().map(|(_, problem)| {
-            core::array::from_fn::<_, 9, _>(|col_idx| problem[col_idx].as_slice())
-        }));
+        let instance_slices = Vec::from_iter(
+            sudoku_problems
+                .iter()
+                .map(|(_, problem)| crate::utilities::as_instance_slices(problem)),
+        );
+
+        // All the problems share the same symbols, so we check them only once.
+        let validated_symbols = SudokuCircuit::<Fp, 9, 3>::validate_symbols(symbols)
+            .expect("the symbols should be valid");
 
         for ((solution, problem), instance_slices) in
             sudoku_problems.iter().zip(instance_slices.iter())
         {
-            let circuit =
-                SudokuCircuit::<Fp, 9, 3>::try_new(problem.clone(), solution.clone(), symbols)
-                    .expect("creation of circuit instance should not fail");
+            let circuit = SudokuCircuit::<Fp, 9, 3>::try_new_with_validated_symbols(
+                *problem,
+                *solution,
+                &validated_symbols,
+            )
+            .expect("creation of circuit instance should not fail");
 
             prover.add_item(circuit, instance_slices);
         }
@@ -543,4 +1062,642 @@ mod tests {
             ));
         };
     }
+
+    #[test]
+    /// Same as `sudoku`, with the prover owning the instances of the problems,
+    /// so that no instance slices have to be kept alive while proving.
+    fn sudoku_owned_prover() {
+        use crate::utilities::{vec_as_instance_slices, OwnedProverWrapper, VerifierWrapper};
+
+        const POW_OF_2_MAX_ROWS: u32 = 9;
+
+        const NR_RANDOM_MASKS_PER_PROBLEM: usize = 1;
+
+        let (symbols, sudoku_problems) = setup_values(NR_RANDOM_MASKS_PER_PROBLEM);
+
+        let circuit_wiring = SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(symbols);
+
+        let mut prover =
+            OwnedProverWrapper::initialize_parameters_and_prover(POW_OF_2_MAX_ROWS, circuit_wiring)
+                .expect("prover setup goes wrong");
+
+        let validated_symbols = SudokuCircuit::<Fp, 9, 3>::validate_symbols(symbols)
+            .expect("the symbols should be valid");
+
+        for (solution, problem) in sudoku_problems {
+            let circuit = SudokuCircuit::<Fp, 9, 3>::try_new_with_validated_symbols(
+                problem,
+                solution,
+                &validated_symbols,
+            )
+            .expect("creation of circuit instance should not fail");
+
+            prover
+                .try_add_item(circuit, Vec::from_iter(problem.map(Vec::from)))
+                .expect("the instance has the expected shape");
+        }
+
+        let transcript = prover.prove().expect("proof generation goes wrong");
+
+        let instances = prover.instances().to_vec();
+        let mut verifier = VerifierWrapper::from(prover);
+
+        let instance_slices = Vec::from_iter(
+            instances
+                .iter()
+                .map(|instance| vec_as_instance_slices(instance)),
+        );
+        assert!(verifier.verify(
+            instance_slices.iter().map(|instance| instance.as_slice()),
+            &transcript
+        ));
+    }
+
+    #[test]
+    /// Test that masked problems are proven to be solvable,
+    /// with a proof that is only verified against the problems,
+    /// and that problems with conflicting clues are refused.
+    fn sudoku_prove_solvable() {
+        use crate::utilities::{ProverWrapper, VerifierWrapper};
+
+        const POW_OF_2_MAX_ROWS: u32 = 9;
+
+        let (symbols, sudoku_problems) = setup_values(1);
+        let problems = Vec::from_iter(sudoku_problems.into_iter().map(|(_, problem)| problem));
+
+        let circuit_wiring = SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(symbols);
+        let mut prover =
+            ProverWrapper::initialize_parameters_and_prover(POW_OF_2_MAX_ROWS, circuit_wiring)
+                .expect("prover setup goes wrong");
+
+        let instance_slices =
+            Vec::from_iter(problems.iter().map(crate::utilities::as_instance_slices));
+
+        for (problem, instance_slices) in problems.iter().zip(instance_slices.iter()) {
+            let solution = SudokuCircuit::<Fp, 9, 3>::solve(problem, &symbols)
+                .expect("the masked problems are solvable");
+            assert!(SudokuCircuit::<Fp, 9, 3>::is_valid_solution(
+                &solution, &symbols
+            ));
+
+            let circuit = SudokuCircuit::<Fp, 9, 3>::prove_solvable(*problem, symbols)
+                .expect("the masked problems are solvable");
+            prover.add_item(circuit, instance_slices);
+        }
+
+        let transcript = prover.prove().expect("proof generation goes wrong");
+
+        let mut verifier = VerifierWrapper::from(prover);
+        assert!(verifier.verify(
+            instance_slices.iter().map(|instance| instance.as_slice()),
+            &transcript
+        ));
+
+        // Two equal clues in the same column.
+        let mut problem = [[Fp::from(0); 9]; 9];
+        problem[0][0] = symbols[0];
+        problem[0][5] = symbols[0];
+        assert!(SudokuCircuit::<Fp, 9, 3>::solve(&problem, &symbols).is_none());
+        assert!(SudokuCircuit::<Fp, 9, 3>::prove_solvable(problem, symbols).is_err());
+    }
+
+    #[test]
+    /// Test that the instance slices built by the utilities
+    /// match the ones built by hand out of the problem grids.
+    fn sudoku_instance_slices() {
+        use crate::utilities::{as_instance_slices, vec_as_instance_slices};
+
+        let (_, sudoku_problems) = setup_values(1);
+        for (_, problem) in sudoku_problems {
+            let manual: [&[Fp]; 9] = core::array::from_fn(|col_idx| problem[col_idx].as_slice());
+            assert_eq!(as_instance_slices(&problem), manual);
+
+            let instance = Vec::from(problem.map(Vec::from));
+            assert_eq!(vec_as_instance_slices(&instance), manual.to_vec());
+        }
+    }
+
+    #[test]
+    /// Test that the resources reported for the 9x9 sudoku circuit
+    /// match the ones we expect from its configuration.
+    fn sudoku_circuit_stats() {
+        use crate::utilities::{circuit_stats, CircuitStats};
+
+        let circuit_wiring =
+            SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(setup_values(0).0);
+
+        assert_eq!(
+            circuit_stats(&circuit_wiring).expect("layout should not fail"),
+            CircuitStats {
+                // 9 item columns, plus 9 / 2 == 4 swap selector columns
+                num_advice_columns: 13,
+                // the symbols column
+                num_fixed_columns: 1,
+                // one column per problem grid column
+                num_instance_columns: 9,
+                // one for the permutation chip, one for the grid compatibility chip
+                num_selectors: 2,
+                // The grid compatibility gate queries every item column
+                // at 2 * 9 == 18 different rotations, so there are
+                // 18 + 2 blinding factors, and 3 extra rows.
+                minimum_rows: 23,
+                // All the gates, and the permutation argument, have degree 3.
+                degree: 3,
+            }
+        );
+    }
+
+    #[test]
+    /// Test that building circuits from validated symbols accepts
+    /// and refuses exactly the same inputs as `try_new`.
+    fn sudoku_validated_symbols() {
+        type Sudoku = SudokuCircuit<Fp, 9, 3>;
+
+        let (symbols, sudoku_problems) = setup_values(2);
+        assert!(Sudoku::validate_symbols(symbols).is_ok());
+
+        // Duplicate symbols, and `F::ZERO` as a symbol, are refused.
+        let mut duplicate_symbols = symbols;
+        duplicate_symbols[0] = duplicate_symbols[1];
+        assert!(Sudoku::validate_symbols(duplicate_symbols).is_err());
+        let mut zero_symbol = symbols;
+        zero_symbol[0] = Fp::from(0);
+        assert!(Sudoku::validate_symbols(zero_symbol).is_err());
+
+        let validated_symbols = Sudoku::validate_symbols(symbols).expect("the symbols are valid");
+        assert_eq!(validated_symbols.symbols(), &symbols);
+
+        for (solution, problem) in sudoku_problems {
+            let mut not_a_symbol = solution;
+            not_a_symbol[3][5] = Fp::from(10);
+            let mut zero_in_solution = solution;
+            zero_in_solution[2][7] = Fp::from(0);
+
+            for (problem, solution) in [
+                (problem, solution),
+                (solution, solution),
+                (not_a_symbol, solution),
+                (problem, not_a_symbol),
+                (problem, zero_in_solution),
+            ] {
+                assert_eq!(
+                    Sudoku::try_new_with_validated_symbols(problem, solution, &validated_symbols)
+                        .is_ok(),
+                    Sudoku::try_new(problem, solution, symbols).is_ok()
+                );
+            }
+            assert!(
+                Sudoku::try_new_with_validated_symbols(problem, solution, &validated_symbols)
+                    .is_ok()
+            );
+            assert!(Sudoku::try_new_with_validated_symbols(
+                problem,
+                not_a_symbol,
+                &validated_symbols
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    /// Test that the permutations applied to a known solution
+    /// are labeled as expected, and are actual permutations.
+    fn sudoku_debug_permutations() {
+        let (symbols, sudoku_problems) = setup_values(1);
+        let (solution, problem) = sudoku_problems
+            .into_iter()
+            .next()
+            .expect("there is at least one problem");
+
+        let circuit_wiring = SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(symbols);
+        assert!(circuit_wiring.debug_permutations().is_none());
+
+        let circuit = SudokuCircuit::<Fp, 9, 3>::try_new(problem, solution, symbols)
+            .expect("circuit generation goes wrong");
+        let permutations = circuit.debug_permutations().expect("the solution is known");
+
+        let labels = Vec::from_iter(permutations.iter().map(|(label, _)| label.as_str()));
+        assert_eq!(labels.len(), 27);
+        assert_eq!(labels[0], "column 0");
+        assert_eq!(labels[8], "column 8");
+        assert_eq!(labels[9], "row 0");
+        assert_eq!(labels[17], "row 8");
+        assert_eq!(labels[18], "region (0, 0)");
+        assert_eq!(labels[19], "region (0, 3)");
+        assert_eq!(labels[26], "region (6, 6)");
+
+        for (label, permutation) in permutations {
+            let mut sorted = permutation;
+            sorted.sort();
+            assert_eq!(
+                sorted,
+                core::array::from_fn(|i| i),
+                "{label} is not a permutation"
+            );
+        }
+    }
+
+    #[test]
+    /// Test that two problems sharing a solution are proven together,
+    /// and that adding a problem not compatible with the solution fails.
+    fn mock_sudoku_multi() {
+        use crate::utilities::{assert_rejected, assert_satisfied};
+
+        const POW_OF_2_MAX_ROWS: u32 = 10;
+
+        let (symbols, sudoku_problems) = setup_values(2);
+        let sudoku_problems = Vec::from_iter(sudoku_problems);
+        // The first two problems are masks of the first solution.
+        let (solution, first_problem) = sudoku_problems[0];
+        let (_, second_problem) = sudoku_problems[1];
+        // A problem without empty cells is only compatible with itself.
+        let (other_solution, _) = sudoku_problems[2];
+        assert_ne!(solution, other_solution);
+
+        let instance = |problems: &[SudokuGrid]| {
+            Vec::from_iter((0..9).map(|col_idx| {
+                Vec::from_iter(problems.iter().flat_map(|problem| problem[col_idx]))
+            }))
+        };
+
+        let problems = vec![first_problem, second_problem];
+        let circuit = SudokuCircuit::<Fp, 9, 3>::try_new_multi(problems.clone(), solution, symbols)
+            .expect("circuit generation goes wrong");
+        assert_satisfied(POW_OF_2_MAX_ROWS, &circuit, instance(&problems));
+
+        let problems = vec![first_problem, other_solution];
+        let circuit = SudokuCircuit::<Fp, 9, 3>::try_new_multi(problems.clone(), solution, symbols)
+            .expect("circuit generation goes wrong");
+        assert_rejected(POW_OF_2_MAX_ROWS, &circuit, instance(&problems));
+
+        assert!(SudokuCircuit::<Fp, 9, 3>::try_new_multi(vec![], solution, symbols).is_err());
+    }
+
+    #[test]
+    /// Test the out-of-circuit solution check on the
+    /// solutions in the test suite, and on corrupted copies of them.
+    fn sudoku_is_valid_solution() {
+        type Sudoku = SudokuCircuit<Fp, 9, 3>;
+
+        let (symbols, sudoku_problems) = setup_values(1);
+
+        for (solution, _) in sudoku_problems {
+            assert!(Sudoku::is_valid_solution(&solution, &symbols));
+
+            // Swapping two cells of a column keeps the column valid,
+            // but breaks two rows.
+            let mut swapped = solution;
+            swapped[0].swap(0, 1);
+            assert!(!Sudoku::is_valid_solution(&swapped, &symbols));
+
+            // Swapping columns 0 and 3 keeps the columns and rows valid,
+            // but breaks the regions, at least for the grids in the test suite.
+            let mut swapped = solution;
+            swapped.swap(0, 3);
+            assert!(!Sudoku::is_valid_solution(&swapped, &symbols));
+
+            let mut not_a_symbol = solution;
+            not_a_symbol[4][4] = Fp::from(10);
+            assert!(!Sudoku::is_valid_solution(&not_a_symbol, &symbols));
+
+            let mut repeated = solution;
+            repeated[4][4] = repeated[4][5];
+            assert!(!Sudoku::is_valid_solution(&repeated, &symbols));
+        }
+
+        assert!(!SudokuCircuit::<Fp, 9, 2>::is_valid_solution(
+            &[[Fp::from(1); 9]; 9],
+            &symbols
+        ));
+    }
+
+    #[test]
+    /// Test that the order in which the symbols are given
+    /// does not change the circuit.
+    fn sudoku_symbols_order() {
+        use halo2_proofs::plonk::Circuit;
+
+        let (symbols, sudoku_problems) = setup_values(1);
+        let (solution, problem) = sudoku_problems
+            .into_iter()
+            .next()
+            .expect("there is at least one problem");
+
+        let mut reversed_symbols = symbols;
+        reversed_symbols.reverse();
+
+        let circuits = [symbols, reversed_symbols].map(|symbols| {
+            SudokuCircuit::<Fp, 9, 3>::try_new(problem, solution, symbols)
+                .expect("circuit generation goes wrong")
+        });
+        let wirings =
+            [symbols, reversed_symbols].map(SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols);
+
+        // The symbols column is laid out in the same way.
+        assert_eq!(circuits[0].symbols, circuits[1].symbols);
+        assert_eq!(wirings[0].symbols, wirings[1].symbols);
+        assert_eq!(circuits[0].without_witnesses().symbols, wirings[0].symbols);
+
+        // The same permutations are applied to the solution.
+        assert_eq!(
+            circuits[0].debug_permutations(),
+            circuits[1].debug_permutations()
+        );
+
+        let instance = Vec::from(problem.map(Vec::from));
+        for circuit in circuits.iter() {
+            crate::utilities::assert_satisfied(10, circuit, instance.clone());
+        }
+    }
+
+    #[test]
+    /// Test that the verifier reports the instance shape of the 9x9 sudoku,
+    /// i.e. 9 columns of 9 rows, one per problem grid column.
+    fn sudoku_expected_instance_shape() {
+        use crate::utilities::{ProverWrapper, VerifierWrapper};
+
+        let (symbols, _) = setup_values(1);
+        let circuit_wiring = SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(symbols);
+
+        let prover = ProverWrapper::initialize_parameters_and_prover(9, circuit_wiring.clone())
+            .expect("prover setup goes wrong");
+        let verifier = VerifierWrapper::from(prover);
+
+        assert_eq!(
+            verifier
+                .expected_instance_shape(&circuit_wiring)
+                .expect("synthesis goes wrong"),
+            vec![9; 9]
+        );
+
+        let two_problems = SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols_multi(symbols, 2);
+        assert_eq!(
+            verifier
+                .expected_instance_shape(&two_problems)
+                .expect("synthesis goes wrong"),
+            vec![18; 9]
+        );
+    }
+
+    #[test]
+    /// Test that `OrderedSudokuCircuit`, with an ordering that reproduces
+    /// the byte representation ordering on the standard symbols, lays out
+    /// the same symbols as `SudokuCircuit`, and accepts the same solutions.
+    /// Also test that an invalid solution, and non-injective orderings, are rejected.
+    fn mock_ordered_sudoku() {
+        use crate::utilities::{assert_rejected, assert_satisfied};
+
+        const POW_OF_2_MAX_ROWS: u32 = 10;
+
+        fn standard_ordinal(x: &Fp) -> usize {
+            (1..=9u64)
+                .position(|n| Fp::from(n) == *x)
+                .unwrap_or(usize::MAX)
+        }
+
+        let (symbols, sudoku_problems) = setup_values(1);
+
+        let ordered_wiring = OrderedSudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(
+            symbols,
+            standard_ordinal,
+        )
+        .expect("the ordering is injective over the symbols");
+        assert_eq!(
+            ordered_wiring.circuit.symbols,
+            SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(symbols).symbols
+        );
+
+        for (solution, problem) in sudoku_problems {
+            let circuit = OrderedSudokuCircuit::<Fp, 9, 3>::try_new(
+                problem,
+                solution,
+                symbols,
+                standard_ordinal,
+            )
+            .expect("circuit generation goes wrong");
+
+            assert_satisfied(
+                POW_OF_2_MAX_ROWS,
+                &circuit,
+                Vec::from(problem.map(|column| Vec::from(column))),
+            );
+        }
+
+        // Every cell of the solution contains the same symbol.
+        let circuit = OrderedSudokuCircuit::<Fp, 9, 3>::try_new(
+            [[Fp::from(0); 9]; 9],
+            [[symbols[0]; 9]; 9],
+            symbols,
+            standard_ordinal,
+        )
+        .expect("the grids only contain symbols");
+        assert_rejected(POW_OF_2_MAX_ROWS, &circuit, vec![vec![Fp::from(0); 9]; 9]);
+
+        assert!(
+            OrderedSudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(symbols, |_| 0).is_err()
+        );
+        assert!(
+            OrderedSudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(symbols, |x| {
+                standard_ordinal(x) + 1
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    /// Test that `problem_instance` returns the problem passed to `try_new`,
+    /// blanks included, and that it is accepted as the public instance.
+    /// Circuits with several problems, or without witness, have no instance.
+    fn sudoku_problem_instance() {
+        let (symbols, sudoku_problems) = setup_values(1);
+
+        for (solution, problem) in sudoku_problems {
+            let circuit = SudokuCircuit::<Fp, 9, 3>::try_new(problem, solution, symbols)
+                .expect("circuit generation goes wrong");
+
+            let problem_instance = circuit.problem_instance().expect("the problem is known");
+            assert_eq!(problem_instance, problem);
+
+            crate::utilities::assert_satisfied(
+                10,
+                &circuit,
+                Vec::from(problem_instance.map(|column| Vec::from(column))),
+            );
+
+            let multi_circuit =
+                SudokuCircuit::<Fp, 9, 3>::try_new_multi(vec![problem; 2], solution, symbols)
+                    .expect("circuit generation goes wrong");
+            assert_eq!(multi_circuit.problem_instance(), None);
+        }
+
+        assert_eq!(
+            SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(symbols).problem_instance(),
+            None
+        );
+    }
+
+    /// A circuit that lays out the grids of a sudoku circuit,
+    /// and reads the values of the symbol cells back.
+    struct SymbolReadBackCircuit(SudokuCircuit<Fp, 9, 3>);
+
+    impl halo2_proofs::plonk::Circuit<Fp> for SymbolReadBackCircuit {
+        type Config = SudokuConfig<9>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self(halo2_proofs::plonk::Circuit::without_witnesses(&self.0))
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            SudokuCircuit::<Fp, 9, 3>::configure_sudoku(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let (_, symbol_cells) = self.0.synthesize_grids(&config, layouter)?;
+            assert_eq!(
+                symbol_cells.map(|cell| cell.known_value()),
+                self.0.symbols().map(Some)
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    /// Test that `symbols` returns the symbols given to the constructors,
+    /// sorted when the constructor checks them, and that they are
+    /// the values assigned to the symbol cells.
+    fn sudoku_symbols() {
+        let (symbols, sudoku_problems) = setup_values(1);
+        let (solution, problem) = sudoku_problems
+            .into_iter()
+            .next()
+            .expect("there is at least one problem");
+
+        let mut reversed_symbols = symbols;
+        reversed_symbols.reverse();
+
+        let circuit = SudokuCircuit::<Fp, 9, 3>::try_new(problem, solution, reversed_symbols)
+            .expect("circuit generation goes wrong");
+        assert_eq!(circuit.symbols(), &symbols);
+        assert_eq!(
+            SudokuCircuit::<Fp, 9, 3>::circuit_wiring_from_symbols(reversed_symbols).symbols(),
+            &symbols
+        );
+        assert_eq!(
+            SudokuCircuit::<Fp, 9, 3>::new_unchecked(
+                Value::known(problem),
+                Value::known(solution),
+                reversed_symbols
+            )
+            .symbols(),
+            &symbols
+        );
+
+        crate::utilities::assert_satisfied(
+            10,
+            &SymbolReadBackCircuit(circuit),
+            Vec::from(problem.map(|column| Vec::from(column))),
+        );
+    }
+
+    #[test]
+    /// Test that a circuit built by `new_unchecked` with unsorted symbols is proved
+    /// with the keys generated out of `circuit_wiring_from_symbols` with the same
+    /// unsorted symbols, i.e. that both lay out the same symbols column.
+    fn sudoku_unchecked_unsorted_symbols() {
+        use crate::utilities::{
+            recommended_k, vec_as_instance_slices, ProverWrapper, VerifierWrapper,
+        };
+
+        let ordinals: [[usize; 4]; 4] = [[0, 1, 2, 3], [2, 3, 0, 1], [1, 0, 3, 2], [3, 2, 1, 0]];
+        let symbols = [4_u64, 3, 2, 1].map(Fp::from);
+        let solution = ordinals.map(|column| column.map(|ordinal| symbols[ordinal]));
+        let mut problem = solution;
+        for (col_idx, row_idx) in [(0, 0), (1, 2), (2, 1), (3, 3)] {
+            problem[col_idx][row_idx] = Fp::from(0);
+        }
+
+        let instance = Vec::from(problem.map(Vec::from));
+        let instance_slices = vec_as_instance_slices(&instance);
+
+        let circuit_wiring = SudokuCircuit::<Fp, 4, 2>::circuit_wiring_from_symbols(symbols);
+        let (k, _) = recommended_k(&circuit_wiring).expect("layout should not fail");
+        let mut prover = ProverWrapper::initialize_parameters_and_prover(k, circuit_wiring)
+            .expect("prover setup goes wrong");
+        prover.add_item(
+            SudokuCircuit::new_unchecked(Value::known(problem), Value::known(solution), symbols),
+            instance_slices.as_slice(),
+        );
+        let transcript = prover.prove().expect("proof generation goes wrong");
+
+        assert!(VerifierWrapper::from(prover).verify([instance_slices.as_slice()], &transcript));
+    }
+
+    #[test]
+    /// Test that a 4x4 sudoku is proved with random symbols,
+    /// that `try_new` accepts.
+    /// The RNG is seeded, so that failures can be reproduced.
+    fn sudoku_random_symbols() {
+        use crate::utilities::mock_prove_auto_k;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        const SEED: u64 = 0x5eed;
+
+        // The ordinals of the symbols in a 4x4 solution, column by column.
+        let ordinals: [[usize; 4]; 4] = [[0, 1, 2, 3], [2, 3, 0, 1], [1, 0, 3, 2], [3, 2, 1, 0]];
+
+        let mut rng = StdRng::seed_from_u64(SEED);
+        for _ in 0..4 {
+            let symbols = random_distinct_symbols::<Fp, 4, _>(&mut rng);
+            let solution = ordinals.map(|column| column.map(|ordinal| symbols[ordinal]));
+            let mut problem = solution;
+            for (col_idx, row_idx) in [(0, 0), (1, 2), (2, 1), (3, 3), (3, 0)] {
+                problem[col_idx][row_idx] = Fp::from(0);
+            }
+
+            assert!(SudokuCircuit::<Fp, 4, 2>::is_valid_solution(
+                &solution, &symbols
+            ));
+            let circuit = SudokuCircuit::<Fp, 4, 2>::try_new(problem, solution, symbols)
+                .expect("random distinct non-zero symbols are valid");
+            assert!(mock_prove_auto_k(&circuit, Vec::from(problem.map(Vec::from))).is_ok());
+        }
+    }
+
+    #[test]
+    /// Test the coordinates of the cells occupied by the sudoku problem chip
+    /// for a known size, and that they are distinct and fit in the grid columns,
+    /// for a range of sizes.
+    fn sudoku_problem_chip_occupied_cells() {
+        assert_eq!(
+            SudokuProblemChip::<2, Fp>::occupied_cells(),
+            vec![
+                (0, 0),
+                (0, 1),
+                (1, 0),
+                (1, 1),
+                (0, 2),
+                (0, 3),
+                (1, 2),
+                (1, 3)
+            ]
+        );
+
+        macro_rules! check_occupied_cells {
+            ($($SIZE: literal),*) => {$({
+                let cells = SudokuProblemChip::<$SIZE, Fp>::occupied_cells();
+                assert_eq!(cells.len(), 2 * $SIZE * $SIZE);
+                assert_eq!(
+                    cells.iter().collect::<std::collections::BTreeSet<_>>().len(),
+                    cells.len()
+                );
+                assert!(cells.iter().all(|&(col, row)| col < $SIZE && row < 2 * $SIZE));
+            })*};
+        }
+        check_occupied_cells!(3, 4, 9);
+    }
 }
@@ -0,0 +1,242 @@
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{sudoku_circuit::SudokuConfig, utilities::sudoku_line_iter, SudokuCircuit};
+
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A part of a sudoku circuit, obtained by `SudokuCircuit::split_by_lines`,
+/// that only constrains some of the columns, rows, and regions
+/// of the solution grid to contain every symbol once.
+///
+/// Each part still lays out the whole problem and solution grids,
+/// and checks that they are compatible. The solution cells belonging to
+/// the lines of the part are exposed, so that a coordinator can check
+/// that all the parts are about the same solution, see `shared_cells_agree`.
+/// The exposed cells are public: the parts split the proving work,
+/// but, together, they reveal the solution.
+///
+/// The instance columns contain the problem grid, as in `SudokuCircuit`,
+/// followed by a column containing the values of the exposed cells,
+/// in the order of `shared_positions`, see `instance`.
+#[derive(Clone, Debug)]
+pub struct SudokuPartCircuit<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize> {
+    circuit: SudokuCircuit<F, SIZE, SIZE_SQRT>,
+    /// The indices of the lines constrained by this part,
+    /// in the order of `sudoku_line_iter`.
+    lines: Vec<usize>,
+}
+
+impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize>
+    SudokuPartCircuit<F, SIZE, SIZE_SQRT>
+{
+    /// It is up to the caller to guarantee that every line index
+    /// is smaller than `3 * SIZE`.
+    pub(crate) fn new(circuit: SudokuCircuit<F, SIZE, SIZE_SQRT>, lines: Vec<usize>) -> Self {
+        Self { circuit, lines }
+    }
+
+    /// The indices of the lines constrained by this part,
+    /// in the order of `sudoku_line_iter`.
+    pub fn lines(&self) -> &[usize] {
+        &self.lines
+    }
+
+    /// The sorted `(col_idx, row_idx)` positions of the solution cells
+    /// exposed by this part, i.e. the cells belonging to its lines.
+    pub fn shared_positions(&self) -> Vec<(usize, usize)> {
+        let positions = BTreeSet::from_iter(
+            sudoku_line_iter::<SIZE, SIZE_SQRT>()
+                .enumerate()
+                .filter(|(line_idx, _)| self.lines.contains(line_idx))
+                .flat_map(|(_, line)| line),
+        );
+        Vec::from_iter(positions)
+    }
+
+    /// The values the instance columns have to contain
+    /// for the given problem and solution grids.
+    pub fn instance(
+        &self,
+        problem: &[[F; SIZE]; SIZE],
+        solution: &[[F; SIZE]; SIZE],
+    ) -> Vec<Vec<F>> {
+        let mut instance = Vec::from_iter(problem.iter().map(|column| column.to_vec()));
+        instance.push(Vec::from_iter(
+            self.shared_positions()
+                .into_iter()
+                .map(|(col_idx, row_idx)| solution[col_idx][row_idx]),
+        ));
+        instance
+    }
+
+    /// The check a coordinator performs, out of the circuit, on the parts
+    /// of a sudoku circuit and on the values of their exposed cells,
+    /// i.e. the last instance column of each part.
+    ///
+    /// Returns `true` if every line is constrained by some part,
+    /// and the parts that expose the same cell agree on its value.
+    pub fn shared_cells_agree(parts: &[Self], shared_values: &[Vec<F>]) -> bool {
+        if parts.len() != shared_values.len() {
+            return false;
+        }
+
+        let covered_lines =
+            BTreeSet::from_iter(parts.iter().flat_map(|part| part.lines.iter().copied()));
+        if !(0..3 * SIZE).all(|line_idx| covered_lines.contains(&line_idx)) {
+            return false;
+        }
+
+        let mut cell_values = BTreeMap::new();
+        for (part, values) in parts.iter().zip(shared_values) {
+            let positions = part.shared_positions();
+            if positions.len() != values.len() {
+                return false;
+            }
+            for (position, value) in positions.into_iter().zip(values) {
+                if *cell_values.entry(position).or_insert(value) != value {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SudokuPartConfig<const SIZE: usize> {
+    sudoku_config: SudokuConfig<SIZE>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    shared_instance: Column<Instance>,
+}
+
+impl<F: ff::PrimeField, const SIZE: usize, const SIZE_SQRT: usize> Circuit<F>
+    for SudokuPartCircuit<F, SIZE, SIZE_SQRT>
+{
+    type Config = SudokuPartConfig<SIZE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: self.circuit.without_witnesses(),
+            lines: self.lines.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // The problem columns come first, so that the
+        // shared cells column is the last instance column.
+        let sudoku_config = SudokuCircuit::<F, SIZE, SIZE_SQRT>::configure_sudoku(meta);
+
+        let shared_instance = meta.instance_column();
+        meta.enable_equality(shared_instance);
+
+        SudokuPartConfig {
+            sudoku_config,
+            shared_instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let solution_cells = self.circuit.synthesize_sudoku_lines(
+            config.sudoku_config,
+            layouter.namespace(|| "sudoku part"),
+            &self.lines,
+        )?;
+
+        let mut shared_layouter = layouter.namespace(|| "shared cells exposure");
+        for (idx, (col_idx, row_idx)) in self.shared_positions().into_iter().enumerate() {
+            shared_layouter.constrain_instance(
+                solution_cells[col_idx][row_idx].cell(),
+                config.shared_instance,
+                idx,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied};
+    use halo2_proofs::pasta::Fp;
+
+    type SudokuGrid = [[Fp; 9]; 9];
+
+    const K: u32 = 10;
+
+    /// A valid solution, and the problem obtained by clearing
+    /// the cells containing 1 or 2 in it.
+    fn solution_and_problem() -> ([Fp; 9], SudokuGrid, SudokuGrid) {
+        let grid: [[u64; 9]; 9] = [
+            [2, 4, 9, 5, 3, 6, 1, 8, 7],
+            [3, 5, 1, 2, 7, 8, 4, 9, 6],
+            [6, 7, 8, 4, 9, 1, 5, 3, 2],
+            [8, 9, 7, 1, 4, 5, 6, 2, 3],
+            [4, 2, 3, 6, 8, 9, 7, 5, 1],
+            [5, 1, 6, 7, 2, 3, 9, 4, 8],
+            [1, 6, 2, 3, 5, 4, 8, 7, 9],
+            [9, 3, 5, 8, 6, 7, 2, 1, 4],
+            [7, 8, 4, 9, 1, 2, 3, 6, 5],
+        ];
+        let to_field = |grid: [[u64; 9]; 9]| grid.map(|column| column.map(Fp::from));
+
+        (
+            core::array::from_fn(|n| Fp::from(n as u64 + 1)),
+            to_field(grid),
+            to_field(grid.map(|column| column.map(|n| if n <= 2 { 0 } else { n }))),
+        )
+    }
+
+    #[test]
+    /// Test that a 9x9 sudoku split in two parts is proven part by part,
+    /// that the exposed cells of the parts agree, and that a part
+    /// exposing a tampered cell is rejected, and disagrees with the other part.
+    fn mock_sudoku_parts() {
+        let (symbols, solution, problem) = solution_and_problem();
+        let circuit = SudokuCircuit::<Fp, 9, 3>::try_new(problem, solution, symbols)
+            .expect("the grids are valid");
+
+        let parts = circuit.split_by_lines(2);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].lines(), Vec::from_iter(0..13));
+        assert_eq!(parts[1].lines(), Vec::from_iter(13..27));
+
+        let instances = Vec::from_iter(parts.iter().map(|part| part.instance(&problem, &solution)));
+        for (part, instance) in parts.iter().zip(instances.iter()) {
+            assert_satisfied(K, part, instance.clone());
+        }
+
+        let shared_values = Vec::from_iter(instances.iter().map(|instance| instance[9].clone()));
+        assert!(SudokuPartCircuit::shared_cells_agree(
+            &parts,
+            &shared_values
+        ));
+        assert!(!SudokuPartCircuit::shared_cells_agree(
+            &parts[..1],
+            &shared_values[..1]
+        ));
+
+        let mut tampered_instance = instances[1].clone();
+        tampered_instance[9][0] += Fp::from(1);
+        assert_rejected(K, &parts[1], tampered_instance.clone());
+
+        let tampered_values = vec![shared_values[0].clone(), tampered_instance[9].clone()];
+        assert!(!SudokuPartCircuit::shared_cells_agree(
+            &parts,
+            &tampered_values
+        ));
+    }
+}
@@ -13,7 +13,7 @@ impl<const SIZE: usize, F: ff::Field> SudokuProblemChip<SIZE, F> {
         let config = self.config();
 
         layouter.assign_region(
-            || "load problem-solution sudoku grids",
+            || prefixed_name(self.name_prefix, "load problem-solution sudoku grids"),
             |mut region| {
                 // enables the chip's gate. This makes it so that the constraints are
                 // actually checked for the grids we are going to load
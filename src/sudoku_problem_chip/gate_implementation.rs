@@ -2,12 +2,39 @@ use super::*;
 
 impl<const SIZE: usize, F: ff::Field> SudokuProblemChip<SIZE, F> {
     pub fn construct(config: SPConfig<SIZE>) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(config: SPConfig<SIZE>, name_prefix: &'static str) -> Self {
         Self {
             config,
+            name_prefix,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `enforce_grid_compatibility` assigns, relative to the first row
+    /// of its region, in the order in which they are assigned.
+    /// The column index is the position of the column in `SPConfig::grid_columns`.
+    ///
+    /// The problem grid takes the first `SIZE` rows, column by column,
+    /// and the solution grid takes the next `SIZE` rows.
+    #[allow(dead_code)]
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        [0, SIZE]
+            .into_iter()
+            .flat_map(|offset| {
+                (0..SIZE).flat_map(move |col_idx| {
+                    (offset..offset + SIZE).map(move |row_idx| (col_idx, row_idx))
+                })
+            })
+            .collect()
+    }
+
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
         grid_columns: [Column<Advice>; SIZE],
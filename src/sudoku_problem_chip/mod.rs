@@ -4,9 +4,11 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
-use crate::utilities::RegionSequenceAssignment;
+use crate::utilities::{prefixed_name, RegionSequenceAssignment};
 
 use super::Number;
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
 
 mod chip_setup_api;
 mod gate_implementation;
@@ -19,13 +21,18 @@ pub(crate) struct SudokuProblemAssignment<const SIZE: usize, F: ff::Field> {
 #[derive(Debug, Clone)]
 pub(crate) struct SudokuProblemChip<const SIZE: usize, F: ff::Field> {
     config: SPConfig<SIZE>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
     _marker: std::marker::PhantomData<F>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct SPConfig<const SIZE: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
     pub grid_columns: [Column<Advice>; SIZE],
 
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
     s_grid_compatibility: Selector,
 }
 
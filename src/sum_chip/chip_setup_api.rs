@@ -0,0 +1,31 @@
+use super::*;
+
+impl<F: ff::Field, const N: usize> SumChip<F, N> {
+    /// Constrains the sum of `cells` to be equal to `expected`.
+    ///
+    /// The summands and the expected sum are copied in a single row,
+    /// where the gate checks the sum.
+    pub fn constrain_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: [Number<F>; N],
+        expected: Number<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || prefixed_name(self.name_prefix, "sum check"),
+            |mut region| {
+                let config = self.config();
+
+                // We enable the gate that constrains the sum.
+                config.s_sum.enable(&mut region, 0)?;
+
+                for (cell, column) in cells.iter().zip(config.summand_columns) {
+                    cell.copy_advice(|| "summand", &mut region, column, 0)?;
+                }
+                expected.copy_advice(|| "expected sum", &mut region, config.sum_column, 0)?;
+
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+impl<F: ff::Field, const N: usize> SumChip<F, N> {
+    pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
+        Self {
+            config,
+            name_prefix,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `constrain_sum` assigns, relative to the first row of its region,
+    /// in the order in which they are assigned. The column index is the
+    /// position of the column in `SConfig::summand_columns`,
+    /// and `sum_column` has index `N`.
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        Vec::from_iter((0..=N).map(|col_idx| (col_idx, 0)))
+    }
+
+    /// `summand_columns` contain the summands, and `sum_column` the expected sum.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        summand_columns: [Column<Advice>; N],
+        sum_column: Column<Advice>,
+    ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
+        for col in summand_columns {
+            meta.enable_equality(col);
+        }
+        meta.enable_equality(sum_column);
+
+        let s_sum = meta.selector();
+
+        meta.create_gate("sum check", |meta| {
+            let s_sum = meta.query_selector(s_sum);
+            let sum = summand_columns
+                .map(|col| meta.query_advice(col, Rotation::cur()))
+                .into_iter()
+                .fold(Expression::Constant(F::ZERO), |sum, cell| sum + cell);
+            let expected = meta.query_advice(sum_column, Rotation::cur());
+
+            // if s_sum is enabled, then `expected == summands[0] + ... + summands[N - 1]`
+            vec![s_sum * (sum - expected)]
+        });
+
+        SConfig {
+            summand_columns,
+            sum_column,
+            s_sum,
+        }
+    }
+}
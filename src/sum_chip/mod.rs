@@ -0,0 +1,147 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{utilities::prefixed_name, Number};
+
+/// in this module, we implement the functions needed to load
+/// the summands and the expected sum in the advice columns.
+mod chip_setup_api;
+/// in this module, we implement the gate logic.
+mod gate_implementation;
+
+#[derive(Debug, Clone)]
+pub struct SumChip<F: ff::Field, const N: usize> {
+    config: SConfig<N>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SConfig<const N: usize> {
+    /// The columns containing the summands, one per column.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
+    pub summand_columns: [Column<Advice>; N],
+    /// The column containing the expected sum.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    pub sum_column: Column<Advice>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
+    s_sum: Selector,
+}
+
+impl<F: ff::Field, const N: usize> halo2_proofs::circuit::Chip<F> for SumChip<F, N> {
+    type Config = SConfig<N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied, RegionSequenceAssignment};
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    const N: usize = 4;
+
+    /// A circuit that proves that secret summands add up to a secret sum.
+    struct SumCircuit {
+        summands: [Value<Fp>; N],
+        expected: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for SumCircuit {
+        type Config = SConfig<N>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                summands: [Value::unknown(); N],
+                expected: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let summand_columns = [(); N].map(|_| meta.advice_column());
+            let sum_column = meta.advice_column();
+            SumChip::<Fp, N>::configure(meta, summand_columns, sum_column)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let sum_chip = SumChip::<Fp, N>::construct(config);
+
+            let (summands, expected) = layouter.assign_region(
+                || "summands and expected sum",
+                |mut region| {
+                    let config = sum_chip.config();
+                    let [summands] = region.assign_grid_to_columns_transposed(
+                        config.summand_columns,
+                        0,
+                        self.summands.map(|value| [value]),
+                    )?;
+                    let expected = region.assign_advice(
+                        || "expected sum",
+                        config.sum_column,
+                        0,
+                        || self.expected,
+                    )?;
+                    Ok((summands, Number(expected)))
+                },
+            )?;
+
+            sum_chip.constrain_sum(layouter.namespace(|| "sum"), summands, expected)
+        }
+    }
+
+    #[test]
+    /// Test the sum chip with the mock prover,
+    /// both with a correct and a wrong expected sum.
+    fn mock_sum() {
+        const POW_OF_2_MAX_ROWS: u32 = 4;
+
+        let circuit = |expected: u64| SumCircuit {
+            summands: [1, 2, 3, 4].map(|n| Value::known(Fp::from(n))),
+            expected: Value::known(Fp::from(expected)),
+        };
+
+        assert_satisfied(POW_OF_2_MAX_ROWS, &circuit(10), vec![]);
+        assert_rejected(POW_OF_2_MAX_ROWS, &circuit(11), vec![]);
+    }
+
+    #[test]
+    /// Test that the sum chip occupies the cells of the summands
+    /// and of the expected sum in a single row, each one once.
+    fn sum_chip_occupied_cells() {
+        let cells = SumChip::<Fp, N>::occupied_cells();
+        assert_eq!(cells, vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)]);
+        assert_eq!(
+            cells
+                .iter()
+                .collect::<std::collections::BTreeSet<_>>()
+                .len(),
+            cells.len()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use halo2_proofs::circuit::Region;
+use crate::utilities::RegionPainter;
 
 use super::*;
 
@@ -7,90 +7,161 @@ impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_
 {
     pub fn compute(
         &self,
-        mut layouter: impl Layouter<F>,
+        layouter: impl Layouter<F>,
         input_cell: Number<F>,
     ) -> Result<Number<F>, Error> {
+        self.compute_batch_products(layouter, input_cell)
+            .map(|mut batch_products| {
+                batch_products
+                    .pop()
+                    .expect("there is always at least one batch product")
+            })
+    }
+
+    /// Same as `compute`, but returns the cells containing the product
+    /// accumulated after each batch of `MUL_BATCH_SIZE` factors,
+    /// in the order in which they are computed.
+    /// The last cell is the output of `compute`.
+    ///
+    /// If `N_FACTORS == 0`, the only returned cell is the one set to `F::ONE`.
+    pub fn compute_batch_products(
+        &self,
+        layouter: impl Layouter<F>,
+        input_cell: Number<F>,
+    ) -> Result<Vec<Number<F>>, Error> {
+        self.assign_factorial(layouter, input_cell.value().cloned(), Some(&input_cell))
+            .map(|(_, batch_products)| batch_products)
+    }
+
+    /// Same as `compute`, but `input_value` is assigned directly in the first
+    /// cell of the chip's layout, i.e. the first cell of `occupied_cells()`,
+    /// rather than copied there from a cell assigned by the caller.
+    /// This saves a cell and an equality constraint.
+    ///
+    /// The precondition is that the caller has no other cell holding the input,
+    /// or that it constrains it to the returned input cell on its own.
+    /// Note that, since the `DivModCounter` layout skips the first cell of the
+    /// region, the input lives in column 1 of row 0 if `N_COLUMNS > 1`,
+    /// and in column 0 of row 1 otherwise.
+    ///
+    /// Returns `(input_cell, output_cell)`. If `N_FACTORS == 0`, the gate does
+    /// not read the input, which is then assigned in the cell after the output,
+    /// i.e. the second cell of `occupied_cells()`, as `compute` does.
+    pub fn compute_in_place(
+        &self,
+        layouter: impl Layouter<F>,
+        input_value: Value<F>,
+    ) -> Result<(Number<F>, Number<F>), Error> {
+        let (input_cell, mut batch_products) =
+            self.assign_factorial(layouter, input_value, None)?;
+        Ok((
+            input_cell.expect("the input is assigned in place"),
+            batch_products
+                .pop()
+                .expect("there is always at least one batch product"),
+        ))
+    }
+
+    /// Assigns the cells of the gate, and returns the cell holding the input,
+    /// if any, together with the batch products.
+    /// If `input_cell` is provided, its value is copied in the first cell,
+    /// and constrained to be equal, otherwise `input_value` is assigned there.
+    fn assign_factorial(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input_value: Value<F>,
+        input_cell: Option<&Number<F>>,
+    ) -> Result<(Option<Number<F>>, Vec<Number<F>>), Error> {
         layouter.assign_region(
-            || "assign factorial chip advice",
+            || prefixed_name(self.name_prefix, "assign factorial chip advice"),
             |mut region| {
                 let config = self.config();
 
                 // We activate the gate
                 config.s_fact.enable(&mut region, 0)?;
 
-                // We build `assign_new_cell`, a closure that, given a value,
-                // allocates it in the next available advice cell. The order of
+                // We paint the values in the next available advice cells. The order of
                 // the cells is consistent with the one in the gate implementation.
-                let mut cell_counter = DivModCounter::new_runtime_mod(0, 0, N_COLUMNS)
-                    .map(|(div, res)| (config.columns[res], div));
-                let mut assign_new_cell = |region: &mut Region<'_, F>, value| {
-                    let (column, offset) = cell_counter.next().expect("the iterator never ends");
-                    region
-                        .assign_advice(
-                            || "truncated factorial advice cell",
-                            column,
-                            offset,
-                            || value,
-                        )
-                        .map(Number)
-                };
+                let mut painter = RegionPainter::new(
+                    &mut region,
+                    &config.columns,
+                    (0, 0),
+                    "truncated factorial advice cell",
+                );
 
                 // If the number of factors is 0, then the only constraint
                 // imposed by the gate is that the first allocated cell
                 // is set to `F::ONE`.
+                // The input is not read by the gate, but it is still laid out
+                // after the output, so that the chip occupies the same cells
+                // whether the input is copied or assigned in place.
                 if N_FACTORS == 0 {
-                    return assign_new_cell(&mut region, Value::known(F::ONE));
+                    let output = painter.paint(Value::known(F::ONE))?;
+                    let local_input_cell = painter.paint(input_value)?;
+                    if let Some(input_cell) = input_cell {
+                        painter
+                            .region()
+                            .constrain_equal(input_cell.cell(), local_input_cell.cell())?;
+                    }
+                    return Ok((Some(local_input_cell), vec![output]));
                 }
                 // From now on, we know that `N_FACTORS > 0`. Keep it in mind!
 
-                let input_value = input_cell.value().cloned();
-
                 // Because of how the constraints in the gate are defined, the first
                 // cell we have to allocate is a copy of the input value.
                 // All the constraints are defined in terms of the value in this cell.
                 //
                 // Since we know the actual value inside it, i.e. `input_value`,
-                // We don't have to bother using this variable.
-                let _local_copy_of_input_cell = assign_new_cell(&mut region, input_value)?;
+                // we don't have to read it back afterwards.
+                let local_input_cell = painter.paint(input_value)?;
 
-                // We make sure that the prover enforces that the
-                // value we copied over is the same as the input.
-                region.constrain_equal(input_cell.cell(), _local_copy_of_input_cell.cell())?;
+                // If the input lives in another cell, we make sure that the prover
+                // enforces that the value we copied over is the same as the input.
+                if let Some(input_cell) = input_cell {
+                    painter
+                        .region()
+                        .constrain_equal(input_cell.cell(), local_input_cell.cell())?;
+                }
 
                 // An iterator that yields the sequence
-                // of the terms to be multiplied in the factorial.
+                // of the terms to be multiplied in the factorial,
+                // starting from the input itself.
                 let mut field_counter =
-                    crate::utilities::FieldCounter::start_counting_from(F::ZERO)
-                        .map(|f| input_value + Value::known(f));
+                    input_value.map(crate::utilities::FieldCounter::start_counting_from);
                 // A closure that integrates `batch_size`
                 // new factors in the factorial product.
-                let mut product_batch = |product_so_far, batch_size| {
-                    (&mut field_counter)
-                        .take(batch_size)
-                        .fold(product_so_far, |prod, e| prod * e)
+                let mut product_batch = |product_so_far: Value<F>, batch_size| {
+                    product_so_far.zip(field_counter.as_mut()).map(
+                        |(product_so_far, field_counter)| {
+                            field_counter
+                                .take(batch_size)
+                                .fold(product_so_far, |prod, e| prod * e)
+                        },
+                    )
                 };
 
                 let mut product = Value::known(F::ONE);
-                let mut output_cell = None;
+                let mut batch_products = Vec::with_capacity(N_FACTORS.div_ceil(MUL_BATCH_SIZE));
 
                 // As in the gate implementation, we add factors in groups of
                 // `mul_batch_size`, until possible
                 for _batch_nr in 0..N_FACTORS / MUL_BATCH_SIZE {
                     product = product_batch(product, MUL_BATCH_SIZE);
-                    output_cell = Some(assign_new_cell(&mut region, product)?);
+                    batch_products.push(painter.paint(product)?);
                 }
 
                 // Then, we apply a smaller batch for the remaining terms.
                 if N_FACTORS % MUL_BATCH_SIZE != 0 {
                     product = product_batch(product, N_FACTORS % MUL_BATCH_SIZE);
-                    output_cell = Some(assign_new_cell(&mut region, product)?);
+                    batch_products.push(painter.paint(product)?);
                 }
 
-                Ok(
-                    output_cell.expect(
-                        "Since N_FACTORS > 0, by this point `output_cell` is not None, because `N_FACTORS / MUL_BATCH_SIZE > 0 || N_FACTORS % MUL_BATCH_SIZE != 0`"
-                    )
-                )
+                // Since N_FACTORS > 0, by this point `batch_products` is not empty,
+                // because `N_FACTORS / MUL_BATCH_SIZE > 0 || N_FACTORS % MUL_BATCH_SIZE != 0`
+                Ok((
+                    input_cell.is_none().then_some(local_input_cell),
+                    batch_products,
+                ))
             },
         )
     }
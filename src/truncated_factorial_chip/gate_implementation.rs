@@ -4,25 +4,134 @@ impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_
     TruncatedFactorialChip<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS>
 {
     pub fn construct(config: <Self as halo2_proofs::circuit::Chip<F>>::Config) -> Self {
+        Self::construct_with_name_prefix(config, "")
+    }
+
+    /// Same as `construct`, but the annotations of the regions laid out
+    /// by the chip are prefixed by `name_prefix`, see
+    /// `PermutationChip::construct_with_name_prefix`.
+    pub fn construct_with_name_prefix(
+        config: <Self as halo2_proofs::circuit::Chip<F>>::Config,
+        name_prefix: &'static str,
+    ) -> Self {
         Self {
             config,
+            name_prefix,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// The number of advice cells `compute` and `compute_in_place` assign:
+    /// one for the copy of the input, and one for each batch of multiplications,
+    /// the last of which may contain less than `MUL_BATCH_SIZE` factors.
+    /// If `MUL_BATCH_SIZE > N_FACTORS`, all the factors end up in a single batch.
+    /// If `N_FACTORS == 0`, there is no batch, but a cell set to `F::ONE`.
+    pub const fn cell_count() -> usize {
+        if N_FACTORS == 0 {
+            return 2;
+        }
+        1 + N_FACTORS / MUL_BATCH_SIZE
+            + if N_FACTORS % MUL_BATCH_SIZE != 0 {
+                1
+            } else {
+                0
+            }
+    }
+
+    /// The number of advice cells the gate spans.
+    /// The cells are laid out by a `DivModCounter`, which yields its first
+    /// position after one increment, so the first cell of the first row
+    /// is skipped, and the gate spans one more cell than `cell_count()`.
+    pub const fn required_cells() -> usize {
+        Self::cell_count() + 1
+    }
+
+    /// The number of rows the gate spans, when its cells are laid out
+    /// in `N_COLUMNS` columns.
+    pub const fn required_rows() -> usize {
+        Self::required_cells().div_ceil(N_COLUMNS)
+    }
+
+    /// The largest rotation queried by the gate.
+    pub const fn max_rotation() -> usize {
+        Self::required_rows() - 1
+    }
+
+    /// The degree of the factorial gate, i.e. the largest degree
+    /// of its constraints, as a polynomial in the queried cells.
+    ///
+    /// Each constraint `s_fact * (next - last * product)` multiplies
+    /// the product accumulated so far by a batch of `MUL_BATCH_SIZE` factors,
+    /// each one of degree one in the first cell, so the degree grows linearly
+    /// with `MUL_BATCH_SIZE`: the selector and the cell holding the product
+    /// so far contribute one degree each, on top of the batch.
+    /// The first batch starts from the constant `1`, so if there is
+    /// a single batch, the degree is one less.
+    pub const fn gate_degree() -> usize {
+        if N_FACTORS == 0 {
+            return 2;
+        }
+        if N_FACTORS <= MUL_BATCH_SIZE {
+            // A single batch, with all the factors.
+            1 + N_FACTORS
+        } else if N_FACTORS - MUL_BATCH_SIZE < MUL_BATCH_SIZE {
+            // A full batch, and a smaller one,
+            // whose constraint has a lower degree.
+            1 + MUL_BATCH_SIZE
+        } else {
+            2 + MUL_BATCH_SIZE
+        }
+    }
+
+    /// The `(column index, row offset)` coordinates of the advice cells
+    /// `compute` assigns, relative to the first row of its region, in the
+    /// order in which they are assigned. The column index is the position
+    /// of the column in `TConfig::columns`.
+    ///
+    /// There are `cell_count()` coordinates, since the cell skipped
+    /// by the `DivModCounter` layout is never assigned.
+    pub fn occupied_cells() -> Vec<(usize, usize)> {
+        DivModCounter::new_runtime_mod(0, 0, N_COLUMNS)
+            .take(Self::cell_count())
+            .map(|(div, remainder)| (remainder, div))
+            .collect()
+    }
+
+    /// Configures the gate over `required_cells()` cells of `columns`,
+    /// spanning `required_rows()` rows.
+    ///
+    /// The gate queries all of those rows, so the number of blinding rows
+    /// reported by `meta` grows with them, but the rows of the gate itself
+    /// are not accounted for in `meta.minimum_rows()`.
+    /// See `TruncatedFactorialCircuit::check_k` to validate a choice of `K`.
+    /// If `N_COLUMNS > required_cells()`, some of the columns are never used.
+    ///
+    /// Panics if the parameters are not valid, see `try_configure`.
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
         columns: [Column<Advice>; N_COLUMNS],
     ) -> <Self as halo2_proofs::circuit::Chip<F>>::Config {
-        assert!(
-            N_COLUMNS > 0,
-            "At least one column to allocate multiplication constraints is needed."
-        );
+        Self::try_configure(meta, columns).unwrap_or_else(|err| panic!("{err}"))
+    }
 
-        assert!(
-            MUL_BATCH_SIZE > 0,
-            "Multiplications have to be batched in groups of at least one at a time."
-        );
+    /// Same as `configure`, but if `N_COLUMNS` or `MUL_BATCH_SIZE` are zero,
+    /// or the gate spans more rows than a rotation can reach,
+    /// an error is returned, and `meta` is left untouched.
+    pub fn try_configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: [Column<Advice>; N_COLUMNS],
+    ) -> Result<<Self as halo2_proofs::circuit::Chip<F>>::Config, ConfigError> {
+        if N_COLUMNS == 0 {
+            return Err(ConfigError::NoColumns);
+        }
+        if MUL_BATCH_SIZE == 0 {
+            return Err(ConfigError::EmptyMultiplicationBatch);
+        }
+        if Self::max_rotation() > i32::MAX as usize {
+            return Err(ConfigError::TooManyRows {
+                required_rows: Self::required_rows(),
+            });
+        }
 
         let s_fact = meta.selector();
 
@@ -34,8 +143,8 @@ impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_
             let s_fact = meta.query_selector(s_fact);
 
             let mut next_cell_iter = DivModCounter::new_runtime_mod(0, 0, N_COLUMNS)
-                .into_iter()
-                .map(|(div, rem)| meta.query_advice(columns[rem], Rotation(div as i32)));
+                .cell_positions(&columns)
+                .map(|(column, rotation)| meta.query_advice(column, rotation));
             let mut next_cell = || next_cell_iter.next().expect("the iterator never ends");
 
             let first_cell = next_cell();
@@ -44,42 +153,14 @@ impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_
                 return vec![s_fact * (first_cell - Expression::Constant(F::ONE))];
             }
 
-            let mut field_counter = crate::utilities::FieldCounter::start_counting_from(F::ZERO);
-
-            let mut constraints = vec![];
-
-            let mut last_cell = Expression::Constant(F::ONE);
-
-            let mut batch_multiply = |batch_size| {
-                // product is a chunk of the factors that appear in the factorial.
-                // Which factors they are is kept track in the state of `field_counter`.
-                let product = (&mut field_counter).take(batch_size).fold(
-                    Expression::Constant(F::ONE),
-                    |product, increment| {
-                        product * (first_cell.clone() + Expression::Constant(increment))
-                    },
-                );
-
-                let next_cell = next_cell();
-
-                // We force the next cell to be equal to
-                // the updated value of aggregated product
-                constraints
-                    .push(s_fact.clone() * (next_cell.clone() - last_cell.clone() * product));
-                last_cell = next_cell;
-            };
-
-            for _batch_nr in 0..N_FACTORS / MUL_BATCH_SIZE {
-                batch_multiply(MUL_BATCH_SIZE);
-            }
-
-            if N_FACTORS % MUL_BATCH_SIZE != 0 {
-                batch_multiply(N_FACTORS % MUL_BATCH_SIZE);
-            }
+            // The factors that appear in the factorial.
+            let factors = crate::utilities::FieldCounter::start_counting_from(F::ZERO)
+                .take(N_FACTORS)
+                .map(|increment| first_cell.clone() + Expression::Constant(increment));
 
-            constraints
+            batched_running_product(&s_fact, factors, MUL_BATCH_SIZE, next_cell).0
         });
 
-        TConfig { columns, s_fact }
+        Ok(TConfig { columns, s_fact })
     }
 }
@@ -3,10 +3,14 @@ use std::marker::PhantomData;
 use halo2_proofs::{
     circuit::{Chip, Layouter, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
-    poly::Rotation,
 };
 
-use crate::{utilities::DivModCounter, Number};
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    utilities::{batched_running_product, prefixed_name, ConfigError, DivModCounter},
+    Number,
+};
 
 mod chip_setup_api;
 mod gate_implementation;
@@ -19,12 +23,17 @@ pub struct TruncatedFactorialChip<
     const N_COLUMNS: usize,
 > {
     config: TConfig<N_COLUMNS>,
+    /// Prepended to the annotations of the regions laid out by the chip.
+    name_prefix: &'static str,
     _marker: PhantomData<F>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TConfig<const N_COLUMNS: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::array"))]
     pub columns: [Column<Advice>; N_COLUMNS],
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
     s_fact: Selector,
 }
 
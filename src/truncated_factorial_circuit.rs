@@ -3,37 +3,97 @@ use halo2_proofs::{
     plonk::{Column, Instance},
 };
 
-use crate::Number;
+#[cfg(feature = "serde")]
+use crate::utilities::serde_layout;
+use crate::{
+    range_check_chip::{RCConfig, RangeCheckChip},
+    truncated_factorial_chip::TruncatedFactorialChip,
+    utilities::ConfigError,
+    Number,
+};
 
+/// A circuit that proves that the output, exposed in the first instance column,
+/// is the product of `N_FACTORS` consecutive factors, starting from a secret one.
+///
+/// If `PUBLIC_INPUT` is set, the first factor is not secret: it is exposed
+/// in a second instance column, so that the circuit proves the output
+/// to be the truncated factorial of a public value.
 #[derive(Default)]
 pub struct TruncatedFactorialCircuit<
     F: ff::Field,
     const N_FACTORS: usize,
     const MUL_BATCH_SIZE: usize,
     const N_COLUMNS: usize,
+    const PUBLIC_INPUT: bool = false,
 > {
     product_starting_from: Value<F>,
 }
 
-impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_COLUMNS: usize>
-    TruncatedFactorialCircuit<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS>
+impl<
+        F: ff::Field,
+        const N_FACTORS: usize,
+        const MUL_BATCH_SIZE: usize,
+        const N_COLUMNS: usize,
+        const PUBLIC_INPUT: bool,
+    > TruncatedFactorialCircuit<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS, PUBLIC_INPUT>
 {
     pub fn new(first_factor: F) -> Self {
         Self {
             product_starting_from: Value::known(first_factor),
         }
     }
+
+    /// The value the circuit outputs for `first_factor`, computed out of the circuit,
+    /// i.e. `first_factor * (first_factor + 1) * ... * (first_factor + N_FACTORS - 1)`.
+    pub fn expected_output(first_factor: F) -> F {
+        crate::utilities::FieldCounter::start_counting_from(first_factor)
+            .take(N_FACTORS)
+            .fold(F::ONE, |product, factor| product * factor)
+    }
+
+    /// Checks that the circuit can be laid out in `2^k` rows.
+    ///
+    /// The minimum number of rows reported by the constraint system
+    /// only accounts for the blinding rows, that grow with the rows
+    /// spanned by the factorial gate, and not for the gate itself,
+    /// so a `k` that satisfies the constraint system may still be too small,
+    /// and the proof generation fails halfway through the assignment.
+    pub fn check_k(k: u32) -> Result<(), halo2_proofs::plonk::Error> {
+        let mut cs = halo2_proofs::plonk::ConstraintSystem::<F>::default();
+        <Self as halo2_proofs::plonk::Circuit<F>>::configure(&mut cs);
+
+        // The input item takes the first row, and the factorial gate
+        // is laid out below it, since they share the first column.
+        let used_rows =
+            1 + TruncatedFactorialChip::<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS>::required_rows();
+        let required_rows = cs.minimum_rows().max(used_rows + cs.blinding_factors() + 1);
+
+        if (1_usize << k) < required_rows {
+            return Err(halo2_proofs::plonk::Error::NotEnoughRowsAvailable { current_k: k });
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TFCircuitConfig<const N_COLUMNS: usize> {
     tf_config: crate::truncated_factorial_chip::TConfig<N_COLUMNS>,
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::item"))]
     instance_column: Column<Instance>,
+    /// Only present if the first factor is exposed.
+    #[cfg_attr(feature = "serde", serde(with = "serde_layout::option"))]
+    input_instance_column: Option<Column<Instance>>,
 }
 
-impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_COLUMNS: usize>
-    halo2_proofs::plonk::Circuit<F>
-    for TruncatedFactorialCircuit<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS>
+impl<
+        F: ff::Field,
+        const N_FACTORS: usize,
+        const MUL_BATCH_SIZE: usize,
+        const N_COLUMNS: usize,
+        const PUBLIC_INPUT: bool,
+    > halo2_proofs::plonk::Circuit<F>
+    for TruncatedFactorialCircuit<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS, PUBLIC_INPUT>
 {
     type Config = TFCircuitConfig<N_COLUMNS>;
     type FloorPlanner = SimpleFloorPlanner;
@@ -46,6 +106,11 @@ impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_
         let columns = [(); N_COLUMNS].map(|_| meta.advice_column());
         let instance_column = meta.instance_column();
         meta.enable_equality(instance_column);
+        let input_instance_column = PUBLIC_INPUT.then(|| {
+            let column = meta.instance_column();
+            meta.enable_equality(column);
+            column
+        });
 
         TFCircuitConfig {
             tf_config: crate::truncated_factorial_chip::TruncatedFactorialChip::<
@@ -55,6 +120,7 @@ impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_
                 N_COLUMNS,
             >::configure(meta, columns),
             instance_column,
+            input_instance_column,
         }
     }
 
@@ -86,6 +152,14 @@ impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_
                 },
             )?;
 
+        if let Some(input_instance_column) = config.input_instance_column {
+            layouter.namespace(|| "copy of input").constrain_instance(
+                input_item.cell(),
+                input_instance_column,
+                0,
+            )?;
+        }
+
         let output_item = factorial_chip.compute(
             layouter.namespace(|| "truncated factorial computation"),
             input_item,
@@ -101,6 +175,165 @@ impl<F: ff::Field, const N_FACTORS: usize, const MUL_BATCH_SIZE: usize, const N_
     }
 }
 
+/// Same as `TruncatedFactorialCircuit`, but the input, and the product
+/// accumulated after each batch of `MUL_BATCH_SIZE` factors, are
+/// range-checked to be in `[0, 2^BITS)`, so that a proof only succeeds if
+/// the product computed over the integers fits in `BITS` bits.
+///
+/// If every range-checked value is below `2^BITS`, then no batch product
+/// wraps around the field modulus, as long as `2^BITS` times the product of
+/// any `MUL_BATCH_SIZE` consecutive factors is smaller than the modulus.
+/// Each factor is below `2^(BITS + 1)`, as long as `N_FACTORS` is at most
+/// `2^BITS`, so `configure` requires both that and
+/// `BITS + MUL_BATCH_SIZE * (BITS + 1)` to be smaller than `F::NUM_BITS`.
+#[derive(Default)]
+pub struct BoundedTruncatedFactorialCircuit<
+    F: ff::PrimeField,
+    const N_FACTORS: usize,
+    const MUL_BATCH_SIZE: usize,
+    const N_COLUMNS: usize,
+    const BITS: usize,
+> {
+    product_starting_from: Value<F>,
+}
+
+impl<
+        F: ff::PrimeField,
+        const N_FACTORS: usize,
+        const MUL_BATCH_SIZE: usize,
+        const N_COLUMNS: usize,
+        const BITS: usize,
+    > BoundedTruncatedFactorialCircuit<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS, BITS>
+{
+    pub fn new(first_factor: F) -> Self {
+        Self {
+            product_starting_from: Value::known(first_factor),
+        }
+    }
+
+    /// Same as `configure`, but if `BITS` is not valid, an error is returned.
+    /// `BITS` is checked first: if it is too large for the batch products
+    /// not to wrap around the field modulus, or too small for `N_FACTORS`
+    /// factors to fit in `BITS + 1` bits, `meta` is left untouched.
+    pub fn try_configure(
+        meta: &mut halo2_proofs::plonk::ConstraintSystem<F>,
+    ) -> Result<BoundedTFCircuitConfig<N_COLUMNS, BITS>, ConfigError> {
+        // `BITS + MUL_BATCH_SIZE * (BITS + 1) < F::NUM_BITS`
+        // holds exactly when `BITS < max_bits`.
+        let max_bits = (F::NUM_BITS as usize)
+            .saturating_sub(MUL_BATCH_SIZE)
+            .div_ceil(MUL_BATCH_SIZE + 1);
+        if BITS >= max_bits {
+            return Err(ConfigError::TooManyBitsForBatch {
+                bits: BITS,
+                mul_batch_size: MUL_BATCH_SIZE,
+                max_bits,
+            });
+        }
+        // The factors are the input, which is below `2^BITS`, plus `0`, ...,
+        // `N_FACTORS - 1`, so they are below `2^(BITS + 1)` if `N_FACTORS <= 2^BITS`.
+        if BITS < usize::BITS as usize && N_FACTORS > 1 << BITS {
+            return Err(ConfigError::TooManyFactorsForBits {
+                factors: N_FACTORS,
+                bits: BITS,
+            });
+        }
+
+        let tf_circuit_config = <TruncatedFactorialCircuit<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS>
+            as halo2_proofs::plonk::Circuit<F>>::configure(meta);
+
+        let input_column = meta.advice_column();
+        let bits_column = meta.advice_column();
+
+        Ok(BoundedTFCircuitConfig {
+            tf_circuit_config,
+            rc_config: RangeCheckChip::<F, BITS>::try_configure(meta, input_column, bits_column)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoundedTFCircuitConfig<const N_COLUMNS: usize, const BITS: usize> {
+    tf_circuit_config: TFCircuitConfig<N_COLUMNS>,
+    rc_config: RCConfig<BITS>,
+}
+
+impl<
+        F: ff::PrimeField,
+        const N_FACTORS: usize,
+        const MUL_BATCH_SIZE: usize,
+        const N_COLUMNS: usize,
+        const BITS: usize,
+    > halo2_proofs::plonk::Circuit<F>
+    for BoundedTruncatedFactorialCircuit<F, N_FACTORS, MUL_BATCH_SIZE, N_COLUMNS, BITS>
+{
+    type Config = BoundedTFCircuitConfig<N_COLUMNS, BITS>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Default::default()
+    }
+
+    fn configure(meta: &mut halo2_proofs::plonk::ConstraintSystem<F>) -> Self::Config {
+        Self::try_configure(meta).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<F>,
+    ) -> Result<(), halo2_proofs::plonk::Error> {
+        let factorial_chip = crate::truncated_factorial_chip::TruncatedFactorialChip::<
+            F,
+            N_FACTORS,
+            MUL_BATCH_SIZE,
+            N_COLUMNS,
+        >::construct(config.tf_circuit_config.tf_config);
+        let range_check_chip = RangeCheckChip::<F, BITS>::construct(config.rc_config);
+
+        let input_item = layouter
+            .namespace(|| "allocation of input item")
+            .assign_region(
+                || "allocation of input item",
+                |mut region| {
+                    region
+                        .assign_advice(
+                            || "input item",
+                            factorial_chip.config().columns[0],
+                            0,
+                            || self.product_starting_from,
+                        )
+                        .map(Number)
+                },
+            )?;
+
+        let batch_products = factorial_chip.compute_batch_products(
+            layouter.namespace(|| "truncated factorial computation"),
+            input_item.clone(),
+        )?;
+
+        for (idx, item) in core::iter::once(input_item)
+            .chain(batch_products.iter().cloned())
+            .enumerate()
+        {
+            range_check_chip
+                .constrain_in_range(layouter.namespace(|| format!("range check {idx}")), item)?;
+        }
+
+        let output_item = batch_products
+            .last()
+            .expect("there is always at least one batch product");
+        layouter.namespace(|| "copy of output").constrain_instance(
+            output_item.cell(),
+            config.tf_circuit_config.instance_column,
+            0,
+        )?;
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,6 +526,175 @@ mod tests {
         );
     }
 
+    #[test]
+    /// Test the factorial circuit on randomly sampled first factors, for a range
+    /// of `N_FACTORS`, `MUL_BATCH_SIZE`, and `N_COLUMNS` values, checking that the
+    /// mock prover accepts the output computed out of the circuit,
+    /// and rejects a perturbed one.
+    /// The RNG is seeded, so that failures can be reproduced.
+    fn mock_factorial_random_inputs() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        const SEED: u64 = 0x5eed;
+        const NR_SAMPLES_PER_CONFIG: usize = 2;
+
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        /// This macro exists because [`iter_apply_macro`] requires
+        /// a macro argument. It behaves like a generic function,
+        /// with `n_factors`, `mul_batch_size` and `n_columns` as generic parameters.
+        macro_rules! test_random_inputs {
+            (
+                $n_factors: literal,
+                $mul_batch_size: literal,
+                $n_columns: literal
+            ) => {{
+                type FactorialCircuit = TruncatedFactorialCircuit<Fp, $n_factors, $mul_batch_size, $n_columns>;
+
+                let pow_of_2_max_rows = (1..)
+                    .find(|&k| FactorialCircuit::check_k(k).is_ok())
+                    .expect("some k is large enough");
+
+                for _ in 0..NR_SAMPLES_PER_CONFIG {
+                    let first_factor = <Fp as ff::Field>::random(&mut rng);
+                    let expected_out = FactorialCircuit::expected_output(first_factor);
+
+                    let config = format!(
+                        "N_FACTORS = {} ; MUL_BATCH_SIZE = {} ; N_COLUMNS = {} ; input = {:?}",
+                        $n_factors, $mul_batch_size, $n_columns, first_factor
+                    );
+                    assert_eq!(
+                        test_with_params!(
+                            <$n_factors, $mul_batch_size, $n_columns>
+                            (first_factor)
+                            [expected_out]
+                            {pow_of_2_max_rows}
+                        ),
+                        Ok(()),
+                        "Proof verification goes wrong with {config}"
+                    );
+                    assert!(
+                        test_with_params!(
+                            <$n_factors, $mul_batch_size, $n_columns>
+                            (first_factor)
+                            [expected_out + Fp::from(1)]
+                            {pow_of_2_max_rows}
+                        )
+                        .is_err(),
+                        "Bogus proof was accepted with {config}"
+                    );
+                }
+            }};
+        }
+
+        crate::iter_apply_macro!(
+            test_random_inputs;
+            [1, 5, 12, 31]
+            [1, 2, 3, 7]
+            [1, 2, 4]
+        );
+    }
+
+    #[test]
+    /// Test that `recommended_k` leaves room for the rows used by the circuit,
+    /// as computed by hand in `check_k`, and for the blinding rows reported
+    /// by `ProverWrapper::blinding_rows`.
+    fn recommended_k_factorial_1000() {
+        use crate::utilities::{recommended_k, ProverWrapper};
+
+        macro_rules! check_recommended_k {
+            ($mul_batch_size: literal, $n_columns: literal) => {{
+                type Factorial = TruncatedFactorialCircuit<Fp, 1000, $mul_batch_size, $n_columns>;
+
+                let mut cs = ConstraintSystem::default();
+                Factorial::configure(&mut cs);
+
+                let (k, minimum_rows) = recommended_k(&Factorial::default()).expect("layout should not fail");
+                assert_eq!(minimum_rows, cs.minimum_rows());
+
+                let blinding_rows = ProverWrapper::<Factorial>::blinding_rows(&Factorial::default());
+                assert_eq!(blinding_rows, cs.blinding_factors());
+
+                // The input item takes the first row, and the factorial gate
+                // is laid out below it. The row after the blinding rows is reserved.
+                let used_rows = 1 + TruncatedFactorialChip::<Fp, 1000, $mul_batch_size, $n_columns>::required_rows();
+                assert!((1_usize << k) >= used_rows + blinding_rows + 1);
+                assert!(Factorial::check_k(k).is_ok());
+            }};
+        }
+
+        crate::iter_apply_macro!(
+            check_recommended_k;
+            [1, 4, 8]
+            [1, 5, 11]
+        );
+    }
+
+    #[test]
+    /// Test that a parameters memo seeded with a single large setup derives
+    /// out of it the parameters for smaller `K` values, that those are the ones
+    /// `Params::new` generates, and that the provers initialized out of them
+    /// prove circuits that need different `K` values.
+    fn factorial_params_memo() {
+        use crate::utilities::{recommended_k, ParamsMemo, ProverWrapper, VerifierWrapper};
+        use halo2_proofs::{pasta::EqAffine, poly::commitment::Params};
+
+        fn factorial(n: u64) -> Fp {
+            (1..=n).fold(Fp::from(1), |product, k| product * Fp::from(k))
+        }
+
+        fn serialized(parameters: &Params<EqAffine>) -> Vec<u8> {
+            let mut bytes = vec![];
+            parameters
+                .write(&mut bytes)
+                .expect("writing to a vector should not fail");
+            bytes
+        }
+
+        type SmallCircuit = TruncatedFactorialCircuit<Fp, 10, 1, 1>;
+        type BigCircuit = TruncatedFactorialCircuit<Fp, 100, 1, 1>;
+
+        let (small_k, _) = recommended_k(&SmallCircuit::default()).expect("layout should not fail");
+        let (big_k, _) = recommended_k(&BigCircuit::default()).expect("layout should not fail");
+        assert!(small_k < big_k);
+        let large_k = big_k + 1;
+
+        let mut memo = ParamsMemo::<EqAffine>::from_parameters(Params::new(large_k));
+        assert_eq!(Vec::from_iter(memo.memoized_ks()), vec![large_k]);
+
+        let small_parameters = serialized(memo.parameters(small_k));
+        assert_eq!(small_parameters, serialized(&Params::new(small_k)));
+        assert_eq!(Vec::from_iter(memo.memoized_ks()), vec![small_k, large_k]);
+
+        let small_instance = [factorial(10)];
+        let small_instance = [small_instance.as_slice()];
+        let big_instance = [factorial(100)];
+        let big_instance = [big_instance.as_slice()];
+
+        let mut prover =
+            ProverWrapper::initialize_prover_from_memo(&mut memo, small_k, SmallCircuit::default())
+                .expect("prover setup should not fail");
+        prover.add_item(SmallCircuit::new(Fp::from(1)), small_instance.as_slice());
+        let transcript = prover.prove().expect("proof generation should not fail");
+        assert!(VerifierWrapper::from(prover)
+            .verify([small_instance.as_slice()], transcript.as_slice()));
+
+        let mut prover =
+            ProverWrapper::initialize_prover_from_memo(&mut memo, big_k, BigCircuit::default())
+                .expect("prover setup should not fail");
+        prover.add_item(BigCircuit::new(Fp::from(1)), big_instance.as_slice());
+        let transcript = prover.prove().expect("proof generation should not fail");
+        assert!(
+            VerifierWrapper::from(prover).verify([big_instance.as_slice()], transcript.as_slice())
+        );
+
+        // Every `K` has been derived out of the large setup.
+        assert_eq!(
+            Vec::from_iter(memo.memoized_ks()),
+            vec![small_k, big_k, large_k]
+        );
+    }
+
     #[test]
     /// Test the sudoku circuit with actual prover and verifier through the wrappers we implemented.
     /// This is very similar to a real use case.
@@ -312,7 +714,8 @@ mod tests {
         .expect("prover setup should not fail");
         let circuit = TruncatedFactorialCircuit::<Fp, N_FACTORS, 20, 10>::new(Fp::from(1));
 
-        let instance = [(1..=N_FACTORS).fold(Fp::from(1), |acc, f| acc * Fp::from(f as u64))];
+        let instance =
+            [TruncatedFactorialCircuit::<Fp, N_FACTORS, 20, 10>::expected_output(Fp::from(1))];
         let instance = [instance.as_slice()];
 
         prover.add_item(circuit, instance.as_slice());
@@ -334,4 +737,499 @@ mod tests {
             )
         };
     }
+
+    #[test]
+    /// Test that `expected_output` agrees with the products computed by hand,
+    /// including the instance value of the `factorial` test.
+    fn factorial_expected_output() {
+        assert_eq!(
+            TruncatedFactorialCircuit::<Fp, 1000, 20, 10>::expected_output(Fp::from(1)),
+            (1..=1000).fold(Fp::from(1), |acc, f| acc * Fp::from(f as u64))
+        );
+        assert_eq!(
+            TruncatedFactorialCircuit::<Fp, 3, 1, 1>::expected_output(Fp::from(5)),
+            Fp::from(5 * 6 * 7)
+        );
+        assert_eq!(
+            TruncatedFactorialCircuit::<Fp, 0, 1, 1>::expected_output(Fp::from(5)),
+            Fp::from(1)
+        );
+    }
+
+    #[test]
+    /// Test that the stepped `FieldCounter`, with the default step,
+    /// yields the factors the chip used to build by offsetting the input,
+    /// and that the chip output still matches the products built that way.
+    fn factorial_stepped_counter() {
+        use crate::utilities::FieldCounter;
+
+        const POW_OF_2_MAX_ROWS: u32 = 6;
+
+        for first_factor in [0, 1, 5, 1000].map(Fp::from) {
+            let offset_factors = FieldCounter::start_counting_from(Fp::from(0))
+                .map(|f| first_factor + f)
+                .take(10);
+            assert!(
+                FieldCounter::start_counting_from_step(first_factor, Fp::from(1))
+                    .take(10)
+                    .eq(offset_factors.clone())
+            );
+
+            let expected = offset_factors.fold(Fp::from(1), |product, factor| product * factor);
+            assert_eq!(
+                test_with_params!(
+                    <10, 4, 3>
+                    (first_factor)
+                    [expected]
+                    {POW_OF_2_MAX_ROWS}
+                ),
+                Ok(())
+            );
+        }
+
+        assert!(
+            FieldCounter::start_counting_from_step(Fp::from(7), Fp::from(3))
+                .take(3)
+                .eq([7, 10, 13].map(Fp::from))
+        );
+    }
+
+    #[test]
+    /// Test the number of cells and rows spanned by the factorial gate.
+    fn factorial_required_cells() {
+        use crate::truncated_factorial_chip::TruncatedFactorialChip;
+
+        assert_eq!(TruncatedFactorialChip::<Fp, 0, 1, 1>::required_cells(), 3);
+        assert_eq!(TruncatedFactorialChip::<Fp, 1, 1, 1>::required_cells(), 3);
+        assert_eq!(TruncatedFactorialChip::<Fp, 10, 3, 4>::required_cells(), 6);
+        assert_eq!(TruncatedFactorialChip::<Fp, 10, 20, 4>::required_cells(), 3);
+        assert_eq!(
+            TruncatedFactorialChip::<Fp, 1000, 20, 10>::required_cells(),
+            52
+        );
+
+        assert_eq!(TruncatedFactorialChip::<Fp, 10, 3, 4>::required_rows(), 2);
+        assert_eq!(TruncatedFactorialChip::<Fp, 10, 20, 4>::required_rows(), 1);
+        assert_eq!(
+            TruncatedFactorialChip::<Fp, 1000, 20, 10>::required_rows(),
+            6
+        );
+        // The skipped first cell makes the gate spill into a new row.
+        assert_eq!(TruncatedFactorialChip::<Fp, 10, 5, 3>::required_rows(), 2);
+        assert_eq!(TruncatedFactorialChip::<Fp, 2, 1, 1>::required_rows(), 4);
+    }
+
+    #[test]
+    /// Test that the degree of the factorial gate grows with `MUL_BATCH_SIZE`,
+    /// and that it is the degree of the constraint system, when it is larger
+    /// than the degree 3 required by the permutation argument.
+    fn factorial_gate_degree() {
+        use crate::truncated_factorial_chip::TruncatedFactorialChip;
+
+        macro_rules! degree {
+            ($n_factors: literal, $mul_batch_size: literal) => {{
+                let mut cs = ConstraintSystem::<Fp>::default();
+                let columns = [(); 2].map(|_| cs.advice_column());
+                TruncatedFactorialChip::<Fp, $n_factors, $mul_batch_size, 2>::configure(
+                    &mut cs, columns,
+                );
+                let degree =
+                    TruncatedFactorialChip::<Fp, $n_factors, $mul_batch_size, 2>::gate_degree();
+                assert_eq!(cs.degree(), degree.max(3));
+                degree
+            }};
+        }
+
+        let degrees = [
+            degree!(20, 1),
+            degree!(20, 2),
+            degree!(20, 3),
+            degree!(20, 5),
+            degree!(20, 8),
+            degree!(20, 10),
+        ];
+        assert_eq!(degrees, [3, 4, 5, 7, 10, 12]);
+
+        // A single batch does not multiply by the product so far.
+        assert_eq!(degree!(4, 4), 5);
+        assert_eq!(degree!(3, 5), 4);
+        // The smaller batch does not raise the degree.
+        assert_eq!(degree!(7, 5), 6);
+        assert_eq!(degree!(0, 1), 2);
+    }
+
+    #[test]
+    /// Test that `cell_count` matches the cells `compute` actually assigns,
+    /// as recorded by laying out the circuit, for a range of configurations,
+    /// including ones with a smaller last batch, and no factors at all.
+    fn factorial_cell_count() {
+        use crate::{truncated_factorial_chip::TruncatedFactorialChip, utilities::assigned_cells};
+
+        macro_rules! check_cell_count {
+            ($(<$N_FACTORS: literal, $MUL_BATCH_SIZE: literal, $N_COLUMNS: literal>),*) => {$({
+                // The circuit assigns the input item on its own, before calling the chip.
+                assert_eq!(
+                    assigned_cells(&TruncatedFactorialCircuit::<
+                        Fp,
+                        $N_FACTORS,
+                        $MUL_BATCH_SIZE,
+                        $N_COLUMNS,
+                    >::default()).expect("layout should not fail"),
+                    1 + TruncatedFactorialChip::<
+                        Fp,
+                        $N_FACTORS,
+                        $MUL_BATCH_SIZE,
+                        $N_COLUMNS,
+                    >::cell_count()
+                );
+            })*};
+        }
+        check_cell_count!(<10, 3, 1>, <9, 3, 1>, <0, 3, 1>, <7, 3, 2>, <20, 4, 5>, <3, 10, 4>);
+
+        // With a single column, the cells are laid out one per row,
+        // after the skipped first row.
+        assert_eq!(
+            TruncatedFactorialChip::<Fp, 10, 3, 1>::required_rows(),
+            TruncatedFactorialChip::<Fp, 10, 3, 1>::cell_count() + 1
+        );
+    }
+
+    #[test]
+    /// Test that the bounded factorial circuit accepts `20!`, that fits in 64 bits,
+    /// and rejects `25!`, that does not, even though it does not wrap around the field.
+    fn mock_bounded_factorial() {
+        // Every range check spans `BITS` rows.
+        const POW_OF_2_MAX_ROWS: u32 = 10;
+
+        fn factorial(n: u64) -> Fp {
+            (1..=n).fold(Fp::from(1), |product, k| product * Fp::from(k))
+        }
+
+        crate::utilities::assert_satisfied(
+            POW_OF_2_MAX_ROWS,
+            &BoundedTruncatedFactorialCircuit::<Fp, 20, 2, 2, 64>::new(Fp::from(1)),
+            vec![vec![factorial(20)]],
+        );
+        crate::utilities::assert_rejected(
+            POW_OF_2_MAX_ROWS,
+            &BoundedTruncatedFactorialCircuit::<Fp, 25, 2, 2, 64>::new(Fp::from(1)),
+            vec![vec![factorial(25)]],
+        );
+
+        // Without the bound, `25!` is accepted.
+        crate::utilities::assert_satisfied(
+            POW_OF_2_MAX_ROWS,
+            &TruncatedFactorialCircuit::<Fp, 25, 2, 2>::new(Fp::from(1)),
+            vec![vec![factorial(25)]],
+        );
+    }
+
+    #[test]
+    /// Test that the circuit with a public input accepts `5 * 6 * 7`, with both
+    /// the first factor and the product exposed, and rejects a mismatched first factor.
+    fn mock_factorial_public_input() {
+        type PublicInputCircuit = TruncatedFactorialCircuit<Fp, 3, 1, 1, true>;
+        const K: u32 = 5;
+
+        let circuit = PublicInputCircuit::new(Fp::from(5));
+        crate::utilities::assert_satisfied(
+            K,
+            &circuit,
+            vec![vec![Fp::from(210)], vec![Fp::from(5)]],
+        );
+        crate::utilities::assert_rejected(
+            K,
+            &circuit,
+            vec![vec![Fp::from(210)], vec![Fp::from(6)]],
+        );
+
+        // The product of `6 * 7 * 8` does not match the exposed first factor either.
+        crate::utilities::assert_rejected(
+            K,
+            &PublicInputCircuit::new(Fp::from(6)),
+            vec![vec![Fp::from(336)], vec![Fp::from(5)]],
+        );
+    }
+
+    #[test]
+    /// Test that `check_k` refuses a `K` that satisfies the constraint system,
+    /// but that does not leave room for the factorial gate,
+    /// and that the mock prover agrees with it.
+    fn factorial_check_k() {
+        type Circuit100 = TruncatedFactorialCircuit<Fp, 100, 1, 1>;
+
+        fn factorial(n: u64) -> Fp {
+            (1..=n).fold(Fp::from(1), |product, k| product * Fp::from(k))
+        }
+
+        let mut cs = ConstraintSystem::<Fp>::default();
+        Circuit100::configure(&mut cs);
+        let small_k = cs.minimum_rows().next_power_of_two().ilog2();
+
+        assert!(Circuit100::check_k(small_k).is_err());
+        assert!(MockProver::run(
+            small_k,
+            &Circuit100::new(Fp::from(1)),
+            vec![vec![factorial(100)]]
+        )
+        .is_err());
+
+        assert!(Circuit100::check_k(small_k + 1).is_ok());
+        crate::utilities::assert_satisfied(
+            small_k + 1,
+            &Circuit100::new(Fp::from(1)),
+            vec![vec![factorial(100)]],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    /// Test that the configuration of the factorial chip survives a round trip through JSON.
+    fn factorial_config_serde_round_trip() {
+        use crate::truncated_factorial_chip::{TConfig, TruncatedFactorialChip};
+
+        let mut meta = ConstraintSystem::<Fp>::default();
+        // We allocate some columns and selectors beforehand,
+        // so that the columns and selector of the chip do not have index 0.
+        let _ = [(); 2].map(|_| meta.advice_column());
+        let _ = [(); 3].map(|_| meta.selector());
+        let columns = [(); 4].map(|_| meta.advice_column());
+        let config = TruncatedFactorialChip::<Fp, 10, 3, 4>::configure(&mut meta, columns);
+
+        let json = serde_json::to_string(&config).expect("serialization should not fail");
+        let deserialized: TConfig<4> =
+            serde_json::from_str(&json).expect("deserialization should not fail");
+
+        assert_eq!(deserialized.columns, config.columns);
+        assert_eq!(format!("{deserialized:?}"), format!("{config:?}"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    /// Test that the configuration of the bounded factorial circuit,
+    /// range check included, survives a round trip through JSON.
+    fn bounded_factorial_config_serde_round_trip() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let config = BoundedTruncatedFactorialCircuit::<Fp, 20, 1, 2, 64>::configure(&mut meta);
+
+        let json = serde_json::to_string(&config).expect("serialization should not fail");
+        let deserialized: BoundedTFCircuitConfig<2, 64> =
+            serde_json::from_str(&json).expect("deserialization should not fail");
+
+        assert_eq!(format!("{deserialized:?}"), format!("{config:?}"));
+    }
+
+    #[test]
+    /// Test the coordinates of the cells occupied by the factorial chip
+    /// for a known configuration, and that they are distinct and fit
+    /// in the rows spanned by the gate, for a range of configurations.
+    fn factorial_chip_occupied_cells() {
+        use crate::truncated_factorial_chip::TruncatedFactorialChip;
+
+        // The input copy, two full batches, and a batch with one factor.
+        assert_eq!(
+            TruncatedFactorialChip::<Fp, 5, 2, 3>::occupied_cells(),
+            vec![(1, 0), (2, 0), (0, 1), (1, 1)]
+        );
+        // The output, set to one, and the input copy, which the gate does not read.
+        assert_eq!(
+            TruncatedFactorialChip::<Fp, 0, 2, 3>::occupied_cells(),
+            vec![(1, 0), (2, 0)]
+        );
+
+        macro_rules! check_occupied_cells {
+            ($(<$N_FACTORS: literal, $MUL_BATCH_SIZE: literal, $N_COLUMNS: literal>),*) => {$({
+                type Chip = TruncatedFactorialChip<Fp, $N_FACTORS, $MUL_BATCH_SIZE, $N_COLUMNS>;
+                let cells = Chip::occupied_cells();
+                assert_eq!(cells.len(), Chip::cell_count());
+                assert_eq!(
+                    cells.iter().collect::<std::collections::BTreeSet<_>>().len(),
+                    cells.len()
+                );
+                assert!(cells
+                    .iter()
+                    .all(|&(col, row)| col < $N_COLUMNS && row < Chip::required_rows()));
+            })*};
+        }
+        check_occupied_cells!(<0, 1, 1>, <1, 1, 1>, <7, 3, 2>, <20, 4, 5>, <20, 1, 3>, <3, 10, 4>);
+    }
+
+    /// A circuit that computes the truncated factorial with `compute_in_place`,
+    /// exposing the output in the first row of the instance column,
+    /// and the input in the second one.
+    struct InPlaceFactorialCircuit<const N_FACTORS: usize> {
+        first_factor: Value<Fp>,
+    }
+
+    impl<const N_FACTORS: usize> Circuit<Fp> for InPlaceFactorialCircuit<N_FACTORS> {
+        type Config = TFCircuitConfig<3>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                first_factor: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            TruncatedFactorialCircuit::<Fp, N_FACTORS, 4, 3>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), halo2_proofs::plonk::Error> {
+            let factorial_chip =
+                TruncatedFactorialChip::<Fp, N_FACTORS, 4, 3>::construct(config.tf_config);
+
+            let (input_item, output_item) = factorial_chip.compute_in_place(
+                layouter.namespace(|| "truncated factorial computation"),
+                self.first_factor,
+            )?;
+
+            layouter.constrain_instance(output_item.cell(), config.instance_column, 0)?;
+            layouter.constrain_instance(input_item.cell(), config.instance_column, 1)
+        }
+    }
+
+    #[test]
+    /// Test that `compute_in_place` yields the same output as `compute` for `10!`,
+    /// and that the input it assigns is the one given.
+    fn mock_factorial_in_place() {
+        const POW_OF_2_MAX_ROWS: u32 = 6;
+
+        let expected = TruncatedFactorialCircuit::<Fp, 10, 4, 3>::expected_output(Fp::from(1));
+        let compute_result = test_with_params!(
+            <10, 4, 3>
+            (Fp::from(1))
+            [expected]
+            {POW_OF_2_MAX_ROWS}
+        );
+        assert_eq!(compute_result, Ok(()));
+
+        let run = |first_factor: u64, instance: Vec<Fp>| {
+            MockProver::run(
+                POW_OF_2_MAX_ROWS,
+                &InPlaceFactorialCircuit::<10> {
+                    first_factor: Value::known(Fp::from(first_factor)),
+                },
+                vec![instance],
+            )
+            .expect("Proof generation goes wrong")
+            .verify()
+        };
+
+        assert_eq!(run(1, vec![expected, Fp::from(1)]), Ok(()));
+        assert!(run(1, vec![expected + Fp::from(1), Fp::from(1)]).is_err());
+        assert!(run(2, vec![expected, Fp::from(1)]).is_err());
+
+        // With no factors, the input is still assigned, and exposed.
+        let prover = MockProver::run(
+            POW_OF_2_MAX_ROWS,
+            &InPlaceFactorialCircuit::<0> {
+                first_factor: Value::known(Fp::from(7)),
+            },
+            vec![vec![Fp::from(1), Fp::from(7)]],
+        )
+        .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The in place input is counted by `cell_count`, with or without factors.
+        macro_rules! check_in_place_cell_count {
+            ($($N_FACTORS: literal),*) => {$({
+                assert_eq!(
+                    crate::utilities::assigned_cells(&InPlaceFactorialCircuit::<$N_FACTORS> {
+                        first_factor: Value::unknown(),
+                    }).expect("layout should not fail"),
+                    TruncatedFactorialChip::<Fp, $N_FACTORS, 4, 3>::cell_count()
+                );
+            })*};
+        }
+        check_in_place_cell_count!(0, 10);
+    }
+
+    /// A circuit that computes the truncated factorial of each of
+    /// `first_factors` with `compute_in_place`, exposing the outputs
+    /// in the instance column, in order.
+    /// The number of factorials is part of the wiring, so circuits
+    /// of the same type may be laid out in a different number of rows.
+    struct RepeatedFactorialCircuit {
+        first_factors: Vec<Value<Fp>>,
+    }
+
+    impl Circuit<Fp> for RepeatedFactorialCircuit {
+        type Config = TFCircuitConfig<3>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                first_factors: vec![Value::unknown(); self.first_factors.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            TruncatedFactorialCircuit::<Fp, 10, 4, 3>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), halo2_proofs::plonk::Error> {
+            let factorial_chip =
+                TruncatedFactorialChip::<Fp, 10, 4, 3>::construct(config.tf_config);
+
+            for (idx, first_factor) in self.first_factors.iter().enumerate() {
+                let (_, output_item) = factorial_chip.compute_in_place(
+                    layouter.namespace(|| format!("{idx}-th truncated factorial")),
+                    *first_factor,
+                )?;
+                layouter.constrain_instance(output_item.cell(), config.instance_column, idx)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    /// Test that the `K` computed for a batch of two factorial circuits
+    /// of the same type, laid out in a different number of rows,
+    /// is large enough to prove each of them.
+    fn factorial_batch_k() {
+        use crate::utilities::{assert_satisfied, recommended_k, ProverWrapper};
+
+        type Factorial = TruncatedFactorialCircuit<Fp, 10, 4, 3>;
+
+        let circuit = |nr_factorials: u64| RepeatedFactorialCircuit {
+            first_factors: Vec::from_iter((1..=nr_factorials).map(|n| Value::known(Fp::from(n)))),
+        };
+        let instance = |nr_factorials: u64| {
+            Vec::from_iter((1..=nr_factorials).map(|n| Factorial::expected_output(Fp::from(n))))
+        };
+
+        let wirings = [
+            circuit(1).without_witnesses(),
+            circuit(16).without_witnesses(),
+        ];
+        let (small_k, _) = recommended_k(&wirings[0]).expect("layout should not fail");
+        let (big_k, _) = recommended_k(&wirings[1]).expect("layout should not fail");
+        assert!(small_k < big_k);
+
+        let batch_k = ProverWrapper::<RepeatedFactorialCircuit>::recommended_k_for_batch(&wirings)
+            .expect("layout should not fail");
+        assert_eq!(batch_k, big_k);
+        assert!(
+            ProverWrapper::<RepeatedFactorialCircuit>::recommended_k_for_batch(&[])
+                .expect("layout should not fail")
+                <= small_k
+        );
+
+        for nr_factorials in [1, 16] {
+            assert_satisfied(
+                batch_k,
+                &circuit(nr_factorials),
+                vec![instance(nr_factorials)],
+            );
+        }
+    }
 }
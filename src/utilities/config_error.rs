@@ -0,0 +1,259 @@
+/// The error returned by the `try_configure` functions of the chips,
+/// when their parameters do not allow to configure their gates.
+/// The `configure` functions panic with the same messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// No column is given to allocate multiplication constraints.
+    NoColumns,
+    /// `MUL_BATCH_SIZE` is zero.
+    EmptyMultiplicationBatch,
+    /// The gate spans more rows than a rotation can reach.
+    TooManyRows { required_rows: usize },
+    /// The number of swap selector columns is out of `1..=max_columns`.
+    SwapSelectorColumns {
+        found_columns: usize,
+        max_columns: usize,
+        recommended_columns: usize,
+    },
+    /// `BITS` is zero.
+    NoBits,
+    /// `BITS` is too large for the bit size of the field,
+    /// it has to be smaller than `max_bits`.
+    TooManyBits { bits: usize, max_bits: usize },
+    /// `BITS` is too large to compare `items` numbers one after the other
+    /// without wrapping around the field modulus,
+    /// it has to be smaller than `max_bits`.
+    TooManyBitsForItems {
+        bits: usize,
+        items: usize,
+        max_bits: usize,
+    },
+    /// `BITS` is too large to multiply `mul_batch_size` factors in a batch
+    /// without wrapping around the field modulus,
+    /// it has to be smaller than `max_bits`.
+    TooManyBitsForBatch {
+        bits: usize,
+        mul_batch_size: usize,
+        max_bits: usize,
+    },
+    /// `factors` consecutive factors are too many for `BITS` bits:
+    /// their number has to be at most `2^bits`.
+    TooManyFactorsForBits { factors: usize, bits: usize },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoColumns => write!(
+                f,
+                "At least one column to allocate multiplication constraints is needed."
+            ),
+            Self::EmptyMultiplicationBatch => write!(
+                f,
+                "Multiplications have to be batched in groups of at least one at a time."
+            ),
+            Self::TooManyRows { required_rows } => write!(
+                f,
+                "The gate spans {required_rows} rows, which is more than a rotation can reach. \
+                Consider increasing N_COLUMNS or MUL_BATCH_SIZE."
+            ),
+            Self::SwapSelectorColumns {
+                found_columns,
+                max_columns,
+                recommended_columns,
+            } => write!(
+                f,
+                "Between 1 and {max_columns} columns to allocate swap selectors \
+                are needed, but {found_columns} were given. Fewer columns make each permutation \
+                take more rows, and {recommended_columns} columns are enough for the swap \
+                selectors not to take more rows than the items."
+            ),
+            Self::NoBits => write!(f, "At least one bit is needed."),
+            Self::TooManyBits { bits, max_bits } => write!(
+                f,
+                "{bits} bits were requested, but the number of bits has to be smaller than {max_bits}, \
+                because of the bit size of the field."
+            ),
+            Self::TooManyBitsForItems {
+                bits,
+                items,
+                max_bits,
+            } => write!(
+                f,
+                "{bits} bits were requested to compare {items} items, but the number of bits \
+                has to be smaller than {max_bits}, so that the differences between the items \
+                cannot wrap around the field modulus."
+            ),
+            Self::TooManyBitsForBatch {
+                bits,
+                mul_batch_size,
+                max_bits,
+            } => write!(
+                f,
+                "{bits} bits were requested to multiply {mul_batch_size} factors at a time, but \
+                the number of bits has to be smaller than {max_bits}, so that the batch products \
+                cannot wrap around the field modulus."
+            ),
+            Self::TooManyFactorsForBits { factors, bits } => write!(
+                f,
+                "{factors} factors were requested with {bits} bits, but the number of factors \
+                has to be at most 2^{bits}, so that every factor fits in {} bits.",
+                bits + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        bit_decomposition_chip::BitDecompositionChip, comparison_chip::ComparisonChip,
+        distinctness_chip::DistinctnessChip, permutation_chip::PermutationChip,
+        range_check_chip::RangeCheckChip, set_membership_chip::SetMembershipChip,
+        truncated_factorial_chip::TruncatedFactorialChip,
+        utilities::proving_utilities::ConstraintSystemShape, BoundedTruncatedFactorialCircuit,
+    };
+    use halo2_proofs::{pasta::Fp, plonk::ConstraintSystem};
+
+    #[test]
+    /// Test that every `try_configure` reports each of its invalid parameters
+    /// with the matching error, without configuring any gate.
+    fn try_configure_errors() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let column = meta.advice_column();
+        let fixed_column = meta.fixed_column();
+
+        assert_eq!(
+            TruncatedFactorialChip::<Fp, 3, 1, 0>::try_configure(&mut meta, []).err(),
+            Some(ConfigError::NoColumns)
+        );
+        assert_eq!(
+            TruncatedFactorialChip::<Fp, 3, 0, 1>::try_configure(&mut meta, [column]).err(),
+            Some(ConfigError::EmptyMultiplicationBatch)
+        );
+        assert!(matches!(
+            TruncatedFactorialChip::<Fp, { 1 << 40 }, 1, 1>::try_configure(&mut meta, [column]),
+            Err(ConfigError::TooManyRows { .. })
+        ));
+
+        assert_eq!(
+            SetMembershipChip::<Fp, 3, 1, 0>::try_configure(&mut meta, [], column, fixed_column)
+                .err(),
+            Some(ConfigError::NoColumns)
+        );
+        assert_eq!(
+            SetMembershipChip::<Fp, 3, 0, 1>::try_configure(
+                &mut meta,
+                [column],
+                column,
+                fixed_column
+            )
+            .err(),
+            Some(ConfigError::EmptyMultiplicationBatch)
+        );
+
+        for nr_columns in [0, 4] {
+            assert_eq!(
+                PermutationChip::<3, Fp>::try_configure(
+                    &mut meta,
+                    [column; 3],
+                    vec![column; nr_columns]
+                )
+                .err(),
+                Some(ConfigError::SwapSelectorColumns {
+                    found_columns: nr_columns,
+                    max_columns: 3,
+                    recommended_columns: 1,
+                })
+            );
+        }
+
+        assert_eq!(
+            BitDecompositionChip::<Fp, 0>::try_configure(&mut meta, column, column).err(),
+            Some(ConfigError::NoBits)
+        );
+        assert_eq!(
+            BitDecompositionChip::<Fp, 255>::try_configure(&mut meta, column, column).err(),
+            Some(ConfigError::TooManyBits {
+                bits: 255,
+                max_bits: 255
+            })
+        );
+        assert_eq!(
+            RangeCheckChip::<Fp, 0>::try_configure(&mut meta, column, column).err(),
+            Some(ConfigError::NoBits)
+        );
+
+        assert_eq!(
+            ComparisonChip::<Fp, 0>::try_configure(&mut meta, [column; 4], column).err(),
+            Some(ConfigError::NoBits)
+        );
+        assert_eq!(
+            ComparisonChip::<Fp, 254>::try_configure(&mut meta, [column; 4], column).err(),
+            Some(ConfigError::TooManyBits {
+                bits: 254,
+                max_bits: 254
+            })
+        );
+
+        assert_eq!(
+            DistinctnessChip::<Fp, 3, 0>::try_configure(
+                &mut meta,
+                [column; 3],
+                vec![column],
+                [column; 4],
+                column,
+                fixed_column
+            )
+            .err(),
+            Some(ConfigError::NoBits)
+        );
+        // The comparison chip accepts 253 bits, but the gaps
+        // between four sorted numbers could wrap around the modulus.
+        assert_eq!(
+            DistinctnessChip::<Fp, 4, 253>::try_configure(
+                &mut meta,
+                [column; 4],
+                vec![column],
+                [column; 4],
+                column,
+                fixed_column
+            )
+            .err(),
+            Some(ConfigError::TooManyBitsForItems {
+                bits: 253,
+                items: 4,
+                max_bits: 252
+            })
+        );
+
+        // `64 + 3 * 65` does not fit in the 255 bits of `Fp`.
+        assert_eq!(
+            BoundedTruncatedFactorialCircuit::<Fp, 20, 3, 2, 64>::try_configure(&mut meta).err(),
+            Some(ConfigError::TooManyBitsForBatch {
+                bits: 64,
+                mul_batch_size: 3,
+                max_bits: 63
+            })
+        );
+        // The last of 20 factors starting from `7` would not fit in 4 bits.
+        assert_eq!(
+            BoundedTruncatedFactorialCircuit::<Fp, 20, 1, 2, 3>::try_configure(&mut meta).err(),
+            Some(ConfigError::TooManyFactorsForBits {
+                factors: 20,
+                bits: 3
+            })
+        );
+
+        assert_eq!(
+            ConstraintSystemShape::of(&meta)
+                .expect("the sizes are read off the constraint system")
+                .num_selectors,
+            0
+        );
+    }
+}
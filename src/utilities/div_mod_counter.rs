@@ -1,40 +1,229 @@
-#[derive(Debug, Clone, Copy, Default)]
+use halo2_proofs::{
+    plonk::{Advice, Column},
+    poly::Rotation,
+};
+
+#[derive(Debug, Clone, Copy)]
 pub struct DivModCounter<const MOD: usize, const RUNTIME_MOD: bool = false> {
     div: usize,
     remainder: usize,
     modulo: usize,
+    /// How many positions the counter advances by at every call of `next`.
+    step: usize,
+    /// The state the counter was constructed with, restored by `reset`.
+    initial_state: (usize, usize),
 }
 
 impl<const MOD: usize> DivModCounter<MOD, false> {
     pub fn new_const_mod(div: usize, remainder: usize) -> Self {
+        Self::new_const_mod_step(div, remainder, 1)
+    }
+
+    /// Same as `new_const_mod`, but every call of `next`
+    /// advances the counter by `step` positions.
+    pub fn new_const_mod_step(div: usize, remainder: usize, step: usize) -> Self {
         assert!(MOD != 0, "divisor cannot be 0");
+        assert!(step != 0, "step cannot be 0");
         Self {
             div,
             remainder: remainder % MOD,
             modulo: 0,
+            step,
+            initial_state: (div, remainder % MOD),
         }
     }
+
+    /// The linear index of the `(div, remainder)` position,
+    /// i.e. `div * MOD + remainder`.
+    pub const fn linear_index(div: usize, remainder: usize) -> usize {
+        assert!(
+            remainder < MOD,
+            "the remainder has to be smaller than the divisor"
+        );
+        div * MOD + remainder
+    }
+
+    /// The inverse of `linear_index`, i.e. the `(index / MOD, index % MOD)` couple.
+    pub const fn from_linear_index(index: usize) -> (usize, usize) {
+        (index / MOD, index % MOD)
+    }
 }
 
 impl DivModCounter<0, true> {
     pub fn new_runtime_mod(div: usize, remainder: usize, modulo: usize) -> Self {
+        Self::new_runtime_mod_step(div, remainder, modulo, 1)
+    }
+
+    /// Same as `new_runtime_mod`, but every call of `next`
+    /// advances the counter by `step` positions.
+    pub fn new_runtime_mod_step(div: usize, remainder: usize, modulo: usize, step: usize) -> Self {
         assert!(modulo != 0, "divisor cannot be 0");
+        assert!(step != 0, "step cannot be 0");
         Self {
             div,
             remainder: remainder % modulo,
             modulo,
+            step,
+            initial_state: (div, remainder % modulo),
         }
     }
+
+    /// Same as `DivModCounter::<MOD, false>::linear_index`,
+    /// with the modulo of the counter.
+    pub fn linear_index(&self, div: usize, remainder: usize) -> usize {
+        assert!(
+            remainder < self.modulo,
+            "the remainder has to be smaller than the divisor"
+        );
+        div * self.modulo + remainder
+    }
+
+    /// Same as `DivModCounter::<MOD, false>::from_linear_index`,
+    /// with the modulo of the counter.
+    pub fn from_linear_index(&self, index: usize) -> (usize, usize) {
+        (index / self.modulo, index % self.modulo)
+    }
+}
+
+impl<const MOD: usize, const RUNTIME_MOD: bool> DivModCounter<MOD, RUNTIME_MOD> {
+    /// Brings the counter back to the state it was constructed with,
+    /// so that it yields again the same sequence.
+    pub fn reset(&mut self) {
+        (self.div, self.remainder) = self.initial_state;
+    }
+
+    /// The current `(div, remainder)` couple, that is, the last one
+    /// yielded by `next`, or the constructed one if `next` was never called.
+    pub fn position(&self) -> (usize, usize) {
+        (self.div, self.remainder)
+    }
+
+    /// Turns the counter into an iterator over cell positions:
+    /// every `(div, remainder)` couple is mapped to the
+    /// `remainder`-th column of `columns`, at the rotation `div`.
+    pub fn cell_positions(
+        self,
+        columns: &[Column<Advice>],
+    ) -> impl Iterator<Item = (Column<Advice>, Rotation)> + '_ {
+        self.map(|(div, remainder)| (columns[remainder], Rotation(div as i32)))
+    }
 }
 
 impl<const MOD: usize, const RUNTIME_MOD: bool> Iterator for DivModCounter<MOD, RUNTIME_MOD> {
     type Item = (usize, usize);
     fn next(&mut self) -> Option<Self::Item> {
-        self.remainder += 1;
-        if self.remainder == if RUNTIME_MOD { self.modulo } else { MOD } {
-            self.remainder = 0;
-            self.div += 1;
-        }
+        let modulo = if RUNTIME_MOD { self.modulo } else { MOD };
+        self.remainder += self.step;
+        self.div += self.remainder / modulo;
+        self.remainder %= modulo;
         Some((self.div, self.remainder))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{pasta::Fp, plonk::ConstraintSystem};
+
+    #[test]
+    /// Test that `position` tracks `next`, and that `reset`
+    /// brings the counter back to the constructed state.
+    fn div_mod_counter_position_and_reset() {
+        let mut counter = DivModCounter::<3>::new_const_mod(1, 4);
+        assert_eq!(counter.position(), (1, 1));
+        for _ in 0..5 {
+            let item = counter.next();
+            assert_eq!(item, Some(counter.position()));
+        }
+        assert_eq!(counter.position(), (3, 0));
+
+        counter.reset();
+        assert_eq!(counter.position(), (1, 1));
+        assert_eq!(counter.next(), Some((1, 2)));
+
+        let mut counter = DivModCounter::<0, true>::new_runtime_mod(0, 0, 2);
+        let first_pass: Vec<_> = (&mut counter).take(4).collect();
+        counter.reset();
+        let second_pass: Vec<_> = counter.take(4).collect();
+        assert_eq!(first_pass, second_pass);
+        assert_eq!(first_pass, vec![(0, 1), (1, 0), (1, 1), (2, 0)]);
+    }
+
+    #[test]
+    /// Test that a step of 2 modulo 3 visits the positions
+    /// `2, 4, 6, 8, ...`, expressed as `(div, remainder)` couples.
+    fn div_mod_counter_step() {
+        let expected = vec![(0, 2), (1, 1), (2, 0), (2, 2), (3, 1), (4, 0)];
+
+        let counter = DivModCounter::<3>::new_const_mod_step(0, 0, 2);
+        assert_eq!(counter.take(6).collect::<Vec<_>>(), expected);
+
+        let counter = DivModCounter::<0, true>::new_runtime_mod_step(0, 0, 3, 2);
+        assert_eq!(counter.take(6).collect::<Vec<_>>(), expected);
+
+        // A step larger than the modulo is allowed as well.
+        let counter = DivModCounter::<3>::new_const_mod_step(0, 1, 4);
+        assert_eq!(
+            counter.take(3).collect::<Vec<_>>(),
+            vec![(1, 2), (3, 0), (4, 1)]
+        );
+    }
+
+    #[test]
+    /// Test that `cell_positions` agrees with the hand-written
+    /// mapping to columns and rotations, for both kinds of counters.
+    fn div_mod_counter_cell_positions() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let columns = [(); 3].map(|_| meta.advice_column());
+
+        let by_hand = |(div, rem): (usize, usize)| (columns[rem], Rotation(div as i32));
+
+        let const_counter = DivModCounter::<3>::new_const_mod(1, 0);
+        assert!(const_counter
+            .cell_positions(&columns)
+            .zip(const_counter.map(by_hand))
+            .take(10)
+            .all(|(left, right)| left == right));
+
+        let runtime_counter = DivModCounter::<0, true>::new_runtime_mod(0, 2, columns.len());
+        assert!(runtime_counter
+            .cell_positions(&columns)
+            .zip(runtime_counter.map(by_hand))
+            .take(10)
+            .all(|(left, right)| left == right));
+    }
+
+    #[test]
+    /// Test that linear indices round trip through `from_linear_index`
+    /// and `linear_index`, and that they agree with the sequence
+    /// of positions visited by the counter.
+    fn div_mod_counter_linear_index() {
+        assert_eq!(DivModCounter::<3>::linear_index(4, 2), 14);
+        assert_eq!(DivModCounter::<3>::from_linear_index(14), (4, 2));
+
+        for index in 0..100 {
+            let (div, remainder) = DivModCounter::<1>::from_linear_index(index);
+            assert_eq!(DivModCounter::<1>::linear_index(div, remainder), index);
+            let (div, remainder) = DivModCounter::<4>::from_linear_index(index);
+            assert_eq!(DivModCounter::<4>::linear_index(div, remainder), index);
+            let (div, remainder) = DivModCounter::<9>::from_linear_index(index);
+            assert_eq!(DivModCounter::<9>::linear_index(div, remainder), index);
+        }
+
+        for modulo in [1, 2, 5, 9] {
+            let counter = DivModCounter::<0, true>::new_runtime_mod(0, 0, modulo);
+            for index in 0..100 {
+                let (div, remainder) = counter.from_linear_index(index);
+                assert!(remainder < modulo);
+                assert_eq!(counter.linear_index(div, remainder), index);
+            }
+
+            // The counter yields its first position after one increment.
+            assert!(counter
+                .take(100)
+                .enumerate()
+                .all(|(idx, (div, remainder))| counter.linear_index(div, remainder) == idx + 1));
+        }
+    }
+}
@@ -1,14 +1,21 @@
-#[derive(Clone, Copy, Debug, Default)]
-#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
 pub struct FieldCounter<F: ff::Field> {
     state: F,
+    /// How much the counter advances by at every call of `next`.
+    step: F,
+}
+
+impl<F: ff::Field> Default for FieldCounter<F> {
+    fn default() -> Self {
+        Self::start_counting_from(F::ZERO)
+    }
 }
 
 impl<F: ff::Field> Iterator for FieldCounter<F> {
     type Item = F;
     fn next(&mut self) -> Option<Self::Item> {
         let r = self.state;
-        self.state += F::ONE;
+        self.state += self.step;
         Some(r)
     }
 }
@@ -19,8 +26,16 @@ impl<F: ff::Field> FieldCounter<F> {
     }
 
     pub fn start_counting_from(initial_state: F) -> Self {
+        Self::start_counting_from_step(initial_state, F::ONE)
+    }
+
+    /// Same as `start_counting_from`, but every call of `next`
+    /// advances the counter by `step`, so that the `k`-th item
+    /// is `initial_state + k * step`.
+    pub fn start_counting_from_step(initial_state: F, step: F) -> Self {
         Self {
             state: initial_state,
+            step,
         }
     }
 }
@@ -72,6 +72,104 @@ macro_rules! _inner_iter_apply_macro {
     }
 }
 
+/// Same as `iter_apply_macro`, but the given macro has to expand to an
+/// expression, and the results of all the calls are collected into
+/// nested arrays, one level of nesting per list of expressions.
+/// The outermost level corresponds to the first list.
+///
+/// Since the results are collected into arrays,
+/// all the calls must evaluate to the same type.
+///
+/// ## Call Syntax
+///
+/// `$macro_name: path ; $([ $($seq: expr),+ ])+`
+///
+/// ## Example:
+///
+/// The following code
+///
+/// ```ignore
+/// iter_apply_collect!(
+///     format ;
+///     ["{} {}!"]
+///     ["hello", "greetings"]
+///     ["world", "mom"]
+/// )
+/// ```
+///
+/// expands to
+///
+/// ``` ignore
+/// [
+///     [
+///         [
+///             format!("{} {}!", "hello", "world"),
+///             format!("{} {}!", "hello", "mom"),
+///         ],
+///         [
+///             format!("{} {}!", "greetings", "world"),
+///             format!("{} {}!", "greetings", "mom"),
+///         ],
+///     ],
+/// ]
+/// ```
+#[macro_export]
+macro_rules! iter_apply_collect {
+    ($macro_name: path; $([$($seq: expr),+])+) => {
+        $crate::_inner_iter_apply_collect!($macro_name; {} {$([$($seq,)+])+})
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _inner_iter_apply_collect {
+    // The parameters and the remaining lists are passed around as single
+    // token trees, so that they can be repeated once per item of `$seq1`.
+    (
+        @level $macro_name: path;
+        $params: tt;
+        $other_seqs: tt;
+        [$($seq1: expr,)+]
+    ) => {
+        [$($crate::_inner_iter_apply_collect!(@push $macro_name; $params; $seq1; $other_seqs)),+]
+    };
+    (
+        @push $macro_name: path;
+        {$($params_list: expr,)*};
+        $new_param: expr;
+        {$($other_seq: tt)*}
+    ) => {
+        $crate::_inner_iter_apply_collect!(
+            $macro_name;
+            {$($params_list,)* $new_param,}
+            {$($other_seq)*}
+        )
+    };
+    (
+        $macro_name: path;
+        {$($params_list: expr,)*}
+        {}
+    ) => {
+        // A path followed by `!` is only parsed as a macro call
+        // in statement position, hence the block.
+        {
+            $macro_name ! ($($params_list),*)
+        }
+    };
+    (
+        $macro_name: path;
+        $params: tt
+        {$seq1: tt $($other_seq: tt)*}
+    ) => {
+        $crate::_inner_iter_apply_collect!(
+            @level $macro_name;
+            $params;
+            {$($other_seq)*};
+            $seq1
+        )
+    };
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -106,4 +204,34 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn iter_apply_collect_test() {
+        macro_rules! mul {
+            ($a: expr, $b: expr) => {
+                $a * $b
+            };
+        }
+
+        let products = iter_apply_collect!(
+            mul;
+            [1, 2, 3]
+            [10_u32, 100]
+        );
+        assert_eq!(products, [[10, 100], [20, 200], [30, 300]]);
+
+        let greetings = iter_apply_collect!(
+            format;
+            ["{} {}!"]
+            ["hello", "greetings"]
+            ["world", "mom"]
+        );
+        assert_eq!(
+            greetings,
+            [[
+                ["hello world!", "hello mom!"],
+                ["greetings world!", "greetings mom!"]
+            ]]
+        );
+    }
 }
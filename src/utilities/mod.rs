@@ -1,7 +1,8 @@
 /// This module defines a utility struct to iterate over a sequence of
 /// `(div: usize, mod: usize)` values such that, given a non-zero `MOD: usize`
 /// value, it holds that for each new item the quantity `div * MOD + mod`
-/// increments by one, and such that, for every item, `0 <= mod < MOD`.
+/// increments by a fixed step, one by default, and such that,
+/// for every item, `0 <= mod < MOD`.
 mod div_mod_counter;
 pub use div_mod_counter::DivModCounter;
 
@@ -10,6 +11,27 @@ pub use div_mod_counter::DivModCounter;
 mod region_sequence_assignment;
 pub use region_sequence_assignment::RegionSequenceAssignment;
 
+/// This module defines the error returned by the chips
+/// when their parameters do not allow to configure them.
+mod config_error;
+pub use config_error::ConfigError;
+
+/// This module defines a utility struct that assigns values to the
+/// advice cells of a region, one after the other, in the order
+/// given by a `DivModCounter`.
+mod region_painter;
+pub use region_painter::RegionPainter;
+
+/// This module defines an iterator over the columns, the rows,
+/// and the regions of a sudoku grid.
+mod sudoku_line_iter;
+pub use sudoku_line_iter::sudoku_line_iter;
+
+/// This module defines a function that turns a grid whose value
+/// may be unknown into a grid of cells whose values may be unknown.
+mod transpose_value_grid;
+pub use transpose_value_grid::transpose_value_grid;
+
 /// This module implements an iterator `FieldCounter`
 /// that, given a type `F: ff::Field`,
 /// iterates over the multiples of `F::ONE`,
@@ -23,15 +45,49 @@ pub use region_sequence_assignment::RegionSequenceAssignment;
 mod field_counter;
 pub use field_counter::FieldCounter;
 
+/// This module defines the constraints shared by the gates that multiply
+/// many factors together, a batch of factors in every advice cell.
+mod running_product;
+pub(crate) use running_product::batched_running_product;
+
+/// This module defines the gates of the accumulators that are updated
+/// with one item at every row, such as the sums committing to a sequence.
+mod running_accumulator;
+pub(crate) use running_accumulator::running_accumulator_gates;
+
+/// This module defines how the chips prefix the annotations
+/// of the regions they lay out.
+mod prefixed_name;
+pub(crate) use prefixed_name::prefixed_name;
+
 mod permutations_iter;
-pub use permutations_iter::{inverse_permutation, PermutationsIter};
+pub use permutations_iter::{
+    compose_permutations, inverse_permutation, is_involution, is_permutation, permutation_sign,
+    unrank_permutation, PermutationsIter,
+};
 
 mod iter_apply_macro;
 
 mod time_it_macro;
 
+/// This module implements transcripts based on the Keccak256 hash function,
+/// mirroring the Blake2b based ones provided by halo2.
+mod keccak_transcript;
+pub use keccak_transcript::{Keccak256Read, Keccak256Write};
+
+/// This module implements the serialization of the columns and selectors
+/// that make up the circuit configurations, in terms of their indices.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_layout;
+
 /// Simple auxiliary structs to be used in circuit tests.
 /// Those are not optimized for use in actual scenarios,
 /// but for ease of use in minimal test cases.
 mod proving_utilities;
-pub use proving_utilities::{ProverWrapper, VerifierWrapper};
+pub use proving_utilities::{
+    as_instance_slices, assert_rejected, assert_satisfied, assigned_cells, circuit_stats,
+    downsize_parameters, mock_prove_auto_k, read_proof, recommended_k, used_regions, used_rows,
+    vec_as_instance_slices, write_proof, CircuitStats, DownsizeError, InstanceShapeError,
+    OwnedProverWrapper, PallasProverWrapper, PallasVerifierWrapper, ParamsMemo, ProofBundle,
+    ProverWrapper, VerifierWrapper,
+};
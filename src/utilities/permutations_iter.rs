@@ -1,6 +1,10 @@
 use try_collect::ForceCollect;
 
 /// A struct that iterates over all the permutations of a given length.
+///
+/// There is exactly one permutation of zero objects, the empty one,
+/// so `PermutationsIter::<0>` yields `[]` once,
+/// just like `PermutationsIter::<1>` yields `[0]` once.
 pub struct PermutationsIter<const N_OBJECTS: usize>;
 
 impl<const N_OBJECTS: usize> IntoIterator for PermutationsIter<N_OBJECTS> {
@@ -11,15 +15,54 @@ impl<const N_OBJECTS: usize> IntoIterator for PermutationsIter<N_OBJECTS> {
     }
 }
 
+impl<const N_OBJECTS: usize> PermutationsIter<N_OBJECTS> {
+    /// Iterates over the permutations with lexicographic rank in
+    /// `[start_rank, end_rank)`, in the same order as `PermutationsIter`,
+    /// so that the permutations can be split in disjoint chunks,
+    /// e.g. to enumerate them in parallel.
+    ///
+    /// Ranks past the last permutation are ignored.
+    pub fn range(start_rank: usize, end_rank: usize) -> core::iter::Take<KnuthL<N_OBJECTS>> {
+        KnuthL(unrank_permutation(start_rank)).take(end_rank.saturating_sub(start_rank))
+    }
+}
+
+/// Returns the permutation with the given rank,
+/// in the lexicographic order in which `PermutationsIter` yields them,
+/// or `None` if `rank` is not smaller than `N_OBJECTS!`.
+///
+/// The rank is decoded as a Lehmer code, i.e. in the factorial number system:
+/// its `i`-th digit is the index of the `i`-th item of the permutation
+/// among the items that are still unused.
+pub fn unrank_permutation<const N_OBJECTS: usize>(mut rank: usize) -> Option<[usize; N_OBJECTS]> {
+    // If `N_OBJECTS!` does not fit in a `usize`, every rank is valid.
+    let factorial = |n: usize| (1..=n).try_fold(1_usize, |product, k| product.checked_mul(k));
+    if factorial(N_OBJECTS).is_some_and(|count| rank >= count) {
+        return None;
+    }
+
+    let mut unused = Vec::from_iter(0..N_OBJECTS);
+    let mut permutation = [0; N_OBJECTS];
+    for (idx, item) in permutation.iter_mut().enumerate() {
+        let digit = match factorial(N_OBJECTS - 1 - idx) {
+            Some(weight) => {
+                let digit = rank / weight;
+                rank %= weight;
+                digit
+            }
+            // The weight is bigger than any `usize`, so the digit is zero.
+            None => 0,
+        };
+        *item = unused.remove(digit);
+    }
+    Some(permutation)
+}
+
 /// A struct that iterates over all the permutations of a given length.
 pub struct KnuthL<const N_OBJECTS: usize>(Option<[usize; N_OBJECTS]>);
 
 impl<const N_OBJECTS: usize> Default for KnuthL<N_OBJECTS> {
     fn default() -> Self {
-        if N_OBJECTS == 0 {
-            return Self(None);
-        }
-
         Self(Some(
             (0..N_OBJECTS).f_collect("the number of items is correct"),
         ))
@@ -34,6 +77,13 @@ impl<const N_OBJECTS: usize> Iterator for KnuthL<N_OBJECTS> {
         // Copy the current state, as return value.
         let current = self.0;
 
+        // With less than two objects, the only permutation is the identity.
+        // We bail out here, since `N_OBJECTS - 2` below would underflow.
+        if N_OBJECTS < 2 {
+            self.0 = None;
+            return current;
+        }
+
         let array = self
             .0
             .as_mut()
@@ -63,6 +113,23 @@ impl<const N_OBJECTS: usize> Iterator for KnuthL<N_OBJECTS> {
     }
 }
 
+/// Checks whether `values` are a permutation of `0..N_OBJECTS`, i.e. whether
+/// they are exactly `N_OBJECTS` values, each one of `0..N_OBJECTS` appearing once.
+/// Values out of `0..N_OBJECTS`, and sequences of the wrong length,
+/// make the check fail, rather than panic.
+pub fn is_permutation<const N_OBJECTS: usize>(values: impl IntoIterator<Item = usize>) -> bool {
+    let mut seen = [false; N_OBJECTS];
+    let mut count = 0;
+    for value in values {
+        match seen.get_mut(value) {
+            Some(seen) if !*seen => *seen = true,
+            _ => return false,
+        }
+        count += 1;
+    }
+    count == N_OBJECTS
+}
+
 /// Given a permutation, outputs its inverse.
 /// It is up to the caller to guarantee that the input
 /// to this function is an actual permutation.
@@ -75,3 +142,177 @@ pub fn inverse_permutation<const N_OBJECTS: usize>(
     }
     output
 }
+
+/// Given two permutations `p` and `q`, outputs the permutation `r = q ∘ p`,
+/// i.e. such that `r[i] = q[p[i]]`.
+///
+/// With the convention of the permutation chip, which moves the `i`-th item
+/// in the position `p[i]`, applying `r` is the same as applying `p` first,
+/// and then `q`.
+/// It is up to the caller to guarantee that the inputs
+/// to this function are actual permutations.
+pub fn compose_permutations<const N_OBJECTS: usize>(
+    p: [usize; N_OBJECTS],
+    q: [usize; N_OBJECTS],
+) -> [usize; N_OBJECTS] {
+    p.map(|idx| q[idx])
+}
+
+/// Given a permutation `p`, checks whether it is its own inverse,
+/// i.e. whether `p[p[i]] = i` for every `i`.
+/// It is up to the caller to guarantee that the input
+/// to this function is an actual permutation.
+pub fn is_involution<const N_OBJECTS: usize>(permutation: [usize; N_OBJECTS]) -> bool {
+    compose_permutations(permutation, permutation) == core::array::from_fn(|idx| idx)
+}
+
+/// Given a permutation, outputs its sign, i.e. `1` if the permutation
+/// is even, and `-1` if it is odd.
+/// It is up to the caller to guarantee that the input
+/// to this function is an actual permutation.
+pub fn permutation_sign<const N_OBJECTS: usize>(permutation: [usize; N_OBJECTS]) -> i8 {
+    // The parity of a permutation is the parity of its inversions.
+    let inversions = (0..N_OBJECTS)
+        .flat_map(|i| (i + 1..N_OBJECTS).map(move |j| (i, j)))
+        .filter(|&(i, j)| permutation[i] > permutation[j])
+        .count();
+    if inversions % 2 == 0 {
+        1
+    } else {
+        -1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compose_permutations_examples() {
+        let identity = [0, 1, 2, 3];
+        let p = [1, 2, 0, 3];
+        let q = [3, 2, 1, 0];
+        let t = [0, 3, 1, 2];
+
+        assert_eq!(compose_permutations(p, q), [2, 1, 3, 0]);
+        assert_eq!(compose_permutations(p, identity), p);
+        assert_eq!(compose_permutations(identity, p), p);
+        assert_eq!(compose_permutations(p, inverse_permutation(p)), identity);
+
+        for (a, b, c) in [(p, q, t), (q, t, p), (t, p, q)] {
+            assert_eq!(
+                compose_permutations(compose_permutations(a, b), c),
+                compose_permutations(a, compose_permutations(b, c))
+            );
+        }
+    }
+
+    #[test]
+    fn is_permutation_examples() {
+        assert!(is_permutation::<4>([0, 1, 2, 3]));
+        assert!(is_permutation::<4>([2, 0, 3, 1]));
+        assert!(is_permutation::<0>([]));
+        assert!(PermutationsIter::<5>.into_iter().all(is_permutation::<5>));
+
+        // A duplicate.
+        assert!(!is_permutation::<4>([0, 1, 1, 3]));
+        // A value out of range.
+        assert!(!is_permutation::<4>([0, 1, 4, 3]));
+        assert!(!is_permutation::<4>([0, 1, usize::MAX, 3]));
+        // The wrong length.
+        assert!(!is_permutation::<4>([0, 1, 2]));
+        assert!(!is_permutation::<4>(0..5));
+        assert!(!is_permutation::<0>([0]));
+    }
+
+    #[test]
+    fn is_involution_examples() {
+        assert!(is_involution([0, 1, 2, 3]));
+        assert!(is_involution([1, 0, 2, 3]));
+        assert!(is_involution([3, 2, 1, 0]));
+        assert!(!is_involution([1, 2, 0, 3]));
+        assert!(is_involution::<0>([]));
+
+        // The involutions of 4 objects are the identity,
+        // the 6 transpositions, and the 3 double transpositions.
+        assert_eq!(
+            PermutationsIter::<4>
+                .into_iter()
+                .filter(|&p| is_involution(p))
+                .count(),
+            10
+        );
+    }
+
+    #[test]
+    fn permutation_sign_examples() {
+        assert_eq!(permutation_sign([0, 1, 2, 3]), 1);
+        assert_eq!(permutation_sign([1, 0, 2, 3]), -1);
+        assert_eq!(permutation_sign([1, 2, 0, 3]), 1);
+        assert_eq!(permutation_sign([3, 2, 1, 0]), 1);
+        assert_eq!(permutation_sign([1, 2, 3, 0]), -1);
+
+        // Half of the permutations are even.
+        assert_eq!(
+            PermutationsIter::<5>
+                .into_iter()
+                .filter(|&p| permutation_sign(p) == 1)
+                .count(),
+            60
+        );
+    }
+
+    #[test]
+    fn permutations_iter_edge_cases() {
+        assert_eq!(
+            PermutationsIter::<0>.into_iter().collect::<Vec<_>>(),
+            vec![[0usize; 0]]
+        );
+        assert_eq!(
+            PermutationsIter::<1>.into_iter().collect::<Vec<_>>(),
+            vec![[0]]
+        );
+        assert_eq!(
+            PermutationsIter::<2>.into_iter().collect::<Vec<_>>(),
+            vec![[0, 1], [1, 0]]
+        );
+    }
+
+    #[test]
+    fn unrank_permutation_examples() {
+        for (rank, permutation) in PermutationsIter::<4>.into_iter().enumerate() {
+            assert_eq!(unrank_permutation::<4>(rank), Some(permutation));
+        }
+        assert_eq!(unrank_permutation::<4>(24), None);
+        assert_eq!(unrank_permutation::<0>(0), Some([]));
+        assert_eq!(unrank_permutation::<0>(1), None);
+        assert_eq!(
+            unrank_permutation::<30>(0),
+            Some(core::array::from_fn(|idx| idx))
+        );
+    }
+
+    #[test]
+    fn permutations_iter_range() {
+        const FACTORIAL: usize = 120;
+        let all = Vec::from_iter(PermutationsIter::<5>);
+
+        for k in [0, 1, 17, 60, FACTORIAL] {
+            let chunked = Vec::from_iter(
+                PermutationsIter::<5>::range(0, k)
+                    .chain(PermutationsIter::<5>::range(k, FACTORIAL)),
+            );
+            assert_eq!(chunked, all);
+        }
+
+        assert_eq!(PermutationsIter::<5>::range(7, 7).count(), 0);
+        assert_eq!(PermutationsIter::<5>::range(9, 3).count(), 0);
+        assert_eq!(PermutationsIter::<5>::range(100, 1000).count(), 20);
+        assert_eq!(PermutationsIter::<5>::range(FACTORIAL, 1000).count(), 0);
+    }
+
+    #[test]
+    fn permutations_iter_count() {
+        assert_eq!(PermutationsIter::<5>.into_iter().count(), 120);
+    }
+}
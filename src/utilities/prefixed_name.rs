@@ -0,0 +1,23 @@
+/// The annotation of a region laid out by a chip,
+/// i.e. `name` prefixed by the chip's `name_prefix`, if any.
+pub(crate) fn prefixed_name(name_prefix: &str, name: &str) -> String {
+    if name_prefix.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{name_prefix}: {name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixed_name_with_and_without_prefix() {
+        assert_eq!(prefixed_name("", "load input"), "load input");
+        assert_eq!(
+            prefixed_name("first chip", "load input"),
+            "first chip: load input"
+        );
+    }
+}
@@ -1,23 +1,558 @@
-use std::marker::PhantomData;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::File,
+    io::{self, Read, Write},
+    marker::PhantomData,
+    path::Path,
+};
 
+use ff::{Field, FromUniformBytes, PrimeField};
+use group::{prime::PrimeCurveAffine, GroupEncoding};
 use halo2_proofs::{
-    pasta::{EqAffine, Fp},
-    plonk::{Circuit, Error, ProvingKey, SingleVerifier, VerifyingKey},
+    arithmetic::{best_fft, CurveAffine},
+    circuit::Value,
+    dev::{MockProver, VerifyFailure},
+    pasta::{EpAffine, EqAffine, Fp},
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, ProvingKey, Selector, SingleVerifier, VerifyingKey,
+    },
     poly::commitment::Params,
-    transcript::{Blake2bRead, Blake2bWrite},
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255, TranscriptRead, TranscriptWrite},
 };
+use rand::{CryptoRng, RngCore};
+
+use super::{Keccak256Read, Keccak256Write};
+
+/// Given a circuit, computes the smallest value for the `K` parameter
+/// of the prover, i.e. the base 2 logarithm of the number of rows available
+/// in the circuit, that is sufficient to prove the circuit.
+///
+/// The rows used by the circuit are found by laying out `circuit_wiring`.
+/// On top of them, halo2 reserves the blinding rows, see
+/// `ProverWrapper::blinding_rows`, and one more row, that closes
+/// the permutation argument.
+///
+/// The return value is the tuple `(k, minimum_rows)`, where `minimum_rows`
+/// is the minimum number of rows required by the circuit constraint system.
+///
+/// The layout only depends on the values that do not depend on the witness,
+/// so the witness values of `circuit_wiring` are never read.
+/// Returns an error if `circuit_wiring` cannot be laid out, see `circuit_stats`.
+pub fn recommended_k<F: ff::Field, C: Circuit<F>>(
+    circuit_wiring: &C,
+) -> Result<(u32, usize), Error> {
+    let (cs, recorder) = record_layout(circuit_wiring)?;
+    let minimum_rows = cs.minimum_rows();
+    let required_rows = minimum_rows.max(recorder.used_rows + cs.blinding_factors() + 1);
+    Ok((required_rows.next_power_of_two().ilog2(), minimum_rows))
+}
+
+/// Given a circuit, computes the number of rows its layout uses,
+/// i.e. one more than the largest row that is assigned, selected,
+/// or involved in a copy constraint, in any column.
+///
+/// Unlike `recommended_k`, the blinding rows are not counted, so that
+/// two layouts can be compared row by row.
+/// The witness values of `circuit_wiring` are never read.
+/// Returns an error if `circuit_wiring` cannot be laid out, see `circuit_stats`.
+pub fn used_rows<F: ff::Field, C: Circuit<F>>(circuit_wiring: &C) -> Result<usize, Error> {
+    Ok(record_layout(circuit_wiring)?.1.used_rows)
+}
+
+/// Given a circuit, computes the number of regions its layout assigns.
+///
+/// The witness values of `circuit_wiring` are never read.
+/// Returns an error if `circuit_wiring` cannot be laid out, see `circuit_stats`.
+pub fn used_regions<F: ff::Field, C: Circuit<F>>(circuit_wiring: &C) -> Result<usize, Error> {
+    Ok(record_layout(circuit_wiring)?.1.regions)
+}
+
+/// Given a circuit, computes the number of distinct advice and fixed cells
+/// its layout assigns, e.g. to check the cell counts the chips report.
+///
+/// The witness values of `circuit_wiring` are never read.
+/// Returns an error if `circuit_wiring` cannot be laid out, see `circuit_stats`.
+pub fn assigned_cells<F: ff::Field, C: Circuit<F>>(circuit_wiring: &C) -> Result<usize, Error> {
+    Ok(record_layout(circuit_wiring)?.1.assigned_cells.len())
+}
+
+/// Lays out `circuit_wiring` with a `LayoutRecorder`,
+/// and returns it together with the constraint system of the circuit.
+fn record_layout<F: ff::Field, C: Circuit<F>>(
+    circuit_wiring: &C,
+) -> Result<(ConstraintSystem<F>, LayoutRecorder), Error> {
+    let mut cs = ConstraintSystem::default();
+    let config = C::configure(&mut cs);
+    let shape = ConstraintSystemShape::of(&cs).ok_or(Error::Synthesis)?;
+
+    let mut recorder = LayoutRecorder::new(shape.instance_columns::<F>());
+    C::FloorPlanner::synthesize(&mut recorder, circuit_wiring, config, shape.constants)?;
+    Ok((cs, recorder))
+}
+
+/// The resources used by a circuit configuration,
+/// as reported by its constraint system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub num_advice_columns: usize,
+    pub num_fixed_columns: usize,
+    pub num_instance_columns: usize,
+    /// Selectors are not counted among the fixed columns,
+    /// even though they end up being compressed into fixed columns
+    /// during key generation.
+    pub num_selectors: usize,
+    pub minimum_rows: usize,
+    pub degree: usize,
+}
+
+/// Given a circuit, computes the resources used by its configuration,
+/// without generating keys or proofs.
+///
+/// `circuit_wiring` is only used to select the circuit type,
+/// its witness values are never read.
+///
+/// The halo2 version we depend on keeps the sizes of a constraint system
+/// private, see `ConstraintSystemShape` for how they are found out.
+/// If they cannot be found out, `Error::Synthesis` is returned,
+/// here as in the other helpers that lay out circuits.
+pub fn circuit_stats<C: Circuit<F>, F: ff::Field>(
+    _circuit_wiring: &C,
+) -> Result<CircuitStats, Error> {
+    let mut cs = ConstraintSystem::default();
+    C::configure(&mut cs);
+    let shape = ConstraintSystemShape::of(&cs).ok_or(Error::Synthesis)?;
+    Ok(CircuitStats {
+        num_advice_columns: shape.num_advice_columns,
+        num_fixed_columns: shape.num_fixed_columns,
+        num_instance_columns: shape.num_instance_columns,
+        num_selectors: shape.num_selectors,
+        minimum_rows: cs.minimum_rows(),
+        degree: cs.degree(),
+    })
+}
+
+/// The sizes of a constraint system, which the halo2 version
+/// we depend on keeps private.
+///
+/// The numbers of columns and selectors are counted by allocating one more
+/// of each kind in a copy of the constraint system, and finding its index
+/// among the ones allocated in a throwaway constraint system, as `serde_layout`
+/// does. The constant columns cannot be found out that way, so they are read
+/// off the `Debug` representation of `ConstraintSystem::pinned`, which is also
+/// the one halo2 identifies verifying keys by.
+#[derive(Debug)]
+pub(crate) struct ConstraintSystemShape {
+    pub(crate) num_fixed_columns: usize,
+    pub(crate) num_advice_columns: usize,
+    pub(crate) num_instance_columns: usize,
+    pub(crate) num_selectors: usize,
+    /// The fixed columns holding the constants of the circuit.
+    pub(crate) constants: Vec<Column<Fixed>>,
+}
+
+impl ConstraintSystemShape {
+    /// Returns `None` if the constant columns cannot be read off the pinned
+    /// constraint system, e.g. because its `Debug` representation changed.
+    pub(crate) fn of<F: ff::Field>(cs: &ConstraintSystem<F>) -> Option<Self> {
+        Some(Self {
+            num_fixed_columns: allocated_count(cs, |meta| meta.fixed_column()),
+            num_advice_columns: allocated_count(cs, |meta| meta.advice_column()),
+            num_instance_columns: allocated_count(cs, |meta| meta.instance_column()),
+            // Simple and complex selectors share their indices.
+            num_selectors: allocated_count(cs, |meta| meta.selector()),
+            constants: constant_columns::<F>(&format!("{:?}", cs.pinned()))?,
+        })
+    }
+
+    /// The instance columns of the constraint system, in allocation order.
+    pub(crate) fn instance_columns<F: ff::Field>(&self) -> Vec<Column<Instance>> {
+        let mut cs = ConstraintSystem::<F>::default();
+        (0..self.num_instance_columns)
+            .map(|_| cs.instance_column())
+            .collect()
+    }
+}
+
+/// Counts the items of one kind that are allocated in `cs`, i.e. finds
+/// the index the next one gets, since halo2 does not expose it.
+fn allocated_count<F: ff::Field, T: PartialEq>(
+    cs: &ConstraintSystem<F>,
+    allocate: impl Fn(&mut ConstraintSystem<F>) -> T,
+) -> usize {
+    let next = allocate(&mut cs.clone());
+    let mut throwaway = ConstraintSystem::<F>::default();
+    std::iter::repeat_with(|| allocate(&mut throwaway))
+        .position(|item| item == next)
+        .expect("the items are allocated with consecutive indices")
+}
 
-pub struct ProverWrapper<'i, C: Circuit<Fp>> {
-    public_parameters: Params<EqAffine>,
+/// Rebuilds the constant columns listed in the `Debug` representation
+/// of a pinned constraint system.
+///
+/// `Column::index` is private, so the columns are rebuilt by allocating
+/// fixed columns in a throwaway constraint system up to each listed index.
+///
+/// The format parsed here is the one of the halo2 revision
+/// 7fd2ce259ec3d0b0e3ede3fa20e4cdcfc395efc9, pinned in `Cargo.toml`.
+/// A different one makes this return `None`, rather than wrong columns,
+/// see the test `constraint_system_shape_counts_sizes`.
+fn constant_columns<F: ff::Field>(pinned: &str) -> Option<Vec<Column<Fixed>>> {
+    // `constants` is the next to last field, after the user-named lookups.
+    let listed = &pinned[pinned.rfind("constants: [")?..];
+    let listed = &listed[..listed.find(']')?];
+    let indices = listed
+        .split("index: ")
+        .skip(1)
+        .map(leading_number)
+        .collect::<Option<Vec<usize>>>()?;
+
+    let mut cs = ConstraintSystem::<F>::default();
+    let columns: Vec<Column<Fixed>> = (0..indices.iter().map(|index| index + 1).max().unwrap_or(0))
+        .map(|_| cs.fixed_column())
+        .collect();
+    Some(indices.into_iter().map(|index| columns[index]).collect())
+}
+
+/// Parses the number `text` starts with, if any.
+fn leading_number(text: &str) -> Option<usize> {
+    text.chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+/// Runs the `MockProver` on `circuit`, and panics if the circuit is not
+/// satisfied by `instances`. The panic message lists the verification failures.
+#[track_caller]
+pub fn assert_satisfied<C: Circuit<Fp>>(k: u32, circuit: &C, instances: Vec<Vec<Fp>>) {
+    let prover = MockProver::run(k, circuit, instances)
+        .unwrap_or_else(|err| panic!("mock proof generation failed: {err:?}"));
+    if let Err(failures) = prover.verify() {
+        panic!(
+            "the circuit is not satisfied, {} verification failures:\n{}",
+            failures.len(),
+            failures
+                .iter()
+                .map(|failure| format!("- {failure}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
+
+/// Runs the `MockProver` on `circuit`, and panics if the circuit
+/// is satisfied by `instances`.
+///
+/// If the mock proof generation fails, e.g. because the circuit
+/// does not fit in `2^k` rows, the circuit is not considered rejected,
+/// so that a misconfigured test does not pass silently.
+#[track_caller]
+pub fn assert_rejected<C: Circuit<Fp>>(k: u32, circuit: &C, instances: Vec<Vec<Fp>>) {
+    let prover = MockProver::run(k, circuit, instances)
+        .unwrap_or_else(|err| panic!("mock proof generation failed: {err:?}"));
+    assert!(
+        prover.verify().is_err(),
+        "the circuit is satisfied, but it was expected to be rejected"
+    );
+}
+
+/// Runs the `MockProver` on `circuit`, with the `K` computed by
+/// `recommended_k`, and returns the verification failures, if any,
+/// so that tests do not need to pick `K` by hand.
+///
+/// Panics if the circuit cannot be laid out, or if the mock proof generation fails.
+#[track_caller]
+pub fn mock_prove_auto_k<C: Circuit<Fp>>(
+    circuit: &C,
+    instances: Vec<Vec<Fp>>,
+) -> Result<(), Vec<VerifyFailure>> {
+    let (k, _) = recommended_k(circuit)
+        .unwrap_or_else(|err| panic!("the circuit cannot be laid out: {err:?}"));
+    MockProver::run(k, circuit, instances)
+        .unwrap_or_else(|err| panic!("mock proof generation with k = {k} failed: {err:?}"))
+        .verify()
+}
+
+/// Borrows the columns of `grid` as the instance slices the prover
+/// and the verifier take, i.e. the `col_idx`-th slice is `grid[col_idx]`.
+pub fn as_instance_slices<F, const R: usize, const C: usize>(grid: &[[F; R]; C]) -> [&[F]; C] {
+    core::array::from_fn(|col_idx| grid[col_idx].as_slice())
+}
+
+/// Same as `as_instance_slices`, for instance columns
+/// stored as vectors, whose lengths may differ.
+pub fn vec_as_instance_slices<F>(columns: &[Vec<F>]) -> Vec<&[F]> {
+    Vec::from_iter(columns.iter().map(Vec::as_slice))
+}
+
+/// Writes `transcript` to `writer`, prefixed by its length as a little endian
+/// `u32`, so that several proofs can be stored one after the other in the
+/// same stream, and read back one at a time with `read_proof`.
+pub fn write_proof<W: Write>(writer: &mut W, transcript: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(transcript.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the proof is too long to be framed with a u32 length",
+        )
+    })?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(transcript)
+}
+
+/// Reads from `reader` a proof written by `write_proof`.
+///
+/// The length is not trusted to preallocate memory, so that a malformed
+/// input can not make the reader allocate more than it actually contains.
+pub fn read_proof<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    let mut transcript = vec![];
+    reader
+        .by_ref()
+        .take(len as u64)
+        .read_to_end(&mut transcript)?;
+    if transcript.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(transcript)
+}
+
+/// The error returned by `ProverWrapper::try_add_item` and
+/// `VerifierWrapper::check_instance_shape` when an instance
+/// does not have the shape the circuit expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceShapeError {
+    /// The instance does not have as many columns as the circuit configuration.
+    ColumnCount {
+        expected_columns: usize,
+        found_columns: usize,
+    },
+    /// The `column`-th instance column has fewer rows
+    /// than the ones the circuit constrains.
+    ColumnLength {
+        column: usize,
+        expected_rows: usize,
+        found_rows: usize,
+    },
+    /// The circuit could not be synthesized to find out its instance shape.
+    Synthesis,
+}
+
+impl std::fmt::Display for InstanceShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ColumnCount {
+                expected_columns,
+                found_columns,
+            } => write!(
+                f,
+                "the circuit expects {expected_columns} instance columns, \
+                but the instance has {found_columns}"
+            ),
+            Self::ColumnLength {
+                column,
+                expected_rows,
+                found_rows,
+            } => write!(
+                f,
+                "the circuit expects at least {expected_rows} rows in the instance \
+                column {column}, but the instance has {found_rows}"
+            ),
+            Self::Synthesis => write!(
+                f,
+                "the circuit could not be synthesized to find out its instance shape"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InstanceShapeError {}
+
+/// Derives the public parameters for `new_k` out of `public_parameters`,
+/// which can be generated for any `K` that is at least `new_k`.
+///
+/// The generators of `Params::new(new_k)` are the first `2^new_k` generators
+/// of the parameters for any larger `K`, so they are not hashed to the curve
+/// again: only their Lagrange basis is computed, with an inverse FFT over
+/// the smaller domain, as `Params::new` does. The derived parameters are
+/// the same that `Params::new(new_k)` generates.
+///
+/// Only halo2 can build `Params`, so this relies on the layout `Params::write`
+/// uses in the halo2 revision pinned in `Cargo.toml`, which is private:
+/// `k` as four little endian bytes, then the `2^k` points of `g`, the `2^k`
+/// points of `g_lagrange`, and the points `w` and `u`. If the written
+/// parameters do not match that layout, e.g. after a halo2 update,
+/// they are generated from scratch with `Params::new(new_k)` instead.
+///
+/// Returns `None` if `new_k` is larger than the `K` of `public_parameters`.
+pub fn downsize_parameters<Curve: CurveAffine>(
+    public_parameters: &Params<Curve>,
+    new_k: u32,
+) -> Option<Params<Curve>> {
+    let g = public_parameters.get_g();
+    if new_k > g.len().ilog2() {
+        return None;
+    }
+    let g = &g[..1 << new_k];
+
+    let mut omega_inv = <Curve::Scalar as PrimeField>::ROOT_OF_UNITY_INV;
+    for _ in new_k..<Curve::Scalar as PrimeField>::S {
+        omega_inv = omega_inv.square();
+    }
+    let mut g_lagrange = Vec::from_iter(g.iter().map(PrimeCurveAffine::to_curve));
+    best_fft(&mut g_lagrange, omega_inv, new_k);
+    let n_inv = Curve::Scalar::from(g.len() as u64)
+        .invert()
+        .expect("the domain size is not a multiple of the field characteristic");
+    for point in g_lagrange.iter_mut() {
+        *point *= n_inv;
+    }
+    let mut g_lagrange_affine = vec![Curve::default(); g.len()];
+    group::Curve::batch_normalize(g_lagrange.as_slice(), g_lagrange_affine.as_mut_slice());
+
+    // The derived parameters are laid out as `Params::write` does, and read
+    // back. The last two points it writes are the generators `w` and `u`,
+    // which do not depend on `K`.
+    let mut serialized = vec![];
+    public_parameters
+        .write(&mut serialized)
+        .expect("writing to a vector should not fail");
+    let point_len = <Curve as GroupEncoding>::Repr::default().as_ref().len();
+    let current_k = parameters_k(public_parameters);
+    let expected_len = 4 + (2 * (1 << current_k) + 2) * point_len;
+    if serialized.len() != expected_len || serialized[..4] != current_k.to_le_bytes() {
+        return Some(Params::new(new_k));
+    }
+    let w_and_u = &serialized[serialized.len() - 2 * point_len..];
+
+    let mut downsized = Vec::from(new_k.to_le_bytes());
+    for point in g.iter().chain(g_lagrange_affine.iter()) {
+        downsized.extend_from_slice(point.to_bytes().as_ref());
+    }
+    downsized.extend_from_slice(w_and_u);
+    Some(Params::read(&mut downsized.as_slice()).unwrap_or_else(|_| Params::new(new_k)))
+}
+
+/// The `K` value `public_parameters` were generated for.
+fn parameters_k<Curve: CurveAffine>(public_parameters: &Params<Curve>) -> u32 {
+    public_parameters.get_g().len().ilog2()
+}
+
+/// The error returned by `ProverWrapper::downsize`.
+#[derive(Debug)]
+pub enum DownsizeError {
+    /// The public parameters can only shrink,
+    /// but `new_k` is larger than their `current_k`.
+    LargerK { current_k: u32, new_k: u32 },
+    /// The keys could not be generated for the smaller domain,
+    /// e.g. because the circuit does not fit in it.
+    KeyGeneration(Error),
+}
+
+impl std::fmt::Display for DownsizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LargerK { current_k, new_k } => write!(
+                f,
+                "the public parameters for k = {current_k} can not be downsized to k = {new_k}"
+            ),
+            Self::KeyGeneration(err) => write!(
+                f,
+                "the keys could not be generated for the smaller domain: {err:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DownsizeError {}
+
+/// A memo of public parameters, indexed by their `K` value.
+///
+/// Generating public parameters is expensive, so this struct
+/// generates them only the first time they are requested for a given `K`,
+/// and then hands out copies of the stored ones.
+///
+/// The parameters for a `K` are derived with `downsize_parameters` out of
+/// the stored ones for the smallest larger `K`, if there are any, so that
+/// a single large setup, e.g. the one passed to `from_parameters`,
+/// is shared by the circuits of every smaller size.
+/// Only the parameters for a `K` larger than all the stored ones
+/// are generated from scratch.
+pub struct ParamsMemo<Curve: CurveAffine = EqAffine> {
+    parameters: BTreeMap<u32, Params<Curve>>,
+}
+
+impl<Curve: CurveAffine> ParamsMemo<Curve> {
+    pub fn new() -> Self {
+        Self {
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    /// Builds a memo that stores `public_parameters`,
+    /// out of which the parameters for every smaller `K` are derived.
+    pub fn from_parameters(public_parameters: Params<Curve>) -> Self {
+        Self {
+            parameters: BTreeMap::from([(parameters_k(&public_parameters), public_parameters)]),
+        }
+    }
+
+    /// Returns the public parameters for `k`,
+    /// deriving or generating them if they are not in the memo yet.
+    pub fn parameters(&mut self, k: u32) -> &Params<Curve> {
+        if !self.parameters.contains_key(&k) {
+            let public_parameters = match self.parameters.range(k..).next() {
+                Some((_, larger)) => downsize_parameters(larger, k)
+                    .expect("the stored parameters are for a larger K"),
+                None => Params::new(k),
+            };
+            self.parameters.insert(k, public_parameters);
+        }
+        &self.parameters[&k]
+    }
+
+    /// The `K` values whose parameters are stored, in increasing order.
+    pub fn memoized_ks(&self) -> impl Iterator<Item = u32> + '_ {
+        self.parameters.keys().copied()
+    }
+}
+
+impl<Curve: CurveAffine> Default for ParamsMemo<Curve> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The curve used by the wrappers is `Curve`, and the circuits are defined
+/// over its scalar field. By default, the Vesta curve `EqAffine` is used,
+/// so that the circuits are defined over `Fp`.
+///
+/// The halo2 version we depend on cannot serialize proving and verifying keys,
+/// so they are generated again every time a wrapper is initialized.
+/// The public parameters can be stored with `write_parameters_to`,
+/// and loaded with `read_parameters_and_initialize_prover`.
+pub struct ProverWrapper<'i, C: Circuit<Curve::Scalar>, Curve: CurveAffine = EqAffine> {
+    public_parameters: Params<Curve>,
     /// The prover does not use this value, but it is necessary to provide
     /// a `VerifierWrapper: From<ProverWrapper>` implementation
-    verifying_key: VerifyingKey<EqAffine>,
-    proving_key: ProvingKey<EqAffine>,
+    verifying_key: VerifyingKey<Curve>,
+    proving_key: ProvingKey<Curve>,
     circuits: Vec<C>,
-    instances: Vec<&'i [&'i [Fp]]>,
+    instances: Vec<&'i [&'i [Curve::Scalar]]>,
 }
 
+/// A `ProverWrapper` over the Pallas curve, for circuits defined over `Fq`.
+pub type PallasProverWrapper<'i, C> = ProverWrapper<'i, C, EpAffine>;
+
 impl<'i, C: Circuit<Fp>> ProverWrapper<'i, C> {
+    /// Generates the public parameters for the default curve, and then
+    /// initializes the prover.
+    ///
+    /// To use another curve, generate its public parameters and
+    /// call `initialize_prover` instead.
     pub fn initialize_parameters_and_prover(
         max_nr_rows_pow_2_exponent: u32,
         circuit_wiring: C,
@@ -25,9 +560,53 @@ impl<'i, C: Circuit<Fp>> ProverWrapper<'i, C> {
         let public_parameters = Params::new(max_nr_rows_pow_2_exponent);
         Self::initialize_prover(public_parameters, circuit_wiring)
     }
+}
+
+impl<'i, C: Circuit<Curve::Scalar>, Curve: CurveAffine> ProverWrapper<'i, C, Curve>
+where
+    Curve::Scalar: FromUniformBytes<64>,
+{
+    /// The number of rows, at the end of the `2^K` rows of the circuit,
+    /// that halo2 fills with random values to blind the advice columns,
+    /// and that the circuit cannot use. The row right before them
+    /// is reserved as well, see `recommended_k`.
+    ///
+    /// The number grows with the number of times a gate queries
+    /// the same advice column, and does not depend on `K`.
+    /// `circuit_wiring` is only used to select the circuit type,
+    /// its witness values are never read.
+    pub fn blinding_rows(_circuit_wiring: &C) -> usize {
+        let mut cs = ConstraintSystem::default();
+        C::configure(&mut cs);
+        cs.blinding_factors()
+    }
+
+    /// The smallest `K` that is large enough to prove every one of `wirings`,
+    /// i.e. the largest `K` that `recommended_k` computes for them.
+    ///
+    /// The circuits of a type may be laid out in a different number of rows,
+    /// depending on their wiring, e.g. on the edges of a graph, so the `K`
+    /// of the public parameters, which are fixed when the prover is initialized,
+    /// has to be chosen once all of them are known. The parameters can then
+    /// be shared, e.g. through a `ParamsMemo`, by the provers of every wiring.
+    /// If `wirings` is empty, only the constraint system is taken into account.
+    /// Returns an error if any of `wirings` cannot be laid out.
+    ///
+    /// Sizing the parameters late, after the items are added, is out of scope:
+    /// the keys depend on the wiring and on `K`, so the `K` has to be known
+    /// before the prover is initialized. A prover with too many rows
+    /// can still be shrunk with `downsize`.
+    pub fn recommended_k_for_batch(wirings: &[C]) -> Result<u32, Error> {
+        let mut cs = ConstraintSystem::default();
+        C::configure(&mut cs);
+        wirings.iter().try_fold(
+            cs.minimum_rows().next_power_of_two().ilog2(),
+            |k, circuit_wiring| Ok(k.max(recommended_k(circuit_wiring)?.0)),
+        )
+    }
 
     pub fn initialize_prover(
-        public_parameters: Params<EqAffine>,
+        public_parameters: Params<Curve>,
         circuit_wiring: C,
     ) -> Result<Self, Error> {
         let verifying_key = halo2_proofs::plonk::keygen_vk(&public_parameters, &circuit_wiring)?;
@@ -45,47 +624,257 @@ impl<'i, C: Circuit<Fp>> ProverWrapper<'i, C> {
         })
     }
 
-    pub fn add_item(&mut self, circuit: C, instance: &'i [&'i [Fp]]) {
+    /// Initializes the prover with the public parameters for `k`
+    /// stored in `memo`, generating them only if needed.
+    pub fn initialize_prover_from_memo(
+        memo: &mut ParamsMemo<Curve>,
+        k: u32,
+        circuit_wiring: C,
+    ) -> Result<Self, Error> {
+        Self::initialize_prover(memo.parameters(k).clone(), circuit_wiring)
+    }
+
+    /// Reads the public parameters from `reader`, as written by
+    /// `write_parameters_to`, and then initializes the prover.
+    ///
+    /// The halo2 version we depend on does not provide a way to serialize
+    /// proving and verifying keys, so those are generated again from
+    /// the parameters and `circuit_wiring`. What is saved is the cost
+    /// of generating the public parameters.
+    pub fn read_parameters_and_initialize_prover<R: Read>(
+        reader: &mut R,
+        circuit_wiring: C,
+    ) -> Result<Self, Error> {
+        // halo2's `Error` only wraps IO errors in its `Transcript` variant.
+        let public_parameters = Params::read(reader).map_err(Error::Transcript)?;
+        Self::initialize_prover(public_parameters, circuit_wiring)
+    }
+
+    /// Writes the public parameters to `writer`, so that they can be
+    /// loaded again with `read_parameters_and_initialize_prover`,
+    /// or with `VerifierWrapper::read_parameters_and_initialize_verifier`.
+    pub fn write_parameters_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.public_parameters.write(writer)
+    }
+
+    /// Replaces the public parameters with the ones for `new_k`, derived
+    /// out of the current ones with `downsize_parameters`, and generates
+    /// the keys again for the smaller domain out of `circuit_wiring`,
+    /// as `initialize_prover` does. The added items are kept.
+    ///
+    /// Proofs generated after downsizing verify against `Params::new(new_k)`.
+    /// If `new_k` is larger than the current `K`, or if the key generation
+    /// fails, the prover is left unchanged.
+    pub fn downsize(&mut self, new_k: u32, circuit_wiring: &C) -> Result<(), DownsizeError> {
+        let public_parameters =
+            downsize_parameters(&self.public_parameters, new_k).ok_or(DownsizeError::LargerK {
+                current_k: parameters_k(&self.public_parameters),
+                new_k,
+            })?;
+        let verifying_key = halo2_proofs::plonk::keygen_vk(&public_parameters, circuit_wiring)
+            .map_err(DownsizeError::KeyGeneration)?;
+        let proving_key = halo2_proofs::plonk::keygen_pk(
+            &public_parameters,
+            verifying_key.clone(),
+            circuit_wiring,
+        )
+        .map_err(DownsizeError::KeyGeneration)?;
+
+        self.public_parameters = public_parameters;
+        self.verifying_key = verifying_key;
+        self.proving_key = proving_key;
+        Ok(())
+    }
+
+    pub fn add_item(&mut self, circuit: C, instance: &'i [&'i [Curve::Scalar]]) {
         self.circuits.push(circuit);
         self.instances.push(instance);
     }
 
+    /// Same as `add_item`, but first checks that `instance` has the shape
+    /// `circuit` expects, see `check_instance_shape`.
+    /// Otherwise, the mismatch would only surface when generating the proof,
+    /// or, for the column lengths, when verifying it.
+    pub fn try_add_item(
+        &mut self,
+        circuit: C,
+        instance: &'i [&'i [Curve::Scalar]],
+    ) -> Result<(), InstanceShapeError> {
+        check_instance_shape(&circuit, instance)?;
+        self.add_item(circuit, instance);
+        Ok(())
+    }
+
     pub fn clear(&mut self) {
         self.circuits.clear();
         self.instances.clear();
     }
 
     pub fn prove(&self) -> Result<Vec<u8>, Error> {
+        self.prove_with_rng(rand::rngs::OsRng)
+    }
+
+    /// Same as `prove`, but the randomness used to generate the proof
+    /// is drawn from `rng`.
+    ///
+    /// With a seeded `rng`, proof generation is reproducible.
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(&self, rng: R) -> Result<Vec<u8>, Error> {
         let mut transcript = Blake2bWrite::init(vec![]);
+        self.create_proof(rng, &mut transcript)?;
+        Ok(transcript.finalize())
+    }
+
+    /// Same as `prove`, but the transcript is streamed into `writer`
+    /// while the proof is generated, rather than buffered in memory.
+    /// This is useful for large proofs, e.g. when many items are added.
+    ///
+    /// Once the proof is complete, `writer` is returned. The bytes written
+    /// to it are a transcript that can be verified with `VerifierWrapper::verify`,
+    /// or with `VerifierWrapper::verify_from_reader`.
+    pub fn prove_to_writer<W: Write>(&self, writer: W) -> Result<W, Error> {
+        let mut transcript = Blake2bWrite::init(writer);
+        self.create_proof(rand::rngs::OsRng, &mut transcript)?;
+        Ok(transcript.finalize())
+    }
+
+    /// Same as `prove`, but the proof transcript is built with
+    /// the Keccak256 hash function instead of Blake2b.
+    /// Such proofs have to be verified with `VerifierWrapper::verify_keccak`.
+    pub fn prove_keccak(&self) -> Result<Vec<u8>, Error> {
+        let mut transcript = Keccak256Write::init(vec![]);
+        self.create_proof(rand::rngs::OsRng, &mut transcript)?;
+        Ok(transcript.finalize())
+    }
 
+    /// Same as `prove`, but the items are proved in chunks of `chunk_size`
+    /// items each, and after every chunk `progress(done, total)` is called,
+    /// where `done` is the number of items proved so far,
+    /// and `total` is the number of added items.
+    ///
+    /// The proofs of the chunks are written one after the other on the
+    /// same transcript, so the transcript layout depends on `chunk_size`,
+    /// and the proof has to be verified with `VerifierWrapper::verify_in_chunks`,
+    /// with the same `chunk_size`.
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn prove_in_chunks(
+        &self,
+        chunk_size: usize,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<u8>, Error> {
+        let total = self.circuits.len();
+        let mut done = 0;
+        let mut transcript = Blake2bWrite::init(vec![]);
+        for (circuits, instances) in self
+            .circuits
+            .chunks(chunk_size)
+            .zip(self.instances.chunks(chunk_size))
+        {
+            halo2_proofs::plonk::create_proof(
+                &self.public_parameters,
+                &self.proving_key,
+                circuits,
+                instances,
+                rand::rngs::OsRng,
+                &mut transcript,
+            )?;
+            done += circuits.len();
+            progress(done, total);
+        }
+        Ok(transcript.finalize())
+    }
+
+    /// Proves every added item on its own, rather than all of them
+    /// in a single proof, and returns one transcript per item,
+    /// in the order in which the items were added.
+    ///
+    /// The randomness used to prove the `i`-th item is drawn from `rng_for_item(i)`.
+    pub fn prove_each_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng_for_item: impl Fn(usize) -> R,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        (0..self.circuits.len())
+            .map(|idx| self.prove_item(idx, rng_for_item(idx)))
+            .collect()
+    }
+
+    /// Same as `prove_each_with_rng`, but the items are proved in parallel,
+    /// each with its own transcript writer.
+    ///
+    /// With the same `rng_for_item`, the transcripts are the same
+    /// as the ones returned by `prove_each_with_rng`.
+    #[cfg(feature = "rayon")]
+    pub fn prove_each_parallel_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng_for_item: impl Fn(usize) -> R + Sync,
+    ) -> Result<Vec<Vec<u8>>, Error>
+    where
+        C: Sync,
+    {
+        use rayon::prelude::*;
+
+        (0..self.circuits.len())
+            .into_par_iter()
+            .map(|idx| self.prove_item(idx, rng_for_item(idx)))
+            .collect()
+    }
+
+    /// Same as `prove_each_parallel_with_rng`,
+    /// with randomness drawn from the OS.
+    #[cfg(feature = "rayon")]
+    pub fn prove_each_parallel(&self) -> Result<Vec<Vec<u8>>, Error>
+    where
+        C: Sync,
+    {
+        self.prove_each_parallel_with_rng(|_| rand::rngs::OsRng)
+    }
+
+    /// Proves the `idx`-th added item alone.
+    fn prove_item<R: RngCore + CryptoRng>(&self, idx: usize, rng: R) -> Result<Vec<u8>, Error> {
+        let mut transcript = Blake2bWrite::init(vec![]);
         halo2_proofs::plonk::create_proof(
             &self.public_parameters,
             &self.proving_key,
-            self.circuits.as_slice(),
-            self.instances.as_slice(),
-            rand::rngs::OsRng,
+            std::slice::from_ref(&self.circuits[idx]),
+            std::slice::from_ref(&self.instances[idx]),
+            rng,
             &mut transcript,
         )?;
-
         Ok(transcript.finalize())
     }
 
-    pub fn public_parameters(&self) -> &Params<EqAffine> {
+    /// Writes the proof of all the added items to `transcript`.
+    fn create_proof<R: RngCore + CryptoRng, T: TranscriptWrite<Curve, Challenge255<Curve>>>(
+        &self,
+        rng: R,
+        transcript: &mut T,
+    ) -> Result<(), Error> {
+        halo2_proofs::plonk::create_proof(
+            &self.public_parameters,
+            &self.proving_key,
+            self.circuits.as_slice(),
+            self.instances.as_slice(),
+            rng,
+            transcript,
+        )
+    }
+
+    pub fn public_parameters(&self) -> &Params<Curve> {
         &self.public_parameters
     }
 
-    pub fn proving_key(&self) -> &ProvingKey<EqAffine> {
+    pub fn proving_key(&self) -> &ProvingKey<Curve> {
         &self.proving_key
     }
 
-    pub fn inner_parts(self) -> (Params<EqAffine>, ProvingKey<EqAffine>) {
+    pub fn inner_parts(self) -> (Params<Curve>, ProvingKey<Curve>) {
         (self.public_parameters, self.proving_key)
     }
 
     pub fn from_inner_parts(
-        public_parameters: Params<EqAffine>,
-        verifying_key: VerifyingKey<EqAffine>,
-        proving_key: ProvingKey<EqAffine>,
+        public_parameters: Params<Curve>,
+        verifying_key: VerifyingKey<Curve>,
+        proving_key: ProvingKey<Curve>,
     ) -> Self {
         Self {
             public_parameters,
@@ -97,15 +886,127 @@ impl<'i, C: Circuit<Fp>> ProverWrapper<'i, C> {
     }
 }
 
-pub struct VerifierWrapper<C: Circuit<Fp>> {
-    public_parameters: Params<EqAffine>,
-    verifying_key: VerifyingKey<EqAffine>,
+/// Same as `ProverWrapper`, but the instances of the added items are owned
+/// by the wrapper, rather than borrowed, so that callers do not have to
+/// keep the instance slices alive for as long as the prover.
+///
+/// The slices halo2 requires are built out of the owned instances
+/// every time a proof is generated.
+pub struct OwnedProverWrapper<C: Circuit<Curve::Scalar>, Curve: CurveAffine = EqAffine> {
+    /// Only holds the public parameters and the keys, the items are added
+    /// to `circuits` and `instances`, so its instance lifetime is never used.
+    prover: ProverWrapper<'static, C, Curve>,
+    circuits: Vec<C>,
+    /// One list of instance columns per added item.
+    instances: Vec<Vec<Vec<Curve::Scalar>>>,
+}
+
+impl<C: Circuit<Fp>> OwnedProverWrapper<C> {
+    /// Same as `ProverWrapper::initialize_parameters_and_prover`.
+    pub fn initialize_parameters_and_prover(
+        max_nr_rows_pow_2_exponent: u32,
+        circuit_wiring: C,
+    ) -> Result<Self, Error> {
+        ProverWrapper::initialize_parameters_and_prover(max_nr_rows_pow_2_exponent, circuit_wiring)
+            .map(Self::from_prover)
+    }
+}
+
+impl<C: Circuit<Curve::Scalar>, Curve: CurveAffine> OwnedProverWrapper<C, Curve>
+where
+    Curve::Scalar: FromUniformBytes<64>,
+{
+    /// Same as `ProverWrapper::initialize_prover`.
+    pub fn initialize_prover(
+        public_parameters: Params<Curve>,
+        circuit_wiring: C,
+    ) -> Result<Self, Error> {
+        ProverWrapper::initialize_prover(public_parameters, circuit_wiring).map(Self::from_prover)
+    }
+
+    fn from_prover(prover: ProverWrapper<'static, C, Curve>) -> Self {
+        Self {
+            prover,
+            circuits: vec![],
+            instances: vec![],
+        }
+    }
+
+    pub fn add_item(&mut self, circuit: C, instance: Vec<Vec<Curve::Scalar>>) {
+        self.circuits.push(circuit);
+        self.instances.push(instance);
+    }
+
+    /// Same as `ProverWrapper::try_add_item`.
+    pub fn try_add_item(
+        &mut self,
+        circuit: C,
+        instance: Vec<Vec<Curve::Scalar>>,
+    ) -> Result<(), InstanceShapeError> {
+        check_instance_shape(&circuit, &vec_as_instance_slices(&instance))?;
+        self.add_item(circuit, instance);
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.circuits.clear();
+        self.instances.clear();
+    }
+
+    /// The instances of the added items, in the order in which they were added.
+    pub fn instances(&self) -> &[Vec<Vec<Curve::Scalar>>] {
+        &self.instances
+    }
+
+    pub fn prove(&self) -> Result<Vec<u8>, Error> {
+        self.prove_with_rng(rand::rngs::OsRng)
+    }
+
+    /// Same as `ProverWrapper::prove_with_rng`.
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(&self, rng: R) -> Result<Vec<u8>, Error> {
+        let instance_columns = Vec::from_iter(
+            self.instances
+                .iter()
+                .map(|instance| vec_as_instance_slices(instance)),
+        );
+        let instances = Vec::from_iter(instance_columns.iter().map(Vec::as_slice));
+
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        halo2_proofs::plonk::create_proof(
+            &self.prover.public_parameters,
+            &self.prover.proving_key,
+            self.circuits.as_slice(),
+            instances.as_slice(),
+            rng,
+            &mut transcript,
+        )?;
+        Ok(transcript.finalize())
+    }
+
+    pub fn public_parameters(&self) -> &Params<Curve> {
+        self.prover.public_parameters()
+    }
+
+    pub fn proving_key(&self) -> &ProvingKey<Curve> {
+        self.prover.proving_key()
+    }
+}
+
+pub struct VerifierWrapper<C: Circuit<Curve::Scalar>, Curve: CurveAffine = EqAffine> {
+    public_parameters: Params<Curve>,
+    verifying_key: VerifyingKey<Curve>,
     _phantom: PhantomData<C>,
 }
 
-impl<C: Circuit<Fp>> VerifierWrapper<C> {
+/// A `VerifierWrapper` over the Pallas curve, for circuits defined over `Fq`.
+pub type PallasVerifierWrapper<C> = VerifierWrapper<C, EpAffine>;
+
+impl<C: Circuit<Curve::Scalar>, Curve: CurveAffine> VerifierWrapper<C, Curve>
+where
+    Curve::Scalar: FromUniformBytes<64>,
+{
     pub fn initialize_verifier(
-        public_parameters: Params<EqAffine>,
+        public_parameters: Params<Curve>,
         circuit_wiring: C,
     ) -> Result<Self, Error> {
         let verifying_key = halo2_proofs::plonk::keygen_vk(&public_parameters, &circuit_wiring)?;
@@ -116,28 +1017,204 @@ impl<C: Circuit<Fp>> VerifierWrapper<C> {
         })
     }
 
-    pub fn verify<'i, I: IntoIterator<Item = &'i [&'i [Fp]]>>(
+    /// Reads the public parameters from `reader`, as written by
+    /// `ProverWrapper::write_parameters_to`, and then initializes the verifier.
+    /// The verifying key is generated again from `circuit_wiring`.
+    pub fn read_parameters_and_initialize_verifier<R: Read>(
+        reader: &mut R,
+        circuit_wiring: C,
+    ) -> Result<Self, Error> {
+        let public_parameters = Params::read(reader).map_err(Error::Transcript)?;
+        Self::initialize_verifier(public_parameters, circuit_wiring)
+    }
+
+    /// Reports, for each instance column of the circuit, how many of its rows
+    /// are constrained by the circuit, i.e. one more than the largest row
+    /// constrained to be equal to some cell.
+    ///
+    /// The rows are found by synthesizing `circuit_wiring`, so only the
+    /// constraints that do not depend on the witness values are taken into account.
+    pub fn expected_instance_shape(&self, circuit_wiring: &C) -> Result<Vec<usize>, Error> {
+        instance_shape(circuit_wiring)
+    }
+
+    /// Checks that `instance` has the shape `circuit_wiring` expects,
+    /// i.e. one column for each instance column of the circuit, and, for
+    /// each column, at least as many rows as `expected_instance_shape` reports.
+    /// Columns may have different lengths, each one is checked on its own.
+    ///
+    /// The rows are found out of the equality constraints of the circuit,
+    /// so the instance cells that gates query directly are not accounted for.
+    /// This is why the reported rows are only a lower bound, and longer
+    /// columns are accepted.
+    pub fn check_instance_shape(
+        &self,
+        circuit_wiring: &C,
+        instance: &[&[Curve::Scalar]],
+    ) -> Result<(), InstanceShapeError> {
+        check_instance_shape(circuit_wiring, instance)
+    }
+
+    /// Checks that the verifying key of the verifier is the one generated
+    /// out of `circuit_wiring` and the public parameters of the verifier,
+    /// e.g. to detect a key that was set up, or cached, for another circuit
+    /// configuration, before it makes every verification fail.
+    ///
+    /// The halo2 version we depend on cannot serialize verifying keys, so
+    /// they are compared through their pinned representation, which covers
+    /// the constraint system and the commitments to the fixed columns and
+    /// to the permutation argument.
+    /// If no key can be generated out of `circuit_wiring`, returns `false`.
+    pub fn check_vk_matches(&self, circuit_wiring: &C) -> bool {
+        match halo2_proofs::plonk::keygen_vk(&self.public_parameters, circuit_wiring) {
+            Ok(verifying_key) => {
+                pinned_representation(&verifying_key) == self.pinned_verifying_key()
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// The pinned representation of the verifying key of the verifier,
+    /// which `check_vk_matches` compares keys through.
+    pub fn pinned_verifying_key(&self) -> String {
+        pinned_representation(&self.verifying_key)
+    }
+
+    pub fn verify<'i, I: IntoIterator<Item = &'i [&'i [Curve::Scalar]]>>(
         &mut self,
         instances: I,
         transcript: &[u8],
+    ) -> bool {
+        self.verify_detailed(instances, transcript).is_ok()
+    }
+
+    /// Same as `verify`, but if the verification fails, the error
+    /// returned by halo2 is forwarded to the caller.
+    /// This allows to tell a malformed input apart from an invalid proof.
+    pub fn verify_detailed<'i, I: IntoIterator<Item = &'i [&'i [Curve::Scalar]]>>(
+        &mut self,
+        instances: I,
+        transcript: &[u8],
+    ) -> Result<(), Error> {
+        self.verify_transcript(instances, &mut Blake2bRead::init(transcript))
+    }
+
+    /// Same as `verify`, for proofs generated with `ProverWrapper::prove_in_chunks`.
+    /// `chunk_size` has to be the same that was used to generate the proof.
+    ///
+    /// The proof is rejected if any bytes of `transcript` are left over
+    /// after the last chunk.
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn verify_in_chunks<'i, I: IntoIterator<Item = &'i [&'i [Curve::Scalar]]>>(
+        &mut self,
+        instances: I,
+        chunk_size: usize,
+        transcript: &[u8],
     ) -> bool {
         let instances = Vec::from_iter(instances);
+        let mut remaining = transcript;
+        let verified = {
+            let mut reader = Blake2bRead::init(&mut remaining);
+            instances.chunks(chunk_size).all(|chunk| {
+                self.verify_transcript(chunk.iter().copied(), &mut reader)
+                    .is_ok()
+            })
+        };
+        verified && remaining.is_empty()
+    }
+
+    /// Same as `verify`, for proofs generated with `ProverWrapper::prove_keccak`.
+    pub fn verify_keccak<'i, I: IntoIterator<Item = &'i [&'i [Curve::Scalar]]>>(
+        &mut self,
+        instances: I,
+        transcript: &[u8],
+    ) -> bool {
+        self.verify_transcript(instances, &mut Keccak256Read::init(transcript))
+            .is_ok()
+    }
+
+    /// Same as `verify`, but the transcript is read from `reader`.
+    pub fn verify_from_reader<'i, I: IntoIterator<Item = &'i [&'i [Curve::Scalar]]>, R: Read>(
+        &mut self,
+        instances: I,
+        reader: R,
+    ) -> bool {
+        self.verify_transcript(instances, &mut Blake2bRead::init(reader))
+            .is_ok()
+    }
+
+    /// Same as `verify`, but the transcript is read from the file at `path`.
+    /// If the file cannot be opened, the verification fails.
+    pub fn verify_from_path<'i, I: IntoIterator<Item = &'i [&'i [Curve::Scalar]]>>(
+        &mut self,
+        instances: I,
+        path: impl AsRef<Path>,
+    ) -> bool {
+        match File::open(path) {
+            Ok(file) => self.verify_from_reader(instances, io::BufReader::new(file)),
+            Err(_) => false,
+        }
+    }
+
+    /// Verifies the proof contained in `transcript`.
+    fn verify_transcript<
+        'i,
+        I: IntoIterator<Item = &'i [&'i [Curve::Scalar]]>,
+        T: TranscriptRead<Curve, Challenge255<Curve>>,
+    >(
+        &mut self,
+        instances: I,
+        transcript: &mut T,
+    ) -> Result<(), Error> {
+        let instances = Vec::from_iter(instances);
 
-        let mut transcript = Blake2bRead::init(transcript);
         let strategy = SingleVerifier::new(&self.public_parameters);
         halo2_proofs::plonk::verify_proof(
             &self.public_parameters,
             &self.verifying_key,
             strategy,
             instances.as_slice(),
-            &mut transcript,
+            transcript,
         )
-        .is_ok()
     }
 
+    /// Verifies many independent proofs at once.
+    /// Each item consists of the instances of a proof, laid out as in `verify`,
+    /// and of the proof transcript.
+    ///
+    /// The checks of all the proofs are accumulated, and performed together,
+    /// which is cheaper than verifying each proof separately.
+    /// If any of the proofs is invalid, the whole batch is rejected.
+    #[cfg(feature = "batch")]
+    pub fn verify_batch<'i, 't, I, J>(&mut self, items: I) -> bool
+    where
+        I: IntoIterator<Item = (J, &'t [u8])>,
+        J: IntoIterator<Item = &'i [&'i [Curve::Scalar]]>,
+    {
+        let mut batch = halo2_proofs::plonk::BatchVerifier::new();
+        for (instances, transcript) in items {
+            let instances = instances
+                .into_iter()
+                .map(|instance| instance.iter().map(|column| column.to_vec()).collect())
+                .collect();
+            batch.add_proof(instances, transcript.to_vec());
+        }
+        batch.finalize(&self.public_parameters, &self.verifying_key)
+    }
+
+    /// Builds a verifier out of its public parameters and verifying key,
+    /// without generating anything.
+    /// This is the way to go for verifiers that do not have access
+    /// to the circuit, nor to a `ProverWrapper`.
+    ///
+    /// The halo2 version we depend on cannot serialize verifying keys,
+    /// so there is no way to build a verifier out of bytes alone:
+    /// the verifying key has to be handed over in memory, or generated
+    /// again out of the circuit with `initialize_verifier`.
     pub fn from_inner_parts(
-        public_parameters: Params<EqAffine>,
-        verifying_key: VerifyingKey<EqAffine>,
+        public_parameters: Params<Curve>,
+        verifying_key: VerifyingKey<Curve>,
     ) -> Self {
         Self {
             public_parameters,
@@ -147,8 +1224,782 @@ impl<C: Circuit<Fp>> VerifierWrapper<C> {
     }
 }
 
-impl<'i, C: Circuit<Fp>> From<ProverWrapper<'i, C>> for VerifierWrapper<C> {
-    fn from(value: ProverWrapper<'i, C>) -> Self {
+impl<'i, C: Circuit<Curve::Scalar>, Curve: CurveAffine> From<ProverWrapper<'i, C, Curve>>
+    for VerifierWrapper<C, Curve>
+where
+    Curve::Scalar: FromUniformBytes<64>,
+{
+    fn from(value: ProverWrapper<'i, C, Curve>) -> Self {
         Self::from_inner_parts(value.public_parameters, value.verifying_key)
     }
 }
+
+impl<C: Circuit<Curve::Scalar>, Curve: CurveAffine> From<OwnedProverWrapper<C, Curve>>
+    for VerifierWrapper<C, Curve>
+where
+    Curve::Scalar: FromUniformBytes<64>,
+{
+    fn from(value: OwnedProverWrapper<C, Curve>) -> Self {
+        Self::from(value.prover)
+    }
+}
+
+/// The `Debug` representation of the pinned verifying key,
+/// which identifies the circuit configuration and the public parameters
+/// the key was generated for.
+fn pinned_representation<Curve: CurveAffine>(verifying_key: &VerifyingKey<Curve>) -> String {
+    format!("{:?}", verifying_key.pinned())
+}
+
+/// A self-contained verifiable artifact, made of the `K` of the public
+/// parameters, the pinned representation of the verifying key,
+/// the public instances, and the transcript of a proof.
+///
+/// The public parameters are not part of the bundle: a proof checked against
+/// parameters chosen by the author of the bundle would prove nothing, so
+/// the verifier uses trusted parameters, e.g. generated with `Params::new(k)`.
+/// The halo2 version we depend on cannot serialize verifying keys,
+/// so the bundle pins the key through its pinned representation,
+/// which the key the verifier generates out of the circuit has to match.
+#[derive(Debug, Clone)]
+pub struct ProofBundle<Curve: CurveAffine = EqAffine> {
+    k: u32,
+    /// See `VerifierWrapper::pinned_verifying_key`.
+    pinned_verifying_key: String,
+    /// The instances of the proved items, laid out as in `VerifierWrapper::verify`,
+    /// i.e. one list of instance columns per item.
+    instances: Vec<Vec<Vec<Curve::Scalar>>>,
+    transcript: Vec<u8>,
+}
+
+impl<Curve: CurveAffine> ProofBundle<Curve>
+where
+    Curve::Scalar: FromUniformBytes<64>,
+{
+    pub fn new(
+        k: u32,
+        pinned_verifying_key: String,
+        instances: Vec<Vec<Vec<Curve::Scalar>>>,
+        transcript: Vec<u8>,
+    ) -> Self {
+        Self {
+            k,
+            pinned_verifying_key,
+            instances,
+            transcript,
+        }
+    }
+
+    /// Bundles `transcript`, a proof generated by `prover`, with the `K`
+    /// of the public parameters of `prover`, its verifying key,
+    /// and the instances of its added items.
+    pub fn from_prover<C: Circuit<Curve::Scalar>>(
+        prover: &ProverWrapper<C, Curve>,
+        transcript: Vec<u8>,
+    ) -> Self {
+        let instances = prover
+            .instances
+            .iter()
+            .map(|instance| instance.iter().map(|column| column.to_vec()).collect())
+            .collect();
+        Self::new(
+            parameters_k(&prover.public_parameters),
+            pinned_representation(&prover.verifying_key),
+            instances,
+            transcript,
+        )
+    }
+
+    /// Writes the bundle to `writer`: first `K`, as a little endian `u32`,
+    /// then the pinned verifying key, as written by `write_proof`,
+    /// then the instances, each length prefixed by a little endian `u32`,
+    /// and finally the transcript, as written by `write_proof`.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let write_len = |writer: &mut W, len: usize| {
+            let len = u32::try_from(len).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the bundle is too large to be framed with u32 lengths",
+                )
+            })?;
+            writer.write_all(&len.to_le_bytes())
+        };
+
+        writer.write_all(&self.k.to_le_bytes())?;
+        write_proof(writer, self.pinned_verifying_key.as_bytes())?;
+
+        write_len(writer, self.instances.len())?;
+        for instance in self.instances.iter() {
+            write_len(writer, instance.len())?;
+            for column in instance.iter() {
+                write_len(writer, column.len())?;
+                for value in column.iter() {
+                    writer.write_all(value.to_repr().as_ref())?;
+                }
+            }
+        }
+
+        write_proof(writer, &self.transcript)
+    }
+
+    /// Reads from `reader` a bundle written by `write`.
+    ///
+    /// Verifying the bundle requires the public parameters for its `K`,
+    /// whose size grows as `2^K`, so bundles whose `K` is larger than `max_k`
+    /// are rejected before anything else is read.
+    pub fn read<R: Read>(reader: &mut R, max_k: u32) -> io::Result<Self> {
+        let read_len = |reader: &mut R| {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            Ok::<_, io::Error>(u32::from_le_bytes(len) as usize)
+        };
+        let read_value = |reader: &mut R| {
+            let mut repr = <Curve::Scalar as PrimeField>::Repr::default();
+            reader.read_exact(repr.as_mut())?;
+            Option::from(<Curve::Scalar as PrimeField>::from_repr(repr)).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the bundle contains a non canonical field element",
+                )
+            })
+        };
+
+        let mut k = [0; 4];
+        reader.read_exact(&mut k)?;
+        let k = u32::from_le_bytes(k);
+        if k > max_k {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the bundle requires public parameters for k = {k}, larger than {max_k}"),
+            ));
+        }
+
+        let pinned_verifying_key = String::from_utf8(read_proof(reader)?).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the pinned verifying key of the bundle is not valid UTF-8",
+            )
+        })?;
+
+        // The lengths are not trusted to preallocate memory,
+        // since they are read from a possibly malformed input.
+        let mut instances = vec![];
+        for _ in 0..read_len(reader)? {
+            let mut instance = vec![];
+            for _ in 0..read_len(reader)? {
+                let mut column = vec![];
+                for _ in 0..read_len(reader)? {
+                    column.push(read_value(reader)?);
+                }
+                instance.push(column);
+            }
+            instances.push(instance);
+        }
+
+        let transcript = read_proof(reader)?;
+
+        Ok(Self::new(k, pinned_verifying_key, instances, transcript))
+    }
+
+    /// Verifies the proof contained in the bundle against its instances,
+    /// with the trusted `public_parameters`, and the verifying key generated
+    /// out of them and `C::default()`.
+    ///
+    /// The bundle is rejected if `public_parameters` are not for its `K`,
+    /// or if the generated verifying key does not match the pinned one.
+    pub fn verify<C: Circuit<Curve::Scalar> + Default>(
+        &self,
+        public_parameters: &Params<Curve>,
+    ) -> bool {
+        if parameters_k(public_parameters) != self.k {
+            return false;
+        }
+        let mut verifier = match VerifierWrapper::<C, Curve>::initialize_verifier(
+            public_parameters.clone(),
+            C::default(),
+        ) {
+            Ok(verifier) => verifier,
+            Err(_) => return false,
+        };
+        if verifier.pinned_verifying_key() != self.pinned_verifying_key {
+            return false;
+        }
+
+        let instances = Vec::from_iter(
+            self.instances
+                .iter()
+                .map(|instance| vec_as_instance_slices(instance)),
+        );
+        verifier.verify(instances.iter().map(Vec::as_slice), &self.transcript)
+    }
+
+    /// Reads a bundle from `blob`, as written by `write`, and verifies it
+    /// with the public parameters generated with `Params::new(k)`.
+    /// Malformed blobs, and blobs whose `K` is larger than `max_k`, are rejected.
+    pub fn verify_bundle<C: Circuit<Curve::Scalar> + Default>(mut blob: &[u8], max_k: u32) -> bool {
+        match Self::read(&mut blob, max_k) {
+            Ok(bundle) => blob.is_empty() && bundle.verify::<C>(&Params::new(bundle.k)),
+            Err(_) => false,
+        }
+    }
+
+    /// The `K` of the public parameters the proof was generated with.
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    pub fn pinned_verifying_key(&self) -> &str {
+        &self.pinned_verifying_key
+    }
+
+    pub fn instances(&self) -> &[Vec<Vec<Curve::Scalar>>] {
+        &self.instances
+    }
+
+    pub fn transcript(&self) -> &[u8] {
+        &self.transcript
+    }
+}
+
+/// See `VerifierWrapper::expected_instance_shape`.
+fn instance_shape<F: ff::Field, C: Circuit<F>>(circuit: &C) -> Result<Vec<usize>, Error> {
+    Ok(record_layout(circuit)?.1.instance_rows)
+}
+
+/// See `VerifierWrapper::check_instance_shape`.
+fn check_instance_shape<F: ff::Field, C: Circuit<F>>(
+    circuit: &C,
+    instance: &[&[F]],
+) -> Result<(), InstanceShapeError> {
+    let shape = instance_shape(circuit).map_err(|_| InstanceShapeError::Synthesis)?;
+    if shape.len() != instance.len() {
+        return Err(InstanceShapeError::ColumnCount {
+            expected_columns: shape.len(),
+            found_columns: instance.len(),
+        });
+    }
+    match shape
+        .into_iter()
+        .zip(instance)
+        .enumerate()
+        .find(|(_, (expected_rows, column))| column.len() < *expected_rows)
+    {
+        Some((column, (expected_rows, found))) => Err(InstanceShapeError::ColumnLength {
+            column,
+            expected_rows,
+            found_rows: found.len(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// An `Assignment` that ignores all the assigned values,
+/// and only keeps track of the rows of the instance columns
+/// involved in copy constraints, of the rows used by the circuit,
+/// of the assigned cells, and of the regions.
+struct LayoutRecorder {
+    /// The instance columns of the circuit, which `instance_rows` is indexed like.
+    instance_columns: Vec<Column<Any>>,
+    instance_rows: Vec<usize>,
+    /// One more than the largest row that is assigned,
+    /// selected, or involved in a copy constraint, in any column.
+    used_rows: usize,
+    /// The `(column, row)` positions of the assigned advice and fixed cells.
+    assigned_cells: HashSet<(Column<Any>, usize)>,
+    /// The number of regions entered by the floor planner.
+    regions: usize,
+}
+
+impl LayoutRecorder {
+    fn new(instance_columns: Vec<Column<Instance>>) -> Self {
+        Self {
+            instance_rows: vec![0; instance_columns.len()],
+            instance_columns: instance_columns.into_iter().map(Column::from).collect(),
+            used_rows: 0,
+            assigned_cells: HashSet::new(),
+            regions: 0,
+        }
+    }
+
+    fn record_row(&mut self, row: usize) {
+        self.used_rows = self.used_rows.max(row + 1);
+    }
+
+    fn record_assignment(&mut self, column: Column<Any>, row: usize) {
+        self.record_row(row);
+        self.assigned_cells.insert((column, row));
+    }
+
+    fn record(&mut self, column: Column<Any>, row: usize) {
+        self.record_row(row);
+        if let Some(index) = self.instance_columns.iter().position(|c| *c == column) {
+            let rows = &mut self.instance_rows[index];
+            *rows = (*rows).max(row + 1);
+        }
+    }
+}
+
+impl<F: ff::Field> Assignment<F> for LayoutRecorder {
+    fn enter_region<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.regions += 1;
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.record_row(row);
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<F>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.record_assignment(column.into(), row);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.record_assignment(column.into(), row);
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.record(left_column, left_row);
+        self.record(right_column, right_row);
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _gadget_name: Option<String>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{Advice, Column, Instance},
+    };
+
+    /// A circuit that exposes a witnessed value in the instance column.
+    struct PublicValueCircuit {
+        value: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for PublicValueCircuit {
+        type Config = (Column<Advice>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+            (advice, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let cell = layouter.assign_region(
+                || "value",
+                |mut region| region.assign_advice(|| "value", advice, 0, || self.value),
+            )?;
+            layouter.constrain_instance(cell.cell(), instance, 0)
+        }
+    }
+
+    const K: u32 = 4;
+
+    #[test]
+    fn assert_satisfied_accepts_valid_circuit() {
+        let circuit = PublicValueCircuit {
+            value: Value::known(Fp::from(3)),
+        };
+        assert_satisfied(K, &circuit, vec![vec![Fp::from(3)]]);
+    }
+
+    #[test]
+    fn assert_rejected_rejects_invalid_circuit() {
+        let circuit = PublicValueCircuit {
+            value: Value::known(Fp::from(3)),
+        };
+        assert_rejected(K, &circuit, vec![vec![Fp::from(4)]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the circuit is not satisfied")]
+    fn assert_satisfied_panics_on_invalid_circuit() {
+        let circuit = PublicValueCircuit {
+            value: Value::known(Fp::from(3)),
+        };
+        assert_satisfied(K, &circuit, vec![vec![Fp::from(4)]]);
+    }
+
+    #[test]
+    fn try_add_item_checks_instance_columns() {
+        let instance = [Fp::from(3)];
+        let instance = [instance.as_slice()];
+        let too_many_columns = [instance[0], instance[0]];
+
+        let mut prover = ProverWrapper::initialize_parameters_and_prover(
+            K,
+            PublicValueCircuit {
+                value: Value::unknown(),
+            },
+        )
+        .expect("prover setup should not fail");
+
+        let err = prover
+            .try_add_item(
+                PublicValueCircuit {
+                    value: Value::known(Fp::from(3)),
+                },
+                too_many_columns.as_slice(),
+            )
+            .expect_err("the instance has too many columns");
+        assert_eq!(
+            err.to_string(),
+            "the circuit expects 1 instance columns, but the instance has 2"
+        );
+
+        prover
+            .try_add_item(
+                PublicValueCircuit {
+                    value: Value::known(Fp::from(3)),
+                },
+                instance.as_slice(),
+            )
+            .expect("the instance has the right number of columns");
+        let transcript = prover.prove().expect("proof generation should not fail");
+        assert!(VerifierWrapper::from(prover).verify([instance.as_slice()], transcript.as_slice()));
+    }
+
+    /// A circuit that exposes 9 witnessed values in its first instance column,
+    /// and 3 more in the second one.
+    struct TwoColumnsCircuit {
+        values: [Value<Fp>; 12],
+    }
+
+    impl Circuit<Fp> for TwoColumnsCircuit {
+        type Config = (Column<Advice>, [Column<Instance>; 2]);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: [Value::unknown(); 12],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            let instance_columns = [(); 2].map(|_| meta.instance_column());
+            meta.enable_equality(advice);
+            for col in instance_columns {
+                meta.enable_equality(col);
+            }
+            (advice, instance_columns)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, [long_column, short_column]): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let cells = layouter.assign_region(
+                || "values",
+                |mut region| {
+                    self.values
+                        .iter()
+                        .enumerate()
+                        .map(|(row, value)| {
+                            region.assign_advice(|| "value", advice, row, || *value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+            for (row, cell) in cells.iter().enumerate() {
+                if row < 9 {
+                    layouter.constrain_instance(cell.cell(), long_column, row)?;
+                } else {
+                    layouter.constrain_instance(cell.cell(), short_column, row - 9)?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    /// Test that instance columns of different lengths are checked one by one,
+    /// and that an instance with the right shape is proved and verified.
+    fn try_add_item_checks_instance_column_lengths() {
+        let values = core::array::from_fn(|idx| Fp::from(idx as u64));
+        let circuit = || TwoColumnsCircuit {
+            values: values.map(Value::known),
+        };
+        let instance = [&values[..9], &values[9..]];
+        let short_instance = [&values[..9], &values[9..11]];
+        let swapped_instance = [&values[9..], &values[..9]];
+        // The rows past the constrained ones may be read by gates,
+        // so longer columns are accepted.
+        let padded_column = [&values[9..], &[Fp::from(0)][..]].concat();
+        let padded_instance = [&values[..9], padded_column.as_slice()];
+
+        let mut prover = ProverWrapper::initialize_parameters_and_prover(5, circuit())
+            .expect("prover setup should not fail");
+        assert_eq!(
+            prover.try_add_item(circuit(), short_instance.as_slice()),
+            Err(InstanceShapeError::ColumnLength {
+                column: 1,
+                expected_rows: 3,
+                found_rows: 2
+            })
+        );
+        assert_eq!(
+            prover.try_add_item(circuit(), swapped_instance.as_slice()),
+            Err(InstanceShapeError::ColumnLength {
+                column: 0,
+                expected_rows: 9,
+                found_rows: 3
+            })
+        );
+        assert_eq!(
+            prover.try_add_item(circuit(), &instance[..1]),
+            Err(InstanceShapeError::ColumnCount {
+                expected_columns: 2,
+                found_columns: 1
+            })
+        );
+        prover
+            .try_add_item(circuit(), instance.as_slice())
+            .expect("the instance has the right shape");
+        let transcript = prover.prove().expect("proof generation should not fail");
+
+        let mut verifier = VerifierWrapper::from(prover);
+        assert_eq!(
+            verifier
+                .expected_instance_shape(&circuit())
+                .expect("synthesis should not fail"),
+            vec![9, 3]
+        );
+        assert_eq!(
+            verifier.check_instance_shape(&circuit(), short_instance.as_slice()),
+            Err(InstanceShapeError::ColumnLength {
+                column: 1,
+                expected_rows: 3,
+                found_rows: 2
+            })
+        );
+        assert_eq!(
+            verifier.check_instance_shape(&circuit(), padded_instance.as_slice()),
+            Ok(())
+        );
+        assert!(verifier.verify([instance.as_slice()], transcript.as_slice()));
+    }
+
+    #[test]
+    fn proofs_round_trip_through_a_stream() {
+        let values = [1, 2, 3].map(Fp::from);
+        let instances = values.map(|value| [value]);
+        let instances = Vec::from_iter(instances.iter().map(|instance| [instance.as_slice()]));
+
+        let mut prover = ProverWrapper::initialize_parameters_and_prover(
+            K,
+            PublicValueCircuit {
+                value: Value::unknown(),
+            },
+        )
+        .expect("prover setup should not fail");
+
+        let mut proofs = vec![];
+        for (value, instance) in values.into_iter().zip(instances.iter()) {
+            prover.clear();
+            prover.add_item(
+                PublicValueCircuit {
+                    value: Value::known(value),
+                },
+                instance.as_slice(),
+            );
+            proofs.push(prover.prove().expect("proof generation should not fail"));
+        }
+
+        let mut stream = vec![];
+        for proof in proofs.iter() {
+            write_proof(&mut stream, proof).expect("writing to a vector should not fail");
+        }
+
+        let mut verifier = VerifierWrapper::from(prover);
+        let mut reader = stream.as_slice();
+        for (proof, instance) in proofs.iter().zip(instances.iter()) {
+            let read_back = read_proof(&mut reader).expect("the proof was written");
+            assert_eq!(&read_back, proof);
+            assert!(verifier.verify([instance.as_slice()], read_back.as_slice()));
+        }
+        assert!(reader.is_empty());
+        assert!(read_proof(&mut reader).is_err());
+    }
+
+    #[test]
+    /// Test that a proof streamed into a writer verifies, and has
+    /// the same length as the one returned by `prove`.
+    /// The bytes differ, since the randomness of the proofs differs.
+    fn prove_to_writer_matches_prove() {
+        let instance = [Fp::from(3)];
+        let instance = [instance.as_slice()];
+
+        let mut prover = ProverWrapper::initialize_parameters_and_prover(
+            K,
+            PublicValueCircuit {
+                value: Value::unknown(),
+            },
+        )
+        .expect("prover setup should not fail");
+        prover.add_item(
+            PublicValueCircuit {
+                value: Value::known(Fp::from(3)),
+            },
+            instance.as_slice(),
+        );
+
+        let proof = prover.prove().expect("proof generation should not fail");
+        let streamed_proof = prover
+            .prove_to_writer(Vec::new())
+            .expect("proof generation should not fail");
+        assert_eq!(streamed_proof.len(), proof.len());
+
+        let mut verifier = VerifierWrapper::from(prover);
+        assert!(verifier.verify([instance.as_slice()], proof.as_slice()));
+        assert!(verifier.verify([instance.as_slice()], streamed_proof.as_slice()));
+        assert!(verifier.verify_from_reader([instance.as_slice()], streamed_proof.as_slice()));
+    }
+
+    #[test]
+    /// Test that the sizes of a constraint system are counted, and pins
+    /// the `Debug` representation of the pinned constraint system,
+    /// which the constant columns are read off, so that a halo2 update
+    /// that changes it is caught here, rather than by the users
+    /// of the helpers that rely on it.
+    fn constraint_system_shape_counts_sizes() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let _ = meta.fixed_column();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+        let _ = [(); 3].map(|_| meta.advice_column());
+        let _ = meta.instance_column();
+        let _ = meta.selector();
+        let _ = meta.complex_selector();
+
+        let shape =
+            ConstraintSystemShape::of(&meta).expect("the sizes are read off the constraint system");
+        assert_eq!(shape.num_fixed_columns, 2);
+        assert_eq!(shape.num_advice_columns, 3);
+        assert_eq!(shape.num_instance_columns, 1);
+        assert_eq!(shape.num_selectors, 2);
+        assert_eq!(shape.constants, vec![constant]);
+
+        // Counting the sizes leaves the constraint system untouched.
+        assert_eq!(
+            ConstraintSystemShape::of(&meta)
+                .expect("the sizes are read off the constraint system")
+                .num_advice_columns,
+            3
+        );
+
+        assert!(constant_columns::<Fp>("PinnedConstraintSystem").is_none());
+        assert!(constant_columns::<Fp>("constants: [Column { index: x").is_none());
+    }
+
+    #[test]
+    /// Test that the parameters derived for a smaller `K` are the ones
+    /// generated from scratch, and that they can not grow.
+    fn downsize_parameters_matches_new() {
+        fn serialized(parameters: &Params<EqAffine>) -> Vec<u8> {
+            let mut bytes = vec![];
+            parameters
+                .write(&mut bytes)
+                .expect("writing to a vector should not fail");
+            bytes
+        }
+
+        let parameters = Params::<EqAffine>::new(K + 2);
+        for new_k in [K, K + 2] {
+            let downsized =
+                downsize_parameters(&parameters, new_k).expect("the parameters are large enough");
+            assert_eq!(serialized(&downsized), serialized(&Params::new(new_k)));
+        }
+        assert!(downsize_parameters(&parameters, K + 3).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "expected to be rejected")]
+    fn assert_rejected_panics_on_valid_circuit() {
+        let circuit = PublicValueCircuit {
+            value: Value::known(Fp::from(3)),
+        };
+        assert_rejected(K, &circuit, vec![vec![Fp::from(3)]]);
+    }
+}
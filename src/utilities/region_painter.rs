@@ -0,0 +1,173 @@
+use crate::{utilities::DivModCounter, Number};
+use halo2_proofs::{
+    circuit::{Region, Value},
+    plonk::{Advice, Column, Error},
+};
+
+/// Assigns values to the advice cells of a region, one after the other,
+/// following the positions yielded by a `DivModCounter` over `columns`.
+///
+/// As with `DivModCounter`, the first painted cell is the one that comes
+/// right after the starting position, so a painter started in `(0, 0)`
+/// paints its first value in the column `columns[1]`, at row 0,
+/// if there is more than one column, and in `columns[0]`, at row 1, otherwise.
+pub struct RegionPainter<'p, 'r, F: ff::Field> {
+    region: &'p mut Region<'r, F>,
+    columns: &'p [Column<Advice>],
+    counter: DivModCounter<0, true>,
+    annotation: &'static str,
+    last_cell: Option<Number<F>>,
+}
+
+impl<'p, 'r, F: ff::Field> RegionPainter<'p, 'r, F> {
+    /// Builds a painter that starts from the row `div`, in the column
+    /// `columns[remainder]`. Every painted cell is named `annotation`.
+    pub fn new(
+        region: &'p mut Region<'r, F>,
+        columns: &'p [Column<Advice>],
+        (div, remainder): (usize, usize),
+        annotation: &'static str,
+    ) -> Self {
+        Self {
+            region,
+            columns,
+            counter: DivModCounter::new_runtime_mod(div, remainder, columns.len()),
+            annotation,
+            last_cell: None,
+        }
+    }
+
+    /// Assigns `value` to the next cell, and returns it.
+    pub fn paint(&mut self, value: Value<F>) -> Result<Number<F>, Error> {
+        let (offset, column_idx) = self.counter.next().expect("the iterator never ends");
+        let annotation = self.annotation;
+        let cell = self
+            .region
+            .assign_advice(|| annotation, self.columns[column_idx], offset, || value)
+            .map(Number)?;
+        self.last_cell = Some(cell.clone());
+        Ok(cell)
+    }
+
+    /// The last painted cell, if any.
+    pub fn last_cell(&self) -> Option<&Number<F>> {
+        self.last_cell.as_ref()
+    }
+
+    /// The region being painted, e.g. to enable selectors
+    /// or to add equality constraints on the painted cells.
+    pub fn region(&mut self) -> &mut Region<'r, F> {
+        self.region
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utilities::{assert_rejected, assert_satisfied};
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Fixed, Selector},
+        poly::Rotation,
+    };
+
+    const N_COLUMNS: usize = 3;
+    const N_VALUES: usize = 7;
+
+    /// A circuit that paints the values `1, ..., N_VALUES` over `N_COLUMNS`
+    /// advice columns, starting from `start`, and constrains every advice cell
+    /// to be equal to the fixed cell next to it, where the values are laid out
+    /// at the positions a painter started in `(0, 0)` is expected to use.
+    struct PaintingCircuit {
+        start: (usize, usize),
+    }
+
+    impl Circuit<Fp> for PaintingCircuit {
+        type Config = (
+            [Column<Advice>; N_COLUMNS],
+            [Column<Fixed>; N_COLUMNS],
+            Selector,
+        );
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { start: self.start }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice_columns = [(); N_COLUMNS].map(|_| meta.advice_column());
+            let fixed_columns = [(); N_COLUMNS].map(|_| meta.fixed_column());
+            let selector = meta.selector();
+
+            meta.create_gate("painted cells", |meta| {
+                let s = meta.query_selector(selector);
+                Vec::from_iter(advice_columns.iter().zip(fixed_columns.iter()).map(
+                    |(advice, fixed)| {
+                        s.clone()
+                            * (meta.query_advice(*advice, Rotation::cur())
+                                - meta.query_fixed(*fixed))
+                    },
+                ))
+            });
+
+            (advice_columns, fixed_columns, selector)
+        }
+
+        fn synthesize(
+            &self,
+            (advice_columns, fixed_columns, selector): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "painting",
+                |mut region| {
+                    let n_rows = (N_VALUES + self.start.0 * N_COLUMNS) / N_COLUMNS + 2;
+                    for row in 0..n_rows {
+                        selector.enable(&mut region, row)?;
+                    }
+
+                    for (idx, value) in (1..=N_VALUES as u64).enumerate() {
+                        let (row, column_idx) =
+                            DivModCounter::<N_COLUMNS>::from_linear_index(idx + 1);
+                        region.assign_fixed(
+                            || "expected value",
+                            fixed_columns[column_idx],
+                            row,
+                            || Value::known(Fp::from(value)),
+                        )?;
+                    }
+
+                    let mut painter = RegionPainter::new(
+                        &mut region,
+                        &advice_columns,
+                        self.start,
+                        "painted value",
+                    );
+                    assert!(painter.last_cell().is_none());
+                    for value in 1..=N_VALUES as u64 {
+                        painter.paint(Value::known(Fp::from(value)))?;
+                    }
+                    assert_eq!(
+                        painter.last_cell().and_then(Number::known_value),
+                        Some(Fp::from(N_VALUES as u64))
+                    );
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    const K: u32 = 4;
+
+    #[test]
+    /// Test that painting a sequence of values, starting from `(0, 0)`,
+    /// lands them in the cells right after the first one, row by row,
+    /// and that starting from another position lands them elsewhere.
+    fn mock_region_painter() {
+        assert_satisfied(K, &PaintingCircuit { start: (0, 0) }, vec![]);
+        assert_rejected(K, &PaintingCircuit { start: (0, 1) }, vec![]);
+        assert_rejected(K, &PaintingCircuit { start: (1, 0) }, vec![]);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::Number;
 use halo2_proofs::{
     circuit::{Region, Value},
-    plonk::{Any, Column, ColumnType, Error},
+    plonk::{Advice, Any, Column, ColumnType, Error},
 };
 
 // The implementation of this TryFrom is motivated by
@@ -52,10 +52,42 @@ pub trait RegionSequenceAssignment<F: ff::Field> {
     where
         Column<CT>: Into<Column<Any>>;
 
+    /// Same as `assign_array_to_column`, but every assigned cell is
+    /// annotated with `annotation(idx)`, where `idx` is the index
+    /// of the assigned value in `to_column_values`.
+    fn assign_array_to_column_annotated<const LEN: usize, CT: ColumnType, A: Fn(usize) -> String>(
+        &mut self,
+        column: Column<CT>,
+        offset: usize,
+        to_column_values: [Value<F>; LEN],
+        annotation: A,
+    ) -> Result<[Number<F>; LEN], Error>
+    where
+        Column<CT>: Into<Column<Any>>;
+
+    /// Given a region, a column, an offset, and a slice of values,
+    /// this function assigns the values of the slice to cells in the given
+    /// column, with relative row index `[offset .. offset + values.len()]`.
+    ///
+    /// This is the runtime-length counterpart of `assign_array_to_column`.
+    /// Instance columns cannot be assigned to, and make it return
+    /// `Error::Synthesis`.
+    fn assign_slice_to_column<CT: ColumnType>(
+        &mut self,
+        column: Column<CT>,
+        offset: usize,
+        values: &[Value<F>],
+    ) -> Result<Vec<Number<F>>, Error>
+    where
+        Column<CT>: Into<Column<Any>>;
+
     /// Given a region, an array of columns, an offset, and
     /// an array of arrays of values,
     /// this function assigns the values of the array to cells in the given
     /// columns, with relative row index `[offset .. offset + ROW_NR]`
+    ///
+    /// With `CT = Any`, advice and fixed columns can be mixed in the same grid,
+    /// since every column is dispatched according to its own type.
     fn assign_grid_to_columns<const COLUMN_NR: usize, const ROW_NR: usize, CT: ColumnType>(
         &mut self,
         columns: [Column<CT>; COLUMN_NR],
@@ -64,6 +96,34 @@ pub trait RegionSequenceAssignment<F: ff::Field> {
     ) -> Result<[[Number<F>; ROW_NR]; COLUMN_NR], Error>
     where
         Column<CT>: Into<Column<Any>>;
+
+    /// Same as `assign_grid_to_columns`, but the assigned cells are returned
+    /// row by row, so that the cell in the `col_idx`-th column and
+    /// in the `row_idx`-th row is found at `[row_idx][col_idx]`.
+    fn assign_grid_to_columns_transposed<
+        const COLUMN_NR: usize,
+        const ROW_NR: usize,
+        CT: ColumnType,
+    >(
+        &mut self,
+        columns: [Column<CT>; COLUMN_NR],
+        offset: usize,
+        grid_values: [[Value<F>; ROW_NR]; COLUMN_NR],
+    ) -> Result<[[Number<F>; COLUMN_NR]; ROW_NR], Error>
+    where
+        Column<CT>: Into<Column<Any>>;
+
+    /// Given a region, an array of advice columns, an offset, and
+    /// a grid of already assigned cells,
+    /// this function copies the cells of the grid to cells in the given
+    /// columns, with relative row index `[offset .. offset + ROW_NR]`.
+    /// Every copy is constrained to be equal to its source cell.
+    fn copy_grid_to_columns<const COLUMN_NR: usize, const ROW_NR: usize>(
+        &mut self,
+        columns: [Column<Advice>; COLUMN_NR],
+        offset: usize,
+        source_cells: &[[Number<F>; ROW_NR]; COLUMN_NR],
+    ) -> Result<[[Number<F>; ROW_NR]; COLUMN_NR], Error>;
 }
 
 use try_collect::{ForceCollect, TryCollect};
@@ -78,35 +138,40 @@ impl<'a, F: ff::Field> RegionSequenceAssignment<F> for Region<'a, F> {
     where
         Column<CT>: Into<Column<Any>>,
     {
-        let ann = || "assigning array to column";
-        let row_and_value_iter = (offset..offset + LEN).zip(to_column_values);
-        // A lot of the code duplication happening here is not avoidable due to
-        // functions in Rust having different types.
-        // The duplication is needed because in one branch we use
-        // `self.assign_advice`, and in the other `self.assign_fixed`,
-        // which require different kinds of column as arguments.
-        match (*column.column_type()).into() {
-            Any::Advice => {
-                let column = column.into().try_into().unwrap();
-                row_and_value_iter
-                    .map(|(row_idx, value)| {
-                        self.assign_advice(ann, column, row_idx, || value)
-                            .map(Number)
-                    })
-                    .try_collect::<[Number<F>; LEN]>()
-            }
-            Any::Fixed => {
-                let column = column.into().try_into().unwrap();
-                row_and_value_iter
-                    .map(|(row_idx, value)| {
-                        self.assign_fixed(ann, column, row_idx, || value)
-                            .map(Number)
-                    })
-                    .try_collect::<[Number<F>; LEN]>()
-            }
-            Any::Instance => unimplemented!("idk how to do it, and i think i won't need it"),
-        }
-        .map_err(|err| err.expect_try_from_error(|| "we know the number of items is correct"))
+        // The annotation is a static string, so that nothing is allocated
+        // unless the backend asks for the annotations.
+        assign_array_with_annotation(self, column, offset, &to_column_values, |_| {
+            "assigning array to column"
+        })
+        .map(|cells| cells.f_collect("the number of items is correct"))
+    }
+
+    fn assign_array_to_column_annotated<const LEN: usize, CT: ColumnType, A: Fn(usize) -> String>(
+        &mut self,
+        column: Column<CT>,
+        offset: usize,
+        to_column_values: [Value<F>; LEN],
+        annotation: A,
+    ) -> Result<[Number<F>; LEN], Error>
+    where
+        Column<CT>: Into<Column<Any>>,
+    {
+        assign_array_with_annotation(self, column, offset, &to_column_values, annotation)
+            .map(|cells| cells.f_collect("the number of items is correct"))
+    }
+
+    fn assign_slice_to_column<CT: ColumnType>(
+        &mut self,
+        column: Column<CT>,
+        offset: usize,
+        values: &[Value<F>],
+    ) -> Result<Vec<Number<F>>, Error>
+    where
+        Column<CT>: Into<Column<Any>>,
+    {
+        assign_array_with_annotation(self, column, offset, values, |_| {
+            "assigning slice to column"
+        })
     }
 
     fn assign_grid_to_columns<const COLUMN_NR: usize, const ROW_NR: usize, CT: ColumnType>(
@@ -126,4 +191,636 @@ impl<'a, F: ff::Field> RegionSequenceAssignment<F> for Region<'a, F> {
             .map_err(|err| err.expect_try_from_error(|| "we know the number of items is correct"))
             .map(|grid| grid.f_collect("the number of items is correct"))
     }
+
+    fn assign_grid_to_columns_transposed<
+        const COLUMN_NR: usize,
+        const ROW_NR: usize,
+        CT: ColumnType,
+    >(
+        &mut self,
+        columns: [Column<CT>; COLUMN_NR],
+        offset: usize,
+        grid_values: [[Value<F>; ROW_NR]; COLUMN_NR],
+    ) -> Result<[[Number<F>; COLUMN_NR]; ROW_NR], Error>
+    where
+        Column<CT>: Into<Column<Any>>,
+    {
+        let grid = self.assign_grid_to_columns(columns, offset, grid_values)?;
+        Ok(core::array::from_fn(|row_idx| {
+            core::array::from_fn(|col_idx| grid[col_idx][row_idx].clone())
+        }))
+    }
+
+    fn copy_grid_to_columns<const COLUMN_NR: usize, const ROW_NR: usize>(
+        &mut self,
+        columns: [Column<Advice>; COLUMN_NR],
+        offset: usize,
+        source_cells: &[[Number<F>; ROW_NR]; COLUMN_NR],
+    ) -> Result<[[Number<F>; ROW_NR]; COLUMN_NR], Error> {
+        let ann = || "copying grid to columns";
+        columns
+            .into_iter()
+            .zip(source_cells)
+            .map(|(column, sources)| {
+                sources
+                    .iter()
+                    .enumerate()
+                    .map(|(row_idx, source)| {
+                        source
+                            .copy_advice(ann, self, column, offset + row_idx)
+                            .map(Number)
+                    })
+                    .try_collect::<[Number<F>; ROW_NR]>()
+                    .map_err(|err| {
+                        err.expect_try_from_error(|| "we know the number of items is correct")
+                    })
+            })
+            .try_collect::<[ArrayWrap<Number<F>, ROW_NR>; COLUMN_NR]>()
+            .map_err(|err| err.expect_try_from_error(|| "we know the number of items is correct"))
+            .map(|grid| grid.f_collect("the number of items is correct"))
+    }
+}
+
+/// The implementation of `assign_array_to_column`,
+/// `assign_array_to_column_annotated`, and `assign_slice_to_column`,
+/// generic over the annotation type.
+fn assign_array_with_annotation<
+    F: ff::Field,
+    CT: ColumnType,
+    AR: Into<String>,
+    A: Fn(usize) -> AR,
+>(
+    region: &mut Region<'_, F>,
+    column: Column<CT>,
+    offset: usize,
+    to_column_values: &[Value<F>],
+    annotation: A,
+) -> Result<Vec<Number<F>>, Error>
+where
+    Column<CT>: Into<Column<Any>>,
+{
+    let annotation = &annotation;
+    let row_and_value_iter = (offset..offset + to_column_values.len())
+        .zip(to_column_values.iter().copied())
+        .enumerate();
+    // A lot of the code duplication happening here is not avoidable due to
+    // functions in Rust having different types.
+    // The duplication is needed because in one branch we use
+    // `region.assign_advice`, and in the other `region.assign_fixed`,
+    // which require different kinds of column as arguments.
+    match (*column.column_type()).into() {
+        Any::Advice => {
+            let column = column.into().try_into().unwrap();
+            row_and_value_iter
+                .map(|(idx, (row_idx, value))| {
+                    region
+                        .assign_advice(|| annotation(idx), column, row_idx, || value)
+                        .map(Number)
+                })
+                .collect()
+        }
+        Any::Fixed => {
+            let column = column.into().try_into().unwrap();
+            row_and_value_iter
+                .map(|(idx, (row_idx, value))| {
+                    region
+                        .assign_fixed(|| annotation(idx), column, row_idx, || value)
+                        .map(Number)
+                })
+                .collect()
+        }
+        Any::Instance => Err(Error::Synthesis),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Assigned, Assignment, Circuit, ConstraintSystem, Fixed, Instance, Selector},
+    };
+
+    /// A circuit that assigns a grid, and then copies it right below itself.
+    /// If `tamper_source` is set, the source cells are then overwritten
+    /// with values that disagree with their copies.
+    struct CopyGridCircuit {
+        grid: [[Value<Fp>; 3]; 2],
+        tamper_source: bool,
+    }
+
+    impl Circuit<Fp> for CopyGridCircuit {
+        type Config = [Column<Advice>; 2];
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                grid: [[Value::unknown(); 3]; 2],
+                tamper_source: self.tamper_source,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let columns = [(); 2].map(|_| meta.advice_column());
+            for col in columns {
+                meta.enable_equality(col);
+            }
+            columns
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "copy grid",
+                |mut region| {
+                    let source = region.assign_grid_to_columns(config, 0, self.grid)?;
+                    region.copy_grid_to_columns(config, 3, &source)?;
+
+                    if self.tamper_source {
+                        let tampered_grid = self
+                            .grid
+                            .map(|col| col.map(|value| value + Value::known(Fp::from(1))));
+                        region.assign_grid_to_columns(config, 0, tampered_grid)?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    /// Test that copied grids are accepted as they are,
+    /// and rejected as soon as the source disagrees with the copy.
+    fn mock_copy_grid_to_columns() {
+        const POW_OF_2_MAX_ROWS: u32 = 4;
+
+        let grid = core::array::from_fn(|col_idx| {
+            core::array::from_fn(|row_idx| Value::known(Fp::from((3 * col_idx + row_idx) as u64)))
+        });
+
+        let circuit = CopyGridCircuit {
+            grid,
+            tamper_source: false,
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+
+        let circuit = CopyGridCircuit {
+            grid,
+            tamper_source: true,
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert!(prover.verify().is_err(), "Bogous proof was accepted");
+    }
+
+    /// A circuit that assigns the same grid twice, once with
+    /// `assign_grid_to_columns` and once with `assign_grid_to_columns_transposed`,
+    /// in two different couples of columns, and constrains the resulting cells,
+    /// accessed with swapped indices, to be equal.
+    struct TransposedGridCircuit {
+        grid: [[Value<Fp>; 3]; 2],
+    }
+
+    impl Circuit<Fp> for TransposedGridCircuit {
+        type Config = [[Column<Advice>; 2]; 2];
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                grid: [[Value::unknown(); 3]; 2],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let columns = [(); 2].map(|_| [(); 2].map(|_| meta.advice_column()));
+            for col in columns.into_iter().flatten() {
+                meta.enable_equality(col);
+            }
+            columns
+        }
+
+        fn synthesize(
+            &self,
+            [columns, transposed_columns]: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "grid and transposed grid assignment",
+                |mut region| {
+                    let cells = region.assign_grid_to_columns(columns, 0, self.grid)?;
+                    let transposed_cells = region.assign_grid_to_columns_transposed(
+                        transposed_columns,
+                        0,
+                        self.grid,
+                    )?;
+
+                    for (col_idx, column_cells) in cells.iter().enumerate() {
+                        for (row_idx, cell) in column_cells.iter().enumerate() {
+                            let transposed_cell = &transposed_cells[row_idx][col_idx];
+                            cell.value()
+                                .zip(transposed_cell.value())
+                                .assert_if_known(|(a, b)| a == b);
+                            region.constrain_equal(cell.cell(), transposed_cell.cell())?;
+                        }
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    /// Test that `assign_grid_to_columns_transposed` assigns the same cells
+    /// as `assign_grid_to_columns`, returned with swapped indices.
+    fn mock_assign_grid_to_columns_transposed() {
+        const POW_OF_2_MAX_ROWS: u32 = 4;
+
+        let circuit = TransposedGridCircuit {
+            grid: core::array::from_fn(|col_idx| {
+                core::array::from_fn(|row_idx| {
+                    Value::known(Fp::from((3 * col_idx + row_idx) as u64))
+                })
+            }),
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+    }
+
+    /// A circuit that assigns a grid to an advice column and a fixed column,
+    /// and constrains the cells of the two columns to be equal, row by row.
+    struct MixedGridCircuit {
+        advice_values: [Value<Fp>; 3],
+        constants: [Fp; 3],
+    }
+
+    impl Circuit<Fp> for MixedGridCircuit {
+        type Config = (Column<Advice>, Column<halo2_proofs::plonk::Fixed>, Selector);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                advice_values: [Value::unknown(); 3],
+                constants: self.constants,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            let fixed = meta.fixed_column();
+            let s_eq = meta.selector();
+            meta.create_gate("advice equals fixed", |meta| {
+                let s_eq = meta.query_selector(s_eq);
+                let advice = meta.query_advice(advice, halo2_proofs::poly::Rotation::cur());
+                let fixed = meta.query_fixed(fixed);
+                vec![s_eq * (advice - fixed)]
+            });
+            (advice, fixed, s_eq)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, fixed, s_eq): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "mixed grid",
+                |mut region| {
+                    for row_idx in 0..3 {
+                        s_eq.enable(&mut region, row_idx)?;
+                    }
+                    let columns: [Column<Any>; 2] = [advice.into(), fixed.into()];
+                    let [_, fixed_cells] = region.assign_grid_to_columns(
+                        columns,
+                        0,
+                        [self.advice_values, self.constants.map(Value::known)],
+                    )?;
+
+                    for (cell, constant) in fixed_cells.iter().zip(self.constants) {
+                        cell.value().assert_if_known(|value| **value == constant);
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    /// Test that a grid mixing an advice and a fixed column
+    /// assigns the constants to the fixed column.
+    fn mock_assign_grid_to_mixed_columns() {
+        const POW_OF_2_MAX_ROWS: u32 = 4;
+
+        let constants = [5, 7, 11].map(|n: u64| Fp::from(n));
+
+        let circuit = MixedGridCircuit {
+            advice_values: constants.map(Value::known),
+            constants,
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+
+        let circuit = MixedGridCircuit {
+            advice_values: constants.map(|constant| Value::known(constant + Fp::from(1))),
+            constants,
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert!(prover.verify().is_err(), "Bogous proof was accepted");
+    }
+
+    /// A circuit that assigns the same values twice, once with
+    /// `assign_array_to_column` and once with `assign_slice_to_column`,
+    /// in two different columns, and constrains the resulting cells to be equal.
+    struct SliceAndArrayCircuit {
+        values: [Value<Fp>; 4],
+    }
+
+    impl Circuit<Fp> for SliceAndArrayCircuit {
+        type Config = [Column<Advice>; 2];
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: [Value::unknown(); 4],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let columns = [(); 2].map(|_| meta.advice_column());
+            for col in columns {
+                meta.enable_equality(col);
+            }
+            columns
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "array and slice assignment",
+                |mut region| {
+                    let array_cells = region.assign_array_to_column(config[0], 0, self.values)?;
+                    let slice_cells = region.assign_slice_to_column(config[1], 0, &self.values)?;
+
+                    assert_eq!(array_cells.len(), slice_cells.len());
+                    for (array_cell, slice_cell) in array_cells.iter().zip(slice_cells.iter()) {
+                        array_cell
+                            .value()
+                            .zip(slice_cell.value())
+                            .assert_if_known(|(a, b)| a == b);
+                        region.constrain_equal(array_cell.cell(), slice_cell.cell())?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    /// Test that `assign_slice_to_column` produces the same cells
+    /// as `assign_array_to_column` for a length-4 input.
+    fn mock_assign_slice_to_column() {
+        const POW_OF_2_MAX_ROWS: u32 = 4;
+
+        let circuit = SliceAndArrayCircuit {
+            values: core::array::from_fn(|n| Value::known(Fp::from(n as u64 + 1))),
+        };
+        let prover = MockProver::run(POW_OF_2_MAX_ROWS, &circuit, vec![])
+            .expect("Proof generation goes wrong");
+        assert_eq!(prover.verify(), Ok(()), "Proof verification goes wrong");
+    }
+
+    /// A circuit that tries to assign a slice, or an array,
+    /// to an instance column.
+    struct InstanceSliceCircuit {
+        as_array: bool,
+    }
+
+    impl Circuit<Fp> for InstanceSliceCircuit {
+        type Config = Column<Instance>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                as_array: self.as_array,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            meta.instance_column()
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "slice assignment to instance column",
+                |mut region| {
+                    let values = [Value::known(Fp::from(1))];
+                    if self.as_array {
+                        region.assign_array_to_column(config, 0, values)?;
+                    } else {
+                        region.assign_slice_to_column(config, 0, &values)?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    /// Test that `assign_slice_to_column` and `assign_array_to_column`
+    /// refuse instance columns with a synthesis error, instead of panicking.
+    fn mock_assign_slice_to_instance_column() {
+        const POW_OF_2_MAX_ROWS: u32 = 4;
+
+        for as_array in [false, true] {
+            assert!(matches!(
+                MockProver::run(
+                    POW_OF_2_MAX_ROWS,
+                    &InstanceSliceCircuit { as_array },
+                    vec![vec![]]
+                ),
+                Err(Error::Synthesis)
+            ));
+        }
+    }
+
+    /// The annotation we give to the cells in `AnnotatedArrayCircuit`.
+    fn cell_annotation(idx: usize) -> String {
+        format!("{idx}-th cell of the annotated array")
+    }
+
+    /// A circuit that assigns an array with per-cell annotations.
+    struct AnnotatedArrayCircuit {
+        values: [Value<Fp>; 4],
+    }
+
+    impl Circuit<Fp> for AnnotatedArrayCircuit {
+        type Config = Column<Advice>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: [Value::unknown(); 4],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            meta.advice_column()
+        }
+
+        fn synthesize(
+            &self,
+            column: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "annotated array",
+                |mut region| {
+                    region.assign_array_to_column_annotated(
+                        column,
+                        0,
+                        self.values,
+                        cell_annotation,
+                    )?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    /// An `Assignment` that records the annotation of every assigned cell,
+    /// along with its column and row, and ignores everything else.
+    #[derive(Default)]
+    struct AnnotationRecorder {
+        annotations: Vec<(Column<Any>, usize, String)>,
+    }
+
+    impl Assignment<Fp> for AnnotationRecorder {
+        fn enter_region<NR, N>(&mut self, _name_fn: N)
+        where
+            NR: Into<String>,
+            N: FnOnce() -> NR,
+        {
+        }
+
+        fn exit_region(&mut self) {}
+
+        fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, _: usize) -> Result<(), Error>
+        where
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            Ok(())
+        }
+
+        fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<Fp>, Error> {
+            Ok(Value::unknown())
+        }
+
+        fn assign_advice<V, VR, A, AR>(
+            &mut self,
+            annotation: A,
+            column: Column<Advice>,
+            row: usize,
+            _: V,
+        ) -> Result<(), Error>
+        where
+            V: FnOnce() -> Value<VR>,
+            VR: Into<Assigned<Fp>>,
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            self.annotations
+                .push((column.into(), row, annotation().into()));
+            Ok(())
+        }
+
+        fn assign_fixed<V, VR, A, AR>(
+            &mut self,
+            annotation: A,
+            column: Column<Fixed>,
+            row: usize,
+            _: V,
+        ) -> Result<(), Error>
+        where
+            V: FnOnce() -> Value<VR>,
+            VR: Into<Assigned<Fp>>,
+            A: FnOnce() -> AR,
+            AR: Into<String>,
+        {
+            self.annotations
+                .push((column.into(), row, annotation().into()));
+            Ok(())
+        }
+
+        fn copy(
+            &mut self,
+            _: Column<Any>,
+            _: usize,
+            _: Column<Any>,
+            _: usize,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn fill_from_row(
+            &mut self,
+            _: Column<Fixed>,
+            _: usize,
+            _: Value<Assigned<Fp>>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn push_namespace<NR, N>(&mut self, _name_fn: N)
+        where
+            NR: Into<String>,
+            N: FnOnce() -> NR,
+        {
+        }
+
+        fn pop_namespace(&mut self, _gadget_name: Option<String>) {}
+    }
+
+    #[test]
+    /// Test that every cell assigned with `assign_array_to_column_annotated`
+    /// is given its own annotation, by recording the annotations
+    /// the floor planner passes to the backend.
+    ///
+    /// The `MockProver` discards the annotations of the cells,
+    /// so they cannot be checked in its verification failures.
+    fn record_assign_array_to_column_annotated() {
+        use halo2_proofs::plonk::FloorPlanner;
+
+        let circuit = AnnotatedArrayCircuit {
+            values: [Value::known(Fp::from(0)); 4],
+        };
+        let mut meta = ConstraintSystem::default();
+        let config = AnnotatedArrayCircuit::configure(&mut meta);
+        let mut recorder = AnnotationRecorder::default();
+        SimpleFloorPlanner::synthesize(&mut recorder, &circuit, config, vec![])
+            .expect("synthesis should not fail");
+
+        let column: Column<Any> = config.into();
+        assert_eq!(
+            recorder.annotations,
+            Vec::from_iter((0..4).map(|idx| (column, idx, cell_annotation(idx))))
+        );
+    }
 }
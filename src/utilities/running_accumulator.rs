@@ -0,0 +1,52 @@
+use halo2_proofs::{
+    plonk::{Advice, Column, ConstraintSystem, Expression, Selector, VirtualCells},
+    poly::Rotation,
+};
+
+/// Creates the gates of running accumulators laid out one item per row,
+/// in `accumulator_columns`.
+///
+/// Starting from zero, every accumulator is updated with `step`, which
+/// is given the accumulators at the previous row, and returns their
+/// updated values, querying the item of the current row as it needs.
+/// The first gate, named `names[0]`, constrains under `s_first`
+/// the accumulators to be the update of zero, and the second one,
+/// named `names[1]`, constrains under `s_next` the accumulators
+/// to be the update of those at the previous row.
+pub(crate) fn running_accumulator_gates<F: ff::Field, const N: usize>(
+    meta: &mut ConstraintSystem<F>,
+    names: [&'static str; 2],
+    [s_first, s_next]: [Selector; 2],
+    accumulator_columns: [Column<Advice>; N],
+    step: impl Fn(&mut VirtualCells<'_, F>, [Expression<F>; N]) -> [Expression<F>; N],
+) {
+    meta.create_gate(names[0], |meta| {
+        let s_first = meta.query_selector(s_first);
+        let updated = step(meta, [(); N].map(|_| Expression::Constant(F::ZERO)));
+        constrain_accumulators(meta, &s_first, accumulator_columns, updated)
+    });
+
+    meta.create_gate(names[1], |meta| {
+        let s_next = meta.query_selector(s_next);
+        let previous = accumulator_columns.map(|col| meta.query_advice(col, Rotation::prev()));
+        let updated = step(meta, previous);
+        constrain_accumulators(meta, &s_next, accumulator_columns, updated)
+    });
+}
+
+/// Constrains, under `selector`, the accumulators at the current row
+/// to be equal to `updated`.
+fn constrain_accumulators<F: ff::Field, const N: usize>(
+    meta: &mut VirtualCells<'_, F>,
+    selector: &Expression<F>,
+    accumulator_columns: [Column<Advice>; N],
+    updated: [Expression<F>; N],
+) -> Vec<Expression<F>> {
+    accumulator_columns
+        .into_iter()
+        .zip(updated)
+        .map(|(col, updated)| {
+            selector.clone() * (meta.query_advice(col, Rotation::cur()) - updated)
+        })
+        .collect()
+}
@@ -0,0 +1,43 @@
+use halo2_proofs::plonk::Expression;
+
+/// Builds the constraints of a running product of `factors`,
+/// multiplied `batch_size` at a time.
+///
+/// Starting from `1`, every batch of factors is multiplied into the product
+/// computed so far, and the result is constrained, under `selector`,
+/// to be equal to the next cell yielded by `next_cell`.
+/// The last batch may contain less than `batch_size` factors.
+///
+/// Returns the constraints, together with the cell holding the whole product,
+/// which is the constant `1` if there are no factors.
+///
+/// Panics if `batch_size` is zero.
+pub(crate) fn batched_running_product<F: ff::Field>(
+    selector: &Expression<F>,
+    factors: impl IntoIterator<Item = Expression<F>>,
+    batch_size: usize,
+    mut next_cell: impl FnMut() -> Expression<F>,
+) -> (Vec<Expression<F>>, Expression<F>) {
+    assert!(batch_size > 0, "the batches must not be empty");
+
+    let mut factors = factors.into_iter().peekable();
+    let mut constraints = vec![];
+    let mut last_cell = Expression::Constant(F::ONE);
+
+    while factors.peek().is_some() {
+        let product = (&mut factors)
+            .take(batch_size)
+            .fold(Expression::Constant(F::ONE), |product, factor| {
+                product * factor
+            });
+
+        let next_cell = next_cell();
+
+        // We force the next cell to be equal to
+        // the updated value of aggregated product
+        constraints.push(selector.clone() * (next_cell.clone() - last_cell * product));
+        last_cell = next_cell;
+    }
+
+    (constraints, last_cell)
+}
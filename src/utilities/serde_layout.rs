@@ -0,0 +1,276 @@
+use halo2_proofs::{
+    pasta::Fp,
+    plonk::{Advice, Column, ConstraintSystem, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A part of a circuit layout, such as a column or a selector,
+/// that can be described by a serializable representation.
+///
+/// halo2 does not allow to build columns and selectors from their indices,
+/// so we rebuild them by allocating them in a throwaway `ConstraintSystem`,
+/// until the required index is reached. Since that allocates every item
+/// with a smaller index, indices are bounded by `MAX_LAYOUT_INDEX`.
+pub(crate) trait LayoutItem: Sized {
+    type Repr: Serialize + for<'de> Deserialize<'de>;
+
+    fn to_repr(&self) -> Result<Self::Repr, String>;
+    fn from_repr(repr: Self::Repr) -> Result<Self, String>;
+}
+
+/// The number of columns, or selectors, of each kind that
+/// a serializable configuration can refer to. No circuit of this crate
+/// gets close to it, and it keeps the throwaway constraint systems small
+/// when deserializing untrusted data.
+pub(crate) const MAX_LAYOUT_INDEX: usize = 1 << 12;
+
+/// Allocates `index + 1` items in a fresh constraint system,
+/// and returns the last one, which is the one with the required index.
+fn allocate_up_to<T>(
+    index: usize,
+    allocate: impl Fn(&mut ConstraintSystem<Fp>) -> T,
+) -> Result<T, String> {
+    if index >= MAX_LAYOUT_INDEX {
+        return Err(format!(
+            "layout index {index} is out of bounds, the maximum is {}",
+            MAX_LAYOUT_INDEX - 1
+        ));
+    }
+    let mut meta = ConstraintSystem::<Fp>::default();
+    Ok((0..=index)
+        .map(|_| allocate(&mut meta))
+        .last()
+        .expect("the range is not empty"))
+}
+
+/// Finds the index of `item` among the items allocated
+/// in a fresh constraint system, since halo2 does not expose it.
+fn find_index<T: PartialEq>(
+    item: &T,
+    allocate: impl Fn(&mut ConstraintSystem<Fp>) -> T,
+) -> Result<usize, String> {
+    let mut meta = ConstraintSystem::<Fp>::default();
+    (0..MAX_LAYOUT_INDEX)
+        .position(|_| allocate(&mut meta) == *item)
+        .ok_or_else(|| {
+            format!(
+                "layout index is out of bounds, the maximum is {}",
+                MAX_LAYOUT_INDEX - 1
+            )
+        })
+}
+
+impl LayoutItem for Column<Advice> {
+    type Repr = usize;
+    fn to_repr(&self) -> Result<Self::Repr, String> {
+        find_index(self, |meta| meta.advice_column())
+    }
+    fn from_repr(repr: Self::Repr) -> Result<Self, String> {
+        allocate_up_to(repr, |meta| meta.advice_column())
+    }
+}
+
+impl LayoutItem for Column<Fixed> {
+    type Repr = usize;
+    fn to_repr(&self) -> Result<Self::Repr, String> {
+        find_index(self, |meta| meta.fixed_column())
+    }
+    fn from_repr(repr: Self::Repr) -> Result<Self, String> {
+        allocate_up_to(repr, |meta| meta.fixed_column())
+    }
+}
+
+impl LayoutItem for Column<Instance> {
+    type Repr = usize;
+    fn to_repr(&self) -> Result<Self::Repr, String> {
+        find_index(self, |meta| meta.instance_column())
+    }
+    fn from_repr(repr: Self::Repr) -> Result<Self, String> {
+        allocate_up_to(repr, |meta| meta.instance_column())
+    }
+}
+
+/// Allocates a selector of the given kind in `meta`.
+fn allocate_selector(meta: &mut ConstraintSystem<Fp>, is_simple: bool) -> Selector {
+    if is_simple {
+        meta.selector()
+    } else {
+        meta.complex_selector()
+    }
+}
+
+impl LayoutItem for Selector {
+    /// The selector index, and whether the selector is simple.
+    type Repr = (usize, bool);
+    fn to_repr(&self) -> Result<Self::Repr, String> {
+        // Selectors are looked for among those of the same kind.
+        let is_simple = self.is_simple();
+        find_index(self, |meta| allocate_selector(meta, is_simple)).map(|index| (index, is_simple))
+    }
+    fn from_repr((index, is_simple): Self::Repr) -> Result<Self, String> {
+        allocate_up_to(index, |meta| allocate_selector(meta, is_simple))
+    }
+}
+
+impl LayoutItem for Rotation {
+    type Repr = i32;
+    fn to_repr(&self) -> Result<Self::Repr, String> {
+        Ok(self.0)
+    }
+    fn from_repr(repr: Self::Repr) -> Result<Self, String> {
+        Ok(Rotation(repr))
+    }
+}
+
+impl<A: LayoutItem, B: LayoutItem> LayoutItem for (A, B) {
+    type Repr = (A::Repr, B::Repr);
+    fn to_repr(&self) -> Result<Self::Repr, String> {
+        Ok((self.0.to_repr()?, self.1.to_repr()?))
+    }
+    fn from_repr((a, b): Self::Repr) -> Result<Self, String> {
+        Ok((A::from_repr(a)?, B::from_repr(b)?))
+    }
+}
+
+/// To be used as `#[serde(with = "crate::utilities::serde_layout::item")]`
+/// on fields whose type implements `LayoutItem`.
+pub(crate) mod item {
+    use super::*;
+
+    pub fn serialize<T: LayoutItem, S: Serializer>(
+        item: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        item.to_repr()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: LayoutItem, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::from_repr(T::Repr::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// To be used as `#[serde(with = "crate::utilities::serde_layout::array")]`
+/// on fields of type `[T; LEN]`, where `T` implements `LayoutItem`.
+pub(crate) mod array {
+    use super::*;
+
+    pub fn serialize<T: LayoutItem, S: Serializer, const LEN: usize>(
+        items: &[T; LEN],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::vec::serialize(items.as_slice(), serializer)
+    }
+
+    pub fn deserialize<'de, T: LayoutItem, D: Deserializer<'de>, const LEN: usize>(
+        deserializer: D,
+    ) -> Result<[T; LEN], D::Error> {
+        let items: Vec<T> = super::vec::deserialize(deserializer)?;
+        let len = items.len();
+        items.try_into().map_err(|_| {
+            serde::de::Error::invalid_length(len, &format!("an array of length {LEN}").as_str())
+        })
+    }
+}
+
+/// To be used as `#[serde(with = "crate::utilities::serde_layout::vec")]`
+/// on fields of type `Vec<T>`, where `T` implements `LayoutItem`.
+pub(crate) mod vec {
+    use super::*;
+
+    pub fn serialize<T: LayoutItem, S: Serializer>(
+        items: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let reprs = items
+            .iter()
+            .map(LayoutItem::to_repr)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(serde::ser::Error::custom)?;
+        serializer.collect_seq(reprs)
+    }
+
+    pub fn deserialize<'de, T: LayoutItem, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        Vec::<T::Repr>::deserialize(deserializer)?
+            .into_iter()
+            .map(T::from_repr)
+            .collect::<Result<_, _>>()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// To be used as `#[serde(with = "crate::utilities::serde_layout::option")]`
+/// on fields of type `Option<T>`, where `T` implements `LayoutItem`.
+pub(crate) mod option {
+    use super::*;
+
+    pub fn serialize<T: LayoutItem, S: Serializer>(
+        item: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        item.as_ref()
+            .map(LayoutItem::to_repr)
+            .transpose()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: LayoutItem, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<T>, D::Error> {
+        Option::<T::Repr>::deserialize(deserializer)?
+            .map(T::from_repr)
+            .transpose()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip<T: LayoutItem + PartialEq + std::fmt::Debug>(item: T) {
+        let repr = item.to_repr().expect("the index is in bounds");
+        assert_eq!(T::from_repr(repr), Ok(item));
+    }
+
+    #[test]
+    fn layout_items_round_trip() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let fixed = [(); 2].map(|_| meta.fixed_column());
+        let instance = meta.instance_column();
+        let selectors = [meta.selector(), meta.complex_selector(), meta.selector()];
+
+        advice.into_iter().for_each(assert_round_trip);
+        fixed.into_iter().for_each(assert_round_trip);
+        assert_round_trip(instance);
+        assert_eq!(
+            selectors.map(|selector| selector.to_repr()),
+            [Ok((0, true)), Ok((1, false)), Ok((2, true))]
+        );
+        selectors.into_iter().for_each(assert_round_trip);
+        assert_round_trip(Rotation(-3));
+    }
+
+    #[test]
+    /// Test that indices out of bounds are rejected when deserializing,
+    /// instead of allocating as many columns or selectors.
+    fn layout_items_out_of_bounds() {
+        assert!(Column::<Advice>::from_repr(MAX_LAYOUT_INDEX - 1).is_ok());
+        assert!(Column::<Advice>::from_repr(MAX_LAYOUT_INDEX).is_err());
+        assert!(Column::<Instance>::from_repr(usize::MAX).is_err());
+        assert!(Selector::from_repr((MAX_LAYOUT_INDEX, false)).is_err());
+
+        let json = usize::MAX.to_string();
+        let deserialized: Result<Column<Fixed>, _> =
+            item::deserialize(&mut serde_json::Deserializer::from_str(&json));
+        assert!(deserialized.is_err());
+    }
+}
@@ -0,0 +1,75 @@
+/// Iterates over the `3 * SIZE` lines of a `SIZE x SIZE` sudoku grid,
+/// i.e. the groups of cells that have to contain every symbol exactly once:
+/// the columns, the rows, and the regions of the grid, in this order.
+///
+/// Each line is given as the `(col_idx, row_idx)` positions of its cells.
+/// The regions are visited column by column, and the cells
+/// of each region are visited column by column, too.
+/// For example, if `SIZE == 4` and `SIZE_SQRT == 2`, the third region
+/// consists of the cells marked in the image below, in the visualized order
+/// ```text
+/// |-------|
+/// | | |0|2|
+/// |-------|
+/// | | |1|3|
+/// |-------|
+/// | | | | |
+/// |-------|
+/// | | | | |
+/// |-------|
+/// ```
+///
+/// It is up to the caller to guarantee that `SIZE == SIZE_SQRT * SIZE_SQRT`.
+pub fn sudoku_line_iter<const SIZE: usize, const SIZE_SQRT: usize>(
+) -> impl Iterator<Item = [(usize, usize); SIZE]> {
+    let columns = (0..SIZE).map(|col_idx| core::array::from_fn(|row_idx| (col_idx, row_idx)));
+    let rows = (0..SIZE).map(|row_idx| core::array::from_fn(|col_idx| (col_idx, row_idx)));
+    let regions = (0..SIZE).map(|region_idx| {
+        let region_col_offset = (region_idx / SIZE_SQRT) * SIZE_SQRT;
+        let region_row_offset = (region_idx % SIZE_SQRT) * SIZE_SQRT;
+        core::array::from_fn(|idx| {
+            (
+                region_col_offset + idx / SIZE_SQRT,
+                region_row_offset + idx % SIZE_SQRT,
+            )
+        })
+    });
+
+    columns.chain(rows).chain(regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sudoku_line_iter_4x4() {
+        let lines = Vec::from_iter(sudoku_line_iter::<4, 2>());
+        assert_eq!(lines.len(), 12);
+
+        assert_eq!(lines[1], [(1, 0), (1, 1), (1, 2), (1, 3)]);
+        assert_eq!(lines[6], [(0, 2), (1, 2), (2, 2), (3, 2)]);
+
+        assert_eq!(
+            lines[8..],
+            [
+                [(0, 0), (0, 1), (1, 0), (1, 1)],
+                [(0, 2), (0, 3), (1, 2), (1, 3)],
+                [(2, 0), (2, 1), (3, 0), (3, 1)],
+                [(2, 2), (2, 3), (3, 2), (3, 3)],
+            ]
+        );
+
+        // Every cell is in exactly one column, one row, and one region.
+        for kind in lines.chunks(4) {
+            let mut cells = Vec::from_iter(kind.iter().flatten().copied());
+            cells.sort();
+            assert_eq!(
+                cells,
+                Vec::from_iter(
+                    (0..4).flat_map(|col_idx| (0..4).map(move |row_idx| (col_idx, row_idx)))
+                )
+            );
+        }
+    }
+}
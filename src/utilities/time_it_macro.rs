@@ -1,5 +1,10 @@
 /// A macro to take the execution time of some code.
 ///
+/// The message can either be a format string literal with a single
+/// placeholder for the elapsed time, or any expression that evaluates
+/// to something that implements `Display`. In the latter case,
+/// the elapsed time is printed right after the message.
+///
 /// ## Usage example
 ///
 /// ```ignore
@@ -9,10 +14,45 @@
 ///         let _sum = (0..100000).fold(0_u64, |sum, n| sum + n);
 ///     }
 /// );
+///
+/// for i in 0..3 {
+///     time_it!(format!("iteration {i} takes"), {
+///         let _sum = (0..100000).fold(0_u64, |sum, n| sum + n);
+///     });
+/// }
 /// ```
 #[macro_export]
 macro_rules! time_it {
     ($print_message: literal, $($thing_to_time: tt)*) => {{
+        let (ret, time) = $crate::time_it_measured!($($thing_to_time)*);
+        println!($print_message, time);
+
+        ret
+    }};
+    ($print_message: expr, $($thing_to_time: tt)*) => {{
+        let print_message = $print_message;
+        let (ret, time) = $crate::time_it_measured!($($thing_to_time)*);
+        println!("{} {:?}", print_message, time);
+
+        ret
+    }};
+}
+
+/// A macro to take the execution time of some code, without printing it.
+/// It returns a tuple `(value, elapsed_time)`, where `value` is the
+/// value returned by the timed code, and `elapsed_time` is a
+/// `std::time::Duration`.
+///
+/// ## Usage example
+///
+/// ```ignore
+/// let (sum, time) = time_it_measured!(
+///     (0..100000).fold(0_u64, |sum, n| sum + n)
+/// );
+/// ```
+#[macro_export]
+macro_rules! time_it_measured {
+    ($($thing_to_time: tt)*) => {{
         let t0 = std::time::Instant::now();
 
         let ret = {
@@ -20,10 +60,7 @@ macro_rules! time_it {
         };
 
         let t1 = std::time::Instant::now();
-        let time = t1 - t0;
-        println!($print_message, time);
-
-        ret
+        (ret, t1 - t0)
     }};
 }
 
@@ -35,4 +72,23 @@ mod tests {
             let _sum = (0..100000).fold(0_u64, |sum, n| sum + n);
         });
     }
+
+    #[test]
+    fn time_it_runtime_message_test() {
+        for i in 0..3_u64 {
+            let sum = time_it!(format!("iteration {i} takes"), {
+                (0..100000).fold(i, |sum, n| sum + n)
+            });
+            assert_eq!(sum, i + 99999 * 100000 / 2);
+        }
+    }
+
+    #[test]
+    fn time_it_measured_test() {
+        let (sum, time) = time_it_measured!({
+            (0..100000_u64).fold(0_u64, |sum, n| std::hint::black_box(sum + n))
+        });
+        assert_eq!(sum, 99999 * 100000 / 2);
+        assert!(!time.is_zero());
+    }
 }
@@ -0,0 +1,54 @@
+use halo2_proofs::circuit::Value;
+
+/// Turns a grid of `C` columns of `R` cells each, whose value may be unknown
+/// as a whole, into a grid of the same shape, of cells whose values may be unknown.
+/// The `col_idx`-th column of the output holds the `col_idx`-th column
+/// of the input, so that no transposition of the grid itself happens.
+///
+/// If the input value is unknown, all the output values are unknown.
+pub fn transpose_value_grid<F: Copy, const C: usize, const R: usize>(
+    grid: Value<[[F; R]; C]>,
+) -> [[Value<F>; R]; C] {
+    grid.transpose_array()
+        .map(|column| column.transpose_array())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The value held by `value`, if it is known.
+    fn known<V: Copy>(value: Value<V>) -> Option<V> {
+        let mut known = None;
+        value.map(|value| known = Some(value));
+        known
+    }
+
+    #[test]
+    /// Test that a known non-square grid ends up in the same cells
+    /// as with the manual double transposition, and that an unknown grid
+    /// gives unknown cells.
+    fn transpose_value_grid_3x2() {
+        let grid: [[u64; 2]; 3] = [[1, 2], [3, 4], [5, 6]];
+
+        let transposed = transpose_value_grid(Value::known(grid));
+        let manually_transposed = Value::known(grid)
+            .transpose_array()
+            .map(|column| column.transpose_array());
+        for col_idx in 0..3 {
+            for row_idx in 0..2 {
+                assert_eq!(
+                    known(transposed[col_idx][row_idx]),
+                    Some(grid[col_idx][row_idx])
+                );
+                assert_eq!(
+                    known(transposed[col_idx][row_idx]),
+                    known(manually_transposed[col_idx][row_idx])
+                );
+            }
+        }
+
+        let unknown = transpose_value_grid(Value::<[[u64; 2]; 3]>::unknown());
+        assert!(unknown.iter().flatten().all(|cell| known(*cell).is_none()));
+    }
+}